@@ -0,0 +1,36 @@
+//! Compare the throughput of the seven-card evaluators.
+//!
+//! ```shell
+//! cargo bench --bench evaluate
+//! ```
+
+use rand::seq::SliceRandom;
+use server::{
+    card::Card,
+    deck::{remaining_cards, seeded_rng},
+    evaluate::{BitCount, Evaluator, Naive},
+};
+use std::{hint::black_box, time::Instant};
+
+const HANDS: usize = 100_000;
+
+fn bench(name: &str, evaluator: &impl Evaluator, hands: &[Vec<Card>]) {
+    let start = Instant::now();
+    for cards in hands {
+        black_box(evaluator.evaluate(black_box(cards)));
+    }
+    let elapsed = start.elapsed();
+    #[allow(clippy::cast_precision_loss)]
+    let per_second = hands.len() as f64 / elapsed.as_secs_f64();
+    println!("{name}\t{per_second:.0} hands/s");
+}
+
+fn main() {
+    let deck = remaining_cards(&[]);
+    let mut rng = seeded_rng(0);
+    let hands: Vec<Vec<Card>> = (0..HANDS)
+        .map(|_| deck.choose_multiple(&mut rng, 7).copied().collect())
+        .collect();
+    bench("naive", &Naive, &hands);
+    bench("bit_count", &BitCount, &hands);
+}
@@ -0,0 +1,75 @@
+#ifndef CASE_POKER_H
+#define CASE_POKER_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Outcome of a call into the library.
+ */
+typedef enum PokerStatus {
+  PokerStatus_Ok = 0,
+  PokerStatus_NullPointer = 1,
+  PokerStatus_InvalidUtf8 = 2,
+  PokerStatus_InvalidCard = 3,
+  PokerStatus_InvalidHand = 4,
+} PokerStatus;
+
+/**
+ * The category of a hand, mirroring [`HandCategory`].
+ */
+typedef enum PokerCategory {
+  PokerCategory_HighCard = 0,
+  PokerCategory_OnePair = 1,
+  PokerCategory_TwoPair = 2,
+  PokerCategory_ThreeOfAKind = 3,
+  PokerCategory_Straight = 4,
+  PokerCategory_Flush = 5,
+  PokerCategory_FullHouse = 6,
+  PokerCategory_FourOfAKind = 7,
+  PokerCategory_StraightFlush = 8,
+} PokerCategory;
+
+/**
+ * Five cards, each given by its index in `0..52`. See [`Card::index`].
+ */
+typedef struct PokerHand {
+  uint8_t cards[5];
+} PokerHand;
+
+/**
+ * Parse a comma-separated list of five cards, like `"tr,jr,qr,kr,1r"`, into `out`.
+ *
+ * # Safety
+ *
+ * `cards` must be null or point to a nul-terminated string, and `out` must be null or point to
+ * memory valid for writing a [`PokerHand`].
+ */
+enum PokerStatus poker_parse_hand(const char *cards, struct PokerHand *out);
+
+/**
+ * Classify `hand` and write its category into `out`.
+ *
+ * # Safety
+ *
+ * `hand` must be null or point to a valid [`PokerHand`], and `out` must be null or point to
+ * memory valid for writing a [`PokerCategory`].
+ */
+enum PokerStatus poker_evaluate(const struct PokerHand *hand, enum PokerCategory *out);
+
+/**
+ * Compare hand `a` with hand `b`, writing -1, 0 or 1 into `out` if `a` loses, ties or wins,
+ * respectively.
+ *
+ * # Safety
+ *
+ * `a` and `b` must each be null or point to a valid [`PokerHand`], and `out` must be null or
+ * point to memory valid for writing an `int32_t`.
+ */
+enum PokerStatus poker_compare(const struct PokerHand *a, const struct PokerHand *b, int32_t *out);
+
+#endif  /* CASE_POKER_H */
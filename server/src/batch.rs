@@ -0,0 +1,267 @@
+//! Classifying batches of hands from an SQS queue, as a second AWS Lambda function, for offline
+//! pipelines which want classification without HTTP.
+//!
+//! The function is triggered by the queue. The body of each message is a JSON object with the
+//! hands of one batch, each five to seven comma-separated cards, like `/analyze/:cards` takes
+//! them:
+//!
+//! ```json
+//! {"hands": ["tr,jr,qr,kr,1r", "2r,2s,5k,9h,jr,7s,8s"]}
+//! ```
+//!
+//! Each hand is evaluated with [`BitCount`], the fast evaluator, and the results of a message are
+//! written to a [`ResultSink`] as one [`BatchResult`], with the strength of the best five cards of
+//! each hand, in order:
+//!
+//! ```json
+//! {"message_id": "…", "hands": [{"hand": "tr,jr,qr,kr,1r", "category": "StraightFlush", "tie_breakers": [14]}, …]}
+//! ```
+//!
+//! [`LogSink`] writes each result as a line of JSON to standard output, which Lambda sends to
+//! CloudWatch Logs, from where a subscription filter delivers them to S3 or elsewhere. A sink
+//! which writes to S3 or DynamoDB directly implements [`ResultSink`] with the AWS SDK.
+//!
+//! A message which is not such an object, has an invalid hand, or whose result is not written, is
+//! reported as a failure of the batch, so SQS delivers it again, and finally moves it to the
+//! dead-letter queue of the queue. The other messages of the batch are done. This takes
+//! `ReportBatchItemFailures` enabled on the event source mapping.
+
+use crate::{
+    card::{Card, InvalidConversion},
+    compare::Strength,
+    deck,
+    evaluate::{BitCount, Evaluator},
+};
+use axum::async_trait;
+use lambda_http::{
+    lambda_runtime::{self, LambdaEvent},
+    service_fn, Error,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{self, Write},
+    sync::Arc,
+};
+use tracing::warn;
+
+/// The event of the messages of one batch, as SQS gives it. Only the fields used are read.
+#[derive(Deserialize, Debug)]
+pub struct SqsEvent {
+    #[serde(rename = "Records")]
+    pub records: Vec<SqsMessage>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SqsMessage {
+    pub message_id: String,
+    pub body: String,
+}
+
+/// The messages which failed, for SQS to deliver again.
+#[derive(Serialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SqsBatchResponse {
+    pub batch_item_failures: Vec<BatchItemFailure>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemFailure {
+    /// The ID of the message.
+    pub item_identifier: String,
+}
+
+/// The body of a message.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct Batch {
+    hands: Vec<String>,
+}
+
+/// The classified hands of one message.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    pub message_id: String,
+    pub hands: Vec<HandResult>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct HandResult {
+    /// The cards as they were given.
+    pub hand: String,
+    #[serde(flatten)]
+    pub strength: Strength,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum BatchError {
+    #[error("message body is invalid: {0}")]
+    Body(#[from] serde_json::Error),
+    #[error("hand {index} is invalid: card is invalid: {error}")]
+    Card {
+        index: usize,
+        error: InvalidConversion,
+    },
+    #[error("hand {index} is invalid: number of cards ({given}) must be from 5 to 7")]
+    Cards { index: usize, given: usize },
+    #[error("hand {index} is invalid: {card} is given more than once")]
+    DuplicateCard { index: usize, card: Card },
+}
+
+/// Where the results of the batches go.
+#[async_trait]
+pub trait ResultSink: Send + Sync {
+    async fn write(&self, result: &BatchResult) -> Result<(), Error>;
+}
+
+/// Writes each result as a line of JSON to standard output.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LogSink;
+
+#[async_trait]
+impl ResultSink for LogSink {
+    async fn write(&self, result: &BatchResult) -> Result<(), Error> {
+        let line = serde_json::to_string(result)?;
+        writeln!(io::stdout().lock(), "{line}")?;
+        Ok(())
+    }
+}
+
+/// Classify the hands of the body of a message.
+fn classify_batch(body: &str) -> Result<Vec<HandResult>, BatchError> {
+    let batch: Batch = serde_json::from_str(body)?;
+    batch
+        .hands
+        .into_iter()
+        .enumerate()
+        .map(|(index, hand)| {
+            let cards = hand
+                .split(',')
+                .map(str::parse)
+                .collect::<Result<Vec<Card>, _>>()
+                .map_err(|error| BatchError::Card { index, error })?;
+            if !(5..=7).contains(&cards.len()) {
+                return Err(BatchError::Cards {
+                    index,
+                    given: cards.len(),
+                });
+            }
+            if let Some(card) = cards
+                .iter()
+                .enumerate()
+                .find_map(|(i, card)| cards[..i].contains(card).then_some(*card))
+            {
+                return Err(BatchError::DuplicateCard { index, card });
+            }
+            Ok(HandResult {
+                strength: BitCount.evaluate(&cards),
+                hand,
+            })
+        })
+        .collect()
+}
+
+/// Classify the messages of the `event`, and write their results to the `sink`, giving the
+/// messages which failed.
+pub async fn handle(event: SqsEvent, sink: &dyn ResultSink) -> SqsBatchResponse {
+    let mut response = SqsBatchResponse::default();
+    for message in event.records {
+        let written = match classify_batch(&message.body) {
+            Ok(hands) => {
+                let result = BatchResult {
+                    message_id: message.message_id.clone(),
+                    hands,
+                };
+                sink.write(&result).await
+            }
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = written {
+            warn!(message_id = message.message_id, %e, "message failed");
+            response.batch_item_failures.push(BatchItemFailure {
+                item_identifier: message.message_id,
+            });
+        }
+    }
+    response
+}
+
+/// Serve the SQS events of the Lambda runtime, writing the results to the `sink`, until the
+/// runtime stops.
+pub async fn run(sink: impl ResultSink + 'static) -> Result<(), Error> {
+    deck::initialize();
+    let sink = Arc::new(sink);
+    lambda_runtime::run(service_fn(move |event: LambdaEvent<SqsEvent>| {
+        let sink = sink.clone();
+        async move { Ok::<_, Error>(handle(event.payload, &*sink).await) }
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand::HandCategory;
+    use std::sync::Mutex;
+
+    /// Keeps the results written.
+    #[derive(Default)]
+    struct Collected(Mutex<Vec<BatchResult>>);
+
+    #[async_trait]
+    impl ResultSink for Collected {
+        async fn write(&self, result: &BatchResult) -> Result<(), Error> {
+            self.0.lock().unwrap().push(result.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn sqs_event_is_classified() {
+        let event = serde_json::from_str(include_str!("../fixtures/lambda/sqs.json")).unwrap();
+        let sink = Collected::default();
+        let response = handle(event, &sink).await;
+
+        let results = sink.0.into_inner().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].message_id, "batch-1");
+        let categories: Vec<_> = results[0]
+            .hands
+            .iter()
+            .map(|hand| hand.strength.category)
+            .collect();
+        assert_eq!(
+            categories,
+            [HandCategory::StraightFlush, HandCategory::Straight]
+        );
+        // The invalid card, the duplicate card and the malformed body are delivered again.
+        let failed: Vec<_> = response
+            .batch_item_failures
+            .iter()
+            .map(|failure| failure.item_identifier.as_str())
+            .collect();
+        assert_eq!(failed, ["batch-2", "batch-3", "batch-4"]);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["batchItemFailures"][0]["itemIdentifier"],
+            "batch-2"
+        );
+    }
+
+    #[test]
+    fn results_are_flat_json() {
+        let hands = classify_batch(r#"{"hands": ["tr,jr,qr,kr,1r"]}"#).unwrap();
+        assert_eq!(
+            serde_json::to_value(&hands[0]).unwrap(),
+            serde_json::json!({
+                "hand": "tr,jr,qr,kr,1r",
+                "category": "StraightFlush",
+                "tie_breakers": [14]
+            })
+        );
+        assert!(matches!(
+            classify_batch(r#"{"hands": ["tr,jr,qr,kr"]}"#),
+            Err(BatchError::Cards { index: 0, given: 4 })
+        ));
+    }
+}
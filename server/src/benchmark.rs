@@ -0,0 +1,140 @@
+//! Benchmark the evaluators of [`crate::evaluate`] against each other on the same hands.
+//!
+//! Which evaluator is fastest depends on the hardware, so [`benchmark`] times every evaluator in
+//! [`EVALUATORS`] on a workload of random hands drawn from a seed, and reports their throughput. It
+//! also reports how often each evaluator agrees with the first, [`Naive`], which is obviously
+//! right, so a faster evaluator is never picked at the cost of wrong results.
+
+use crate::{
+    card::Card,
+    compare::Strength,
+    deck::{remaining_cards, seeded_rng},
+    evaluate::{BitCount, Evaluator, Naive},
+};
+use rand::seq::SliceRandom;
+use serde::Serialize;
+use std::time::Instant;
+
+/// The evaluators of this crate by name, with the reference which the others are checked against
+/// first.
+pub const EVALUATORS: [(&str, &dyn Evaluator); 2] = [("naive", &Naive), ("bit_count", &BitCount)];
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum BenchmarkError {
+    #[error("number of cards per hand ({0}) must be from 5 to 7")]
+    Cards(usize),
+}
+
+/// The result of benchmarking the evaluators on one workload.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Benchmark {
+    /// The number of hands in the workload.
+    pub hands: usize,
+    /// The number of cards in each hand.
+    pub cards: usize,
+    /// The seed the hands were drawn with.
+    pub seed: u64,
+    pub evaluators: Vec<EvaluatorBenchmark>,
+}
+
+/// How one evaluator did on the workload.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EvaluatorBenchmark {
+    pub name: &'static str,
+    /// Time taken to evaluate every hand.
+    pub seconds: f64,
+    pub hands_per_second: f64,
+    /// The share of hands, from 0 to 1, where the evaluator gives the same [`Strength`] as the
+    /// reference evaluator, which is 1 for the reference itself.
+    pub agreement: f64,
+}
+
+/// Time each of the `evaluators` on `hands` random hands of `cards` cards drawn with `seed`, and
+/// compare their results with the first evaluator's.
+pub fn benchmark(
+    evaluators: &[(&'static str, &dyn Evaluator)],
+    hands: usize,
+    cards: usize,
+    seed: u64,
+) -> Result<Benchmark, BenchmarkError> {
+    if !(5..=7).contains(&cards) {
+        return Err(BenchmarkError::Cards(cards));
+    }
+    let deck = remaining_cards(&[]);
+    let mut rng = seeded_rng(seed);
+    let workload: Vec<Vec<Card>> = (0..hands)
+        .map(|_| deck.choose_multiple(&mut rng, cards).copied().collect())
+        .collect();
+
+    let mut reference: Option<Vec<Strength>> = None;
+    let evaluators = evaluators
+        .iter()
+        .map(|&(name, evaluator)| {
+            let start = Instant::now();
+            let strengths: Vec<Strength> = workload
+                .iter()
+                .map(|hand| evaluator.evaluate(hand))
+                .collect();
+            let seconds = start.elapsed().as_secs_f64();
+            let reference = reference.get_or_insert_with(|| strengths.clone());
+            let agreeing = strengths
+                .iter()
+                .zip(reference.iter())
+                .filter(|(a, b)| a == b)
+                .count();
+            #[allow(clippy::cast_precision_loss)]
+            let (hands, agreeing) = (hands as f64, agreeing as f64);
+            EvaluatorBenchmark {
+                name,
+                seconds,
+                hands_per_second: hands / seconds.max(f64::MIN_POSITIVE),
+                agreement: if hands == 0.0 { 1.0 } else { agreeing / hands },
+            }
+        })
+        .collect();
+    Ok(Benchmark {
+        hands,
+        cards,
+        seed,
+        evaluators,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand::HandCategory;
+
+    #[test]
+    fn evaluators_agree_on_every_hand() {
+        let benchmark = benchmark(&EVALUATORS, 2000, 7, 196).unwrap();
+        assert_eq!(benchmark.evaluators.len(), 2);
+        for evaluator in &benchmark.evaluators {
+            assert_eq!(evaluator.agreement, 1.0, "{}", evaluator.name);
+            assert!(evaluator.hands_per_second > 0.0);
+        }
+    }
+
+    #[test]
+    fn disagreement_is_reported() {
+        struct AlwaysHighCard;
+
+        impl Evaluator for AlwaysHighCard {
+            fn evaluate(&self, _: &[Card]) -> Strength {
+                Strength {
+                    category: HandCategory::HighCard,
+                    tie_breakers: vec![],
+                }
+            }
+        }
+
+        let evaluators: [(&str, &dyn Evaluator); 2] =
+            [("bit_count", &BitCount), ("wrong", &AlwaysHighCard)];
+        let benchmark = benchmark(&evaluators, 500, 5, 196).unwrap();
+        assert!(benchmark.evaluators[1].agreement < 1.0);
+        assert_eq!(
+            super::benchmark(&EVALUATORS, 1, 4, 0),
+            Err(BenchmarkError::Cards(4))
+        );
+    }
+}
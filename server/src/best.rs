@@ -0,0 +1,62 @@
+//! Find the best five-card [`Hand`] among more cards, like the seven cards of Texas hold'em.
+//!
+//! Every combination of five cards is tried, so this is meant for a handful of cards.
+
+use crate::{
+    card::Card,
+    classify::classify,
+    compare::strength,
+    hand::{Hand, HandCategory},
+};
+use itertools::Itertools;
+
+/// The five cards which make the strongest [`Hand`], or `None` if there are fewer than five unique
+/// cards.
+pub fn best_hand(cards: &[Card]) -> Option<Hand> {
+    hands(cards).max_by_key(strength)
+}
+
+/// The highest [`HandCategory`] of any five of the cards, or `None` if there are fewer than five
+/// unique cards.
+///
+/// This is the category of [`best_hand`], found without comparing hands within a category.
+pub fn best_category(cards: &[Card]) -> Option<HandCategory> {
+    hands(cards).map(|hand| classify(&hand)).max()
+}
+
+/// All hands of five of the cards.
+fn hands(cards: &[Card]) -> impl Iterator<Item = Hand> + '_ {
+    cards
+        .iter()
+        .copied()
+        .combinations(5)
+        .filter_map(|five| Hand::try_from(five.as_slice()).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn finds_flush_among_seven() {
+        let cards = cards("1s,ks,2s,7s,9s,1h,1r");
+        assert_eq!(best_category(&cards), Some(HandCategory::Flush));
+        assert_eq!(best_hand(&cards), Some("1s,ks,2s,7s,9s".parse().unwrap()));
+    }
+
+    #[test]
+    fn picks_highest_kickers() {
+        let cards = cards("1s,1h,kr,qk,2s,3h,jk");
+        assert_eq!(best_hand(&cards), Some("1s,1h,kr,qk,jk".parse().unwrap()));
+    }
+
+    #[test]
+    fn too_few_cards() {
+        assert_eq!(best_hand(&cards("1s,1h,kr,qk")), None);
+        assert_eq!(best_category(&cards("1s,1h,kr,qk")), None);
+    }
+}
@@ -1,17 +1,21 @@
-//! An AWS lambda function runtime
+//! An AWS lambda function runtime. See [`server::lambda`] for the events it serves.
 
+use server::config::{Config, LogFormat};
 use tracing::info;
 
 #[tokio::main(flavor = "current_thread")]
 async fn main() -> Result<(), lambda_http::Error> {
-    tracing_subscriber::fmt()
+    let config = Config::from_env()?;
+    let subscriber = tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .with_target(false)
-        .without_time()
-        .init();
+        .without_time();
+    match config.log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
 
     info!("starting");
 
-    let router = server::router::create();
-    lambda_http::run(router).await
+    server::lambda::run(&config.router).await
 }
@@ -0,0 +1,25 @@
+//! An AWS lambda function classifying batches of hands from SQS. See [`server::batch`] for the
+//! messages it takes.
+
+use server::{
+    batch::LogSink,
+    config::{Config, LogFormat},
+};
+use tracing::info;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), lambda_http::Error> {
+    let config = Config::from_env()?;
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_target(false)
+        .without_time();
+    match config.log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+
+    info!("starting");
+
+    server::batch::run(LogSink).await
+}
@@ -0,0 +1,121 @@
+//! Command line interface to the poker library, for use without running the server.
+
+use clap::{Parser, Subcommand};
+use rand::Rng;
+use server::{
+    card::{Card, InvalidConversion},
+    classify::classify,
+    compare::compare,
+    deck::seeded_rng,
+    equity::equity,
+    hand::Hand,
+    shuffle,
+    simulate::category_frequencies,
+};
+use std::{cmp::Ordering, process::ExitCode, str::FromStr};
+
+#[derive(Parser)]
+#[command(about = "Draw, classify and compare five-card poker hands")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Cards are given as comma-separated lists of rank and suit, e.g., `tr,jr,qr,kr,1r`.
+#[derive(Subcommand)]
+enum Command {
+    /// Print the category of a hand.
+    Analyze { cards: Hand },
+    /// Draw random hands and print them with their categories.
+    Draw {
+        #[arg(long, default_value_t = 1)]
+        count: u64,
+        /// Seed for reproducible draws.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Print which of two hands wins: `a`, `b` or `tie`.
+    Compare { a: Hand, b: Hand },
+    /// Estimate the equity of two partial hands of up to five cards each.
+    Equity {
+        a: Cards,
+        b: Cards,
+        #[arg(long, default_value_t = 100_000)]
+        iterations: u64,
+        /// Seed for reproducible simulations.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Draw many hands and print how often each category occurs.
+    Simulate {
+        #[arg(long, default_value_t = 100_000)]
+        iterations: u64,
+        /// Seed for reproducible simulations.
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+/// A comma-separated list of any number of cards. The empty string is an empty list.
+#[derive(Clone)]
+struct Cards(Vec<Card>);
+
+impl FromStr for Cards {
+    type Err = InvalidConversion;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Self(Vec::new()));
+        }
+        s.split(',')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut rng = match cli.command {
+        Command::Draw { seed, .. }
+        | Command::Equity { seed, .. }
+        | Command::Simulate { seed, .. } => seed,
+        _ => None,
+    }
+    .map_or_else(|| seeded_rng(rand::thread_rng().gen()), seeded_rng);
+
+    match cli.command {
+        Command::Analyze { cards } => println!("{:?}", classify(&cards)),
+        Command::Draw { count, seed } => {
+            // The same hands as `/replay/:seed?count=`, drawn by the specified shuffle.
+            let seed = seed.unwrap_or_else(|| rand::thread_rng().gen());
+            for k in 0..count {
+                let hand = shuffle::draw_nth(seed, k);
+                println!("{hand}\t{:?}", classify(&hand));
+            }
+        }
+        Command::Compare { a, b } => match compare(&a, &b) {
+            Ordering::Greater => println!("a"),
+            Ordering::Less => println!("b"),
+            Ordering::Equal => println!("tie"),
+        },
+        Command::Equity {
+            a, b, iterations, ..
+        } => match equity(&a.0, &b.0, iterations, &mut rng) {
+            Ok(equity) => println!(
+                "win\t{:.4}\ntie\t{:.4}\nlose\t{:.4}",
+                equity.win, equity.tie, equity.lose
+            ),
+            Err(e) => {
+                eprintln!("error: {e}");
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::Simulate { iterations, .. } => {
+            for (category, count) in category_frequencies(iterations, &mut rng) {
+                println!("{category:?}\t{count}");
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
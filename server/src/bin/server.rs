@@ -1,17 +1,381 @@
 //! Local server
 
-use server::router::create;
-use tracing::info;
+use axum::Router;
+use futures_util::{stream, Stream, StreamExt};
+use server::{
+    config::{Config, HttpConfig, Listen, LogFormat, TlsConfig},
+    router::create_with,
+};
+use std::{
+    io,
+    net::TcpListener,
+    pin::Pin,
+    process,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+use tokio_stream::wrappers::TcpListenerStream;
+use tracing::{error, info};
 
-/// Start a web server at port 8080.
+#[cfg(unix)]
+use std::os::unix::net::UnixListener;
+
+/// Start a web server, configured by environment variables. See [`server::config`].
 #[tokio::main]
 async fn main() {
-    tracing_subscriber::fmt::init();
-    let addr = "0.0.0.0:8080".parse().unwrap();
-    info!(?addr, "starting server");
-    let router = create();
-    axum::Server::bind(&addr)
+    let config = match Config::from_env() {
+        Ok(config) => config,
+        Err(e) => {
+            // Logging is configured too, so it cannot be set up yet.
+            eprintln!("invalid configuration: {e}");
+            process::exit(1);
+        }
+    };
+    let subscriber = tracing_subscriber::fmt();
+    match config.log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+    let listener = match open_listener(&config.listen) {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!(%e, listen = ?config.listen, "failed to listen");
+            process::exit(1);
+        }
+    };
+    let router = create_with(&config.router);
+    let limit = ConnectionLimit::new(config.http.max_connections);
+    match (listener, config.tls) {
+        (Listener::Tcp(listener), None) => {
+            info!(addr = ?listener.local_addr(), "starting server");
+            listener.set_nonblocking(true).unwrap();
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            serve(
+                retry_accept_errors(TcpListenerStream::new(listener)),
+                limit,
+                &config.http,
+                router,
+            )
+            .await;
+        }
+        (Listener::Tcp(listener), Some(tls)) => {
+            serve_tls(listener, tls, limit, &config.http, router).await;
+        }
+        #[cfg(unix)]
+        (Listener::Unix(listener), None) => {
+            use tokio_stream::wrappers::UnixListenerStream;
+
+            info!(addr = ?listener.local_addr(), "starting server");
+            listener.set_nonblocking(true).unwrap();
+            let listener = tokio::net::UnixListener::from_std(listener).unwrap();
+            serve(
+                retry_accept_errors(UnixListenerStream::new(listener)),
+                limit,
+                &config.http,
+                router,
+            )
+            .await;
+        }
+        #[cfg(unix)]
+        (Listener::Unix(_), Some(_)) => {
+            error!("TLS is not supported on a Unix socket");
+            process::exit(1);
+        }
+    }
+}
+
+enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+fn open_listener(listen: &Listen) -> io::Result<Listener> {
+    match listen {
+        Listen::Tcp(addr) => Ok(Listener::Tcp(TcpListener::bind(addr)?)),
+        #[cfg(unix)]
+        Listen::Unix(path) => {
+            use std::os::unix::fs::FileTypeExt;
+
+            // A socket file left behind by an earlier run would make binding fail.
+            if std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+                std::fs::remove_file(path)?;
+            }
+            Ok(Listener::Unix(UnixListener::bind(path)?))
+        }
+        #[cfg(unix)]
+        Listen::Systemd => Ok(inherit_listener()),
+        #[cfg(not(unix))]
+        Listen::Unix(_) | Listen::Systemd => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Unix sockets are only supported on Unix",
+        )),
+    }
+}
+
+/// Take over the first socket passed by systemd socket activation, which is always file descriptor
+/// 3. See `sd_listen_fds(3)`.
+#[cfg(unix)]
+fn inherit_listener() -> Listener {
+    use std::os::fd::{FromRawFd, IntoRawFd, RawFd};
+
+    const SD_LISTEN_FDS_START: RawFd = 3;
+
+    // SAFETY: The configuration checked that systemd passed this process a socket, and nothing else
+    // in the process owns its file descriptor.
+    let tcp = unsafe { TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+    // Only a socket of the IP families has a TCP address.
+    if tcp.local_addr().is_ok() {
+        Listener::Tcp(tcp)
+    } else {
+        // SAFETY: We just took the file descriptor back from the TCP listener.
+        Listener::Unix(unsafe { UnixListener::from_raw_fd(tcp.into_raw_fd()) })
+    }
+}
+
+/// How long to wait after an accept error which is not about a single connection, like running out
+/// of file descriptors, before accepting again.
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The connections accepted by `listener`, without its accept errors, which are logged instead.
+/// Hyper stops serving on the first accept error, but they are mostly transient, like a client
+/// aborting a connection or the process running out of file descriptors under load. Like hyper's
+/// own TCP listener, errors about a single connection are skipped at once, and others after
+/// [`ACCEPT_ERROR_BACKOFF`].
+fn retry_accept_errors<S: Send + 'static>(
+    listener: impl Stream<Item = io::Result<S>> + Unpin + Send + 'static,
+) -> Pin<Box<dyn Stream<Item = io::Result<S>> + Send>> {
+    Box::pin(stream::unfold(listener, |mut listener| async move {
+        loop {
+            match listener.next().await? {
+                Ok(stream) => return Some((Ok(stream), listener)),
+                Err(e) if is_connection_error(&e) => {
+                    info!(%e, "connection failed while being accepted");
+                }
+                Err(e) => {
+                    error!(%e, backoff = ?ACCEPT_ERROR_BACKOFF, "failed to accept connection");
+                    tokio::time::sleep(ACCEPT_ERROR_BACKOFF).await;
+                }
+            }
+        }
+    }))
+}
+
+/// Whether the accept error is about a single connection, and not about the listener.
+fn is_connection_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
+}
+
+/// Serve plain HTTP on the connections accepted by `listener`, which should not yield accept
+/// errors, see [`retry_accept_errors`].
+async fn serve<S>(
+    listener: impl Stream<Item = io::Result<S>> + Unpin + Send + 'static,
+    limit: ConnectionLimit,
+    http: &HttpConfig,
+    router: Router,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    // Wait for a free connection slot before accepting the next connection.
+    let incoming = stream::unfold((listener, limit), |(mut listener, limit)| async move {
+        let permit = limit.acquire().await;
+        let accepted = listener.next().await?;
+        let connection = accepted.map(|stream| Connection {
+            stream,
+            _permit: permit,
+        });
+        Some((connection, (listener, limit)))
+    });
+    axum::Server::builder(hyper::server::accept::from_stream(incoming))
+        .http1_only(!http.http2)
+        .http1_keepalive(http.http1_keep_alive)
+        .http2_keep_alive_interval(http.http2_keep_alive_interval)
+        .http2_keep_alive_timeout(http.http2_keep_alive_timeout)
+        .http2_max_concurrent_streams(http.http2_max_concurrent_streams)
         .serve(router.into_make_service())
         .await
-        .unwrap();
+        .unwrap_or_else(|e| {
+            error!(%e, "server failed");
+            process::exit(1);
+        });
+}
+
+/// Serve HTTPS, reloading the certificate and key from disk whenever the process gets `SIGHUP`.
+#[cfg(feature = "tls")]
+async fn serve_tls(
+    listener: TcpListener,
+    tls: TlsConfig,
+    limit: ConnectionLimit,
+    http: &HttpConfig,
+    router: Router,
+) {
+    use axum_server::tls_rustls::{RustlsAcceptor, RustlsConfig};
+
+    let rustls = match RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await {
+        Ok(rustls) => rustls,
+        Err(e) => {
+            error!(%e, ?tls, "failed to load TLS certificate");
+            process::exit(1);
+        }
+    };
+    #[cfg(unix)]
+    tokio::spawn(reload_on_hangup(rustls.clone(), tls));
+    info!(addr = ?listener.local_addr(), "starting server with TLS");
+    axum_server::from_tcp(listener)
+        .acceptor(RustlsAcceptor::new(rustls).acceptor(limit))
+        .http_config(
+            axum_server::HttpConfig::new()
+                .http1_only(!http.http2)
+                .http1_keep_alive(http.http1_keep_alive)
+                .http2_keep_alive_interval(http.http2_keep_alive_interval)
+                .http2_keep_alive_timeout(http.http2_keep_alive_timeout)
+                .http2_max_concurrent_streams(http.http2_max_concurrent_streams)
+                .build(),
+        )
+        .serve(router.into_make_service())
+        .await
+        .unwrap_or_else(|e| {
+            error!(%e, "server failed");
+            process::exit(1);
+        });
+}
+
+#[cfg(all(feature = "tls", unix))]
+async fn reload_on_hangup(rustls: axum_server::tls_rustls::RustlsConfig, tls: TlsConfig) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = signal(SignalKind::hangup()).expect("we can listen for SIGHUP");
+    while hangups.recv().await.is_some() {
+        match rustls
+            .reload_from_pem_file(&tls.cert_path, &tls.key_path)
+            .await
+        {
+            Ok(()) => info!("reloaded TLS certificate"),
+            Err(e) => error!(%e, "failed to reload TLS certificate, keeping the old one"),
+        }
+    }
+}
+
+#[cfg(not(feature = "tls"))]
+async fn serve_tls(_: TcpListener, _: TlsConfig, _: ConnectionLimit, _: &HttpConfig, _: Router) {
+    error!("TLS is configured, but the server was built without the `tls` feature");
+    process::exit(1);
+}
+
+/// Limits how many connections are served at once. Unlimited if constructed with `None`.
+#[derive(Clone)]
+struct ConnectionLimit(Option<Arc<Semaphore>>);
+
+impl ConnectionLimit {
+    fn new(max_connections: Option<usize>) -> Self {
+        Self(max_connections.map(|max| Arc::new(Semaphore::new(max))))
+    }
+
+    /// Wait until another connection may be served.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.0.clone()?;
+        Some(
+            semaphore
+                .acquire_owned()
+                .await
+                .expect("the semaphore is never closed"),
+        )
+    }
+}
+
+#[cfg(feature = "tls")]
+impl<I, S> axum_server::accept::Accept<I, S> for ConnectionLimit
+where
+    I: Send + 'static,
+    S: Send + 'static,
+{
+    type Stream = Connection<I>;
+    type Service = S;
+    type Future =
+        futures_util::future::BoxFuture<'static, io::Result<(Self::Stream, Self::Service)>>;
+
+    fn accept(&self, stream: I, service: S) -> Self::Future {
+        let limit = self.clone();
+        Box::pin(async move {
+            let permit = limit.acquire().await;
+            Ok((
+                Connection {
+                    stream,
+                    _permit: permit,
+                },
+                service,
+            ))
+        })
+    }
+}
+
+/// A connection which counts against its [`ConnectionLimit`] until it is dropped.
+struct Connection<S> {
+    stream: S,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Connection<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Connection<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.stream.is_write_vectored()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn accept_errors_do_not_end_the_listener() {
+        let accepted = stream::iter([Ok(1), Err(io::ErrorKind::ConnectionAborted.into()), Ok(2)]);
+        let accepted: Vec<_> = retry_accept_errors(accepted)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(accepted, [1, 2]);
+    }
 }
@@ -0,0 +1,97 @@
+//! A least-recently-used cache of results computed from a [`Hand`].
+//!
+//! Entries are keyed by the [canonical](Hand::canonical) form of the hand, so hands which only
+//! differ by a relabeling of the suits share an entry. Only cache results which are the same for
+//! all such hands, like the [`HandCategory`](crate::hand::HandCategory).
+
+use crate::hand::Hand;
+use lru::LruCache;
+use std::{num::NonZeroUsize, sync::Mutex};
+
+#[derive(Debug)]
+pub struct HandCache<V> {
+    /// `None` if the capacity is 0, which disables the cache.
+    entries: Option<Mutex<LruCache<Hand, V>>>,
+}
+
+impl<V: Clone> HandCache<V> {
+    /// Create a cache of at most `capacity` entries. A capacity of 0 caches nothing.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: NonZeroUsize::new(capacity)
+                .map(|capacity| Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    /// Get the cached value for `hand`, or compute and cache it with `compute`. The returned flag
+    /// tells whether the value was cached.
+    pub fn get_or_insert_with(&self, hand: &Hand, compute: impl FnOnce() -> V) -> (V, bool) {
+        let Some(entries) = &self.entries else {
+            return (compute(), false);
+        };
+        let key = hand.canonical();
+        if let Some(value) = entries.lock().expect("the cache never panics").get(&key) {
+            return (value.clone(), true);
+        }
+        // Compute without holding the lock, so other requests are not held up.
+        let value = compute();
+        entries
+            .lock()
+            .expect("the cache never panics")
+            .put(key, value.clone());
+        (value, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(cards: &str) -> Hand {
+        cards.parse().unwrap()
+    }
+
+    #[test]
+    fn relabeled_hands_hit() {
+        let cache = HandCache::new(10);
+        assert_eq!(
+            cache.get_or_insert_with(&hand("2r,2s,5k,9h,jr"), || 1),
+            (1, false)
+        );
+        assert_eq!(
+            cache.get_or_insert_with(&hand("2h,2k,5s,9r,jh"), || 2),
+            (1, true)
+        );
+        assert_eq!(
+            cache.get_or_insert_with(&hand("3h,2k,5s,9r,jh"), || 3),
+            (3, false)
+        );
+    }
+
+    #[test]
+    fn least_recently_used_is_evicted() {
+        let cache = HandCache::new(2);
+        cache.get_or_insert_with(&hand("2r,2s,5k,9h,jr"), || 1);
+        cache.get_or_insert_with(&hand("3r,3s,5k,9h,jr"), || 2);
+        cache.get_or_insert_with(&hand("2r,2s,5k,9h,jr"), || 1);
+        cache.get_or_insert_with(&hand("4r,4s,5k,9h,jr"), || 3);
+        assert_eq!(
+            cache.get_or_insert_with(&hand("2r,2s,5k,9h,jr"), || 0),
+            (1, true)
+        );
+        assert_eq!(
+            cache.get_or_insert_with(&hand("3r,3s,5k,9h,jr"), || 0),
+            (0, false)
+        );
+    }
+
+    #[test]
+    fn zero_capacity_caches_nothing() {
+        let cache = HandCache::new(0);
+        cache.get_or_insert_with(&hand("2r,2s,5k,9h,jr"), || 1);
+        assert_eq!(
+            cache.get_or_insert_with(&hand("2r,2s,5k,9h,jr"), || 2),
+            (2, false)
+        );
+    }
+}
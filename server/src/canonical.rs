@@ -0,0 +1,122 @@
+//! Canonical JSON of hands, cards and classifications, for hashing.
+//!
+//! Hashes of the same value only match if it is serialized to the same bytes, which JSON does not
+//! promise: keys may come in any order, whitespace is free, and a float may be written in more
+//! than one way. The canonical form is pinned down:
+//!
+//! * Objects have their keys sorted by their UTF-8 bytes.
+//! * There is no whitespace.
+//! * Numbers are integers. Floats are rejected, see [`CanonicalError::Float`].
+//! * Strings are escaped like [`serde_json`] escapes them.
+//!
+//! Cards keep the order they are serialized in, since it may matter, like the order of a deal.
+//! Cards whose order does not matter, like a set of hole cards, are sorted first with [`cards`].
+//! A [`Hand`](crate::hand::Hand) keeps its cards sorted, so it is canonical as it is.
+
+use crate::card::Card;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum CanonicalError {
+    #[error("the value at {0} is a float, which has no canonical form")]
+    Float(String),
+    #[error("the value could not be serialized: {0}")]
+    Serialize(String),
+}
+
+/// Serialize the value to canonical JSON.
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, CanonicalError> {
+    let value =
+        serde_json::to_value(value).map_err(|e| CanonicalError::Serialize(e.to_string()))?;
+    let mut out = String::new();
+    write(&value, "$", &mut out)?;
+    Ok(out)
+}
+
+/// Serialize the cards to canonical JSON, sorted like [`Card`]'s [`Ord`], for cards whose order
+/// does not matter.
+pub fn cards(cards: &[Card]) -> Result<String, CanonicalError> {
+    let mut cards = cards.to_vec();
+    cards.sort_unstable();
+    to_string(&cards)
+}
+
+/// Write the value to `out`, where `path` is where it is in the whole value, like `$.hand[0]`.
+fn write(value: &Value, path: &str, out: &mut String) -> Result<(), CanonicalError> {
+    match value {
+        Value::Number(number) if number.is_f64() => {
+            return Err(CanonicalError::Float(path.to_owned()));
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write(value, &format!("{path}[{i}]"), out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_unstable_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push(':');
+                write(value, &format!("{path}.{key}"), out)?;
+            }
+            out.push('}');
+        }
+        _ => out.push_str(&value.to_string()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{compare::strength, hand::Hand, percentile::percentile};
+
+    fn parse(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn keys_are_sorted_without_whitespace() {
+        let hand: Hand = "kr,kh,3k,3r,3h".parse().unwrap();
+        assert_eq!(
+            to_string(&strength(&hand)).unwrap(),
+            r#"{"category":"FullHouse","tie_breakers":[3,13]}"#
+        );
+        let value = serde_json::json!({"b": {"z": 1, "a": [true, null]}, "a": "\"q\""});
+        assert_eq!(
+            to_string(&value).unwrap(),
+            r#"{"a":"\"q\"","b":{"a":[true,null],"z":1}}"#
+        );
+    }
+
+    #[test]
+    fn cards_are_sorted_in_any_order() {
+        assert_eq!(
+            cards(&parse("ks,1h,5r")).unwrap(),
+            cards(&parse("5r,ks,1h")).unwrap()
+        );
+        let hand: Hand = "ks,1h,5r,2k,9s".parse().unwrap();
+        let shuffled: Hand = "9s,2k,5r,1h,ks".parse().unwrap();
+        assert_eq!(to_string(&hand).unwrap(), to_string(&shuffled).unwrap());
+    }
+
+    #[test]
+    fn floats_are_rejected() {
+        let hand: Hand = "ks,1h,5r,2k,9s".parse().unwrap();
+        assert_eq!(
+            to_string(&percentile(&hand)),
+            Err(CanonicalError::Float("$.beats".to_owned()))
+        );
+    }
+}
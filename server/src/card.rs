@@ -1,9 +1,10 @@
 //! Here we model a playing [`Card`] with a [`Rank`] and a [`Suit`].
 
-use serde::Serialize;
-use std::str::FromStr;
+use core::{fmt, str::FromStr};
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, Ord, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Suit {
     Clubs,
     Diamonds,
@@ -11,7 +12,8 @@ pub enum Suit {
     Spades,
 }
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, Ord, PartialOrd, Serialize)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, Ord, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Rank {
     Ace,
     Two,
@@ -28,7 +30,29 @@ pub enum Rank {
     King,
 }
 
+impl Suit {
+    /// All four suits, in the order used for card indices.
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+}
+
 impl Rank {
+    /// All thirteen ranks, from Ace to King.
+    pub const ALL: [Rank; 13] = [
+        Rank::Ace,
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+    ];
+
     pub fn numeric(self) -> u8 {
         match self {
             Rank::Ace => 1,
@@ -46,9 +70,19 @@ impl Rank {
             Rank::King => 13,
         }
     }
+
+    /// Like [`Rank::numeric`], but with the Ace counted as 14, which is how ranks are compared
+    /// when breaking ties.
+    pub fn numeric_ace_high(self) -> u8 {
+        match self {
+            Rank::Ace => 14,
+            _ => self.numeric(),
+        }
+    }
 }
 
-#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, Serialize)]
+#[derive(Copy, Clone, Hash, Eq, PartialEq, Debug, Ord, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
@@ -58,6 +92,18 @@ impl Card {
     pub fn new(rank: Rank, suit: Suit) -> Self {
         Self { rank, suit }
     }
+
+    /// A unique index in `0..52` for the card, ordered by suit and then by rank.
+    pub fn index(self) -> u8 {
+        self.suit as u8 * 13 + self.rank as u8
+    }
+
+    /// The inverse of [`Card::index`]. Returns `None` if `index` is not in `0..52`.
+    pub fn from_index(index: u8) -> Option<Self> {
+        let suit = *Suit::ALL.get(usize::from(index / 13))?;
+        let rank = Rank::ALL[usize::from(index % 13)];
+        Some(Self::new(rank, suit))
+    }
 }
 
 impl TryFrom<char> for Suit {
@@ -103,6 +149,39 @@ impl TryFrom<char> for Rank {
     }
 }
 
+impl From<Suit> for char {
+    /// The inverse of [`Suit::try_from`].
+    fn from(suit: Suit) -> Self {
+        match suit {
+            Suit::Diamonds => 'r',
+            Suit::Spades => 's',
+            Suit::Clubs => 'k',
+            Suit::Hearts => 'h',
+        }
+    }
+}
+
+impl From<Rank> for char {
+    /// The inverse of [`Rank::try_from`].
+    fn from(rank: Rank) -> Self {
+        match rank {
+            Rank::Ace => '1',
+            Rank::Ten => 't',
+            Rank::Jack => 'j',
+            Rank::Queen => 'q',
+            Rank::King => 'k',
+            _ => char::from(b'0' + rank.numeric()),
+        }
+    }
+}
+
+impl fmt::Display for Card {
+    /// Formats the card in the same two-character notation that [`Card::from_str`] parses.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", char::from(self.rank), char::from(self.suit))
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum InvalidConversion {
     #[error("length of str ({0}) must be 2")]
@@ -160,4 +239,20 @@ mod tests {
     fn invalid_suit_yields_error() {
         assert!("1p".parse::<Card>().is_err());
     }
+
+    #[test]
+    fn display_round_trips() {
+        for index in 0..52 {
+            let card = Card::from_index(index).unwrap();
+            assert_eq!(card, card.to_string().parse().unwrap());
+        }
+    }
+
+    #[test]
+    fn index_round_trips() {
+        for index in 0..52 {
+            assert_eq!(Card::from_index(index).unwrap().index(), index);
+        }
+        assert!(Card::from_index(52).is_none());
+    }
 }
@@ -0,0 +1,289 @@
+//! Casino games played against a [`Paytable`]: Caribbean Stud and Let It Ride.
+//!
+//! Both games rank five-card hands as in [`crate::compare`], but pay by the category of the hand,
+//! at the odds of a paytable. Paytables differ between casinos, so they are [`Deserialize`] for
+//! loading from configuration, with the common ones given by [`Paytable::caribbean_stud`] and
+//! [`Paytable::let_it_ride`].
+//!
+//! * Caribbean Stud: the player bets an ante, and raises to play on with twice the ante. The dealer
+//!   qualifies with Ace-King high or better. If the dealer does not qualify, the ante pays even
+//!   money and the raise is returned. Otherwise, a winning player is paid even money on the ante
+//!   and by the paytable on the raise, and a losing player loses both.
+//! * Let It Ride: the player bets three equal bets and is dealt three cards, and two community
+//!   cards are dealt. The player may take back the first bet after seeing their cards, and the
+//!   second after the first community card. The bets left standing are paid by the paytable.
+
+use crate::{
+    card::Card,
+    chips::Chips,
+    compare::{strength, Strength},
+    hand::{Hand, HandCategory},
+};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap};
+
+/// The odds, to one, paid for each category of hand.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Paytable {
+    /// Categories which are not listed do not pay.
+    pub odds: BTreeMap<HandCategory, u32>,
+    /// The odds of a royal flush, if it pays more than other straight flushes.
+    #[serde(default)]
+    pub royal_flush: Option<u32>,
+    /// The lowest pair which pays, with the Ace as 14, e.g., 10 for Tens or better.
+    #[serde(default = "lowest_rank")]
+    pub lowest_pair: u8,
+}
+
+fn lowest_rank() -> u8 {
+    2
+}
+
+impl Paytable {
+    /// The common paytable of the raise in Caribbean Stud.
+    pub fn caribbean_stud() -> Self {
+        use HandCategory::*;
+        Self {
+            odds: BTreeMap::from([
+                (HighCard, 1),
+                (OnePair, 1),
+                (TwoPair, 2),
+                (ThreeOfAKind, 3),
+                (Straight, 4),
+                (Flush, 5),
+                (FullHouse, 7),
+                (FourOfAKind, 20),
+                (StraightFlush, 50),
+            ]),
+            royal_flush: Some(100),
+            lowest_pair: lowest_rank(),
+        }
+    }
+
+    /// The common paytable of Let It Ride, which pays Tens or better.
+    pub fn let_it_ride() -> Self {
+        use HandCategory::*;
+        Self {
+            odds: BTreeMap::from([
+                (OnePair, 1),
+                (TwoPair, 2),
+                (ThreeOfAKind, 3),
+                (Straight, 5),
+                (Flush, 8),
+                (FullHouse, 11),
+                (FourOfAKind, 50),
+                (StraightFlush, 200),
+            ]),
+            royal_flush: Some(1000),
+            lowest_pair: 10,
+        }
+    }
+
+    /// The odds paid for a hand of the [`Strength`], or `None` if it does not pay.
+    pub fn pays(&self, strength: &Strength) -> Option<u32> {
+        match strength.category {
+            HandCategory::OnePair if strength.tie_breakers[0] < self.lowest_pair => None,
+            HandCategory::StraightFlush if strength.tie_breakers[0] == 14 => self
+                .royal_flush
+                .or_else(|| self.odds.get(&strength.category).copied()),
+            category => self.odds.get(&category).copied(),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum CasinoError {
+    #[error("{0} is dealt more than once")]
+    DuplicateCard(Card),
+    #[error("number of bets left standing ({0}) must be from 1 to 3")]
+    Bets(u8),
+}
+
+/// Fail if a card is dealt to more than one of the `hands`.
+fn check_deal<'a>(hands: impl IntoIterator<Item = &'a Card>) -> Result<(), CasinoError> {
+    let mut dealt = Vec::new();
+    for card in hands {
+        if dealt.contains(card) {
+            return Err(CasinoError::DuplicateCard(*card));
+        }
+        dealt.push(*card);
+    }
+    Ok(())
+}
+
+/// Whether the dealer's hand qualifies in Caribbean Stud: Ace-King high or better.
+pub fn dealer_qualifies(dealer: &Strength) -> bool {
+    dealer.category > HandCategory::HighCard || dealer.tie_breakers[..2] == [14, 13]
+}
+
+/// The outcome of a Caribbean Stud hand where the player raised.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct CaribbeanStud {
+    pub player: Strength,
+    pub dealer: Strength,
+    pub dealer_qualifies: bool,
+    /// What the player won, or lost if negative, on the ante and the raise together.
+    pub net: Chips,
+}
+
+/// Play the `player`'s hand against the `dealer`'s in Caribbean Stud, with the `ante`, where the
+/// player raises twice the ante. The raise is paid by the `paytable`, or even money for a
+/// category it does not list.
+pub fn caribbean_stud(
+    player: &Hand,
+    dealer: &Hand,
+    ante: Chips,
+    paytable: &Paytable,
+) -> Result<CaribbeanStud, CasinoError> {
+    check_deal(player.cards().chain(dealer.cards()))?;
+    let (player, dealer) = (strength(player), strength(dealer));
+    let dealer_qualifies = dealer_qualifies(&dealer);
+    let raise = ante * 2;
+    let net = if !dealer_qualifies {
+        ante
+    } else {
+        match player.cmp(&dealer) {
+            Ordering::Greater => ante + raise * i64::from(paytable.pays(&player).unwrap_or(1)),
+            Ordering::Equal => Chips::ZERO,
+            Ordering::Less => -(ante + raise),
+        }
+    };
+    Ok(CaribbeanStud {
+        player,
+        dealer,
+        dealer_qualifies,
+        net,
+    })
+}
+
+/// The outcome of a Let It Ride hand.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct LetItRide {
+    pub hand: Hand,
+    pub strength: Strength,
+    /// The odds paid, or `None` if the hand does not pay.
+    pub odds: Option<u32>,
+    /// What the player won, or lost if negative, on the bets left standing.
+    pub net: Chips,
+}
+
+/// Play the player's three cards with the two `community` cards in Let It Ride, with `bets` of
+/// `bet` each left standing, paid by the `paytable`.
+pub fn let_it_ride(
+    player: [Card; 3],
+    community: [Card; 2],
+    bets: u8,
+    bet: Chips,
+    paytable: &Paytable,
+) -> Result<LetItRide, CasinoError> {
+    if !(1..=3).contains(&bets) {
+        return Err(CasinoError::Bets(bets));
+    }
+    check_deal(player.iter().chain(&community))?;
+    let cards = [player[0], player[1], player[2], community[0], community[1]];
+    let hand = Hand::try_from(cards.as_slice()).expect("five unique cards are dealt");
+    let strength = strength(&hand);
+    let odds = paytable.pays(&strength);
+    let staked = bet * i64::from(bets);
+    let net = match odds {
+        Some(odds) => staked * i64::from(odds),
+        None => -staked,
+    };
+    Ok(LetItRide {
+        hand,
+        strength,
+        odds,
+        net,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(cards: &str) -> Hand {
+        cards.parse().unwrap()
+    }
+
+    const fn chips(chips: i64) -> Chips {
+        Chips::whole(chips)
+    }
+
+    fn cards<const N: usize>(cards: &str) -> [Card; N] {
+        let cards: Vec<Card> = cards.split(',').map(|c| c.parse().unwrap()).collect();
+        cards.try_into().unwrap()
+    }
+
+    #[test]
+    fn dealer_qualifies_with_ace_king() {
+        assert!(dealer_qualifies(&strength(&hand("1s,kh,9r,7k,5s"))));
+        assert!(!dealer_qualifies(&strength(&hand("1s,qh,9r,7k,5s"))));
+        assert!(dealer_qualifies(&strength(&hand("2s,2h,9r,7k,5s"))));
+    }
+
+    #[test]
+    fn caribbean_stud_pays_ante_when_dealer_does_not_qualify() {
+        let paytable = Paytable::caribbean_stud();
+        let player = hand("2s,3h,9r,7k,5s");
+        let outcome =
+            caribbean_stud(&player, &hand("1h,qh,9h,7r,4s"), chips(10), &paytable).unwrap();
+        assert!(!outcome.dealer_qualifies);
+        assert_eq!(outcome.net, chips(10));
+    }
+
+    #[test]
+    fn caribbean_stud_pays_raise_by_paytable() {
+        let paytable = Paytable::caribbean_stud();
+        let dealer = hand("1h,kk,9h,7r,4s");
+        let flush = hand("2s,5s,8s,js,qs");
+        assert_eq!(
+            caribbean_stud(&flush, &dealer, chips(10), &paytable)
+                .unwrap()
+                .net,
+            chips(10 + 20 * 5)
+        );
+        let losing = hand("1s,kh,8r,6k,3s");
+        assert_eq!(
+            caribbean_stud(&losing, &dealer, chips(10), &paytable)
+                .unwrap()
+                .net,
+            chips(-30)
+        );
+        assert_eq!(
+            caribbean_stud(&flush, &hand("1s,kh,9r,7k,5s"), chips(10), &paytable),
+            Err(CasinoError::DuplicateCard("5s".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn let_it_ride_pays_tens_or_better() {
+        let paytable = Paytable::let_it_ride();
+        let tens = let_it_ride(cards("ts,th,2r"), cards("5k,8s"), 3, chips(5), &paytable).unwrap();
+        assert_eq!((tens.odds, tens.net), (Some(1), chips(15)));
+        let half = "2.5".parse().unwrap();
+        let nines = let_it_ride(cards("9s,9h,2r"), cards("5k,8s"), 2, half, &paytable).unwrap();
+        assert_eq!((nines.odds, nines.net), (None, chips(-5)));
+        assert_eq!(
+            let_it_ride(cards("9s,9h,2r"), cards("5k,8s"), 0, chips(5), &paytable).unwrap_err(),
+            CasinoError::Bets(0)
+        );
+    }
+
+    #[test]
+    fn royal_flush_pays_its_own_odds() {
+        let paytable = Paytable::let_it_ride();
+        let royal = let_it_ride(cards("ts,js,qs"), cards("ks,1s"), 1, chips(1), &paytable).unwrap();
+        assert_eq!(royal.odds, Some(1000));
+        let straight_flush = let_it_ride(cards("9s,ts,js"), cards("qs,ks"), 1, chips(1), &paytable);
+        assert_eq!(straight_flush.unwrap().odds, Some(200));
+    }
+
+    #[test]
+    fn paytable_is_configurable() {
+        let paytable: Paytable =
+            serde_json::from_str(r#"{"odds": {"Flush": 6, "FullHouse": 9}}"#).unwrap();
+        assert_eq!(paytable.lowest_pair, 2);
+        assert_eq!(paytable.pays(&strength(&hand("2s,5s,8s,js,qs"))), Some(6));
+        assert_eq!(paytable.pays(&strength(&hand("1s,1h,8s,js,qs"))), None);
+    }
+}
@@ -0,0 +1,68 @@
+//! CBOR responses, for clients like microcontrollers which do not speak JSON.
+//!
+//! A request whose `Accept` header asks for `application/cbor` before `application/json` gets
+//! its JSON response encoded as CBOR by [`encode_cbor`], with the same structure. Like
+//! [field selection](crate::fields), this is done on the serialized response, so handlers need not
+//! know of it, and fields are selected before encoding. Errors, and bodies which are not JSON, are
+//! returned as they are.
+
+use crate::router::prefers;
+use axum::{
+    body::{self, Full},
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+
+/// The media type of CBOR.
+pub const CBOR: &str = "application/cbor";
+
+/// Encode the JSON value as CBOR.
+pub fn to_vec(value: &Value) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes).expect("writing to a vector never fails");
+    bytes
+}
+
+/// Middleware which encodes JSON responses as CBOR, when the request prefers it. Use with
+/// [`axum::middleware::from_fn`].
+pub async fn encode_cbor<B>(request: Request<B>, next: Next<B>) -> Response {
+    let wants_cbor = prefers(request.headers(), CBOR);
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if !wants_cbor || !response.status().is_success() || !is_json {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = hyper::body::to_bytes(body).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let Ok(value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, body::boxed(Full::from(bytes)));
+    };
+    parts
+        .headers
+        .insert(header::CONTENT_TYPE, HeaderValue::from_static(CBOR));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, body::boxed(Full::from(to_vec(&value))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn values_keep_their_structure() {
+        let value = json!({"category": "Flush", "tie_breakers": [13, 9], "beats": 0.5});
+        let bytes = to_vec(&value);
+        let decoded: Value = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, value);
+        // A text string of three bytes, major type 3.
+        assert_eq!(to_vec(&json!("abc")), [0x63, b'a', b'b', b'c']);
+    }
+}
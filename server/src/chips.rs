@@ -0,0 +1,274 @@
+//! Chip amounts as integer micro-units, so pot math never rounds.
+//!
+//! A [`Chips`] amount counts millionths of a chip in an `i64`, which holds over nine trillion
+//! chips. Amounts are added, subtracted and multiplied by whole numbers only, and these panic on
+//! overflow instead of wrapping, like a debug build does. Amounts are serialized as their number of
+//! micro-units, so no float is ever written, and shown and parsed as decimals, like `12.5`.
+//!
+//! [`breakdown`] splits an amount into the chip denominations of a table, like for paying out a
+//! win in physical chips.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// The number of micro-units in a chip.
+pub const MICROS_PER_CHIP: i64 = 1_000_000;
+
+/// An amount of chips, negative for a loss.
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(transparent)]
+pub struct Chips(i64);
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum ChipsError {
+    #[error("{0:?} is not an amount of chips, like 12.5")]
+    Parse(String),
+    #[error("{0:?} has more than 6 decimals")]
+    Precision(String),
+    #[error("{0:?} is too large an amount of chips")]
+    Overflow(String),
+    #[error("denominations must be positive")]
+    Denomination,
+    #[error("{0} cannot be made of the denominations")]
+    Breakdown(Chips),
+}
+
+impl Chips {
+    pub const ZERO: Chips = Chips(0);
+
+    pub const fn from_micros(micros: i64) -> Self {
+        Self(micros)
+    }
+
+    /// An amount of whole chips.
+    ///
+    /// # Panics
+    ///
+    /// If the amount overflows.
+    pub const fn whole(chips: i64) -> Self {
+        match chips.checked_mul(MICROS_PER_CHIP) {
+            Some(micros) => Self(micros),
+            None => panic!("chip amount overflows"),
+        }
+    }
+
+    pub const fn micros(self) -> i64 {
+        self.0
+    }
+
+    pub fn checked_add(self, other: Chips) -> Option<Chips> {
+        self.0.checked_add(other.0).map(Chips)
+    }
+
+    pub fn checked_sub(self, other: Chips) -> Option<Chips> {
+        self.0.checked_sub(other.0).map(Chips)
+    }
+
+    pub fn checked_mul(self, times: i64) -> Option<Chips> {
+        self.0.checked_mul(times).map(Chips)
+    }
+}
+
+impl Add for Chips {
+    type Output = Chips;
+
+    fn add(self, other: Chips) -> Chips {
+        self.checked_add(other).expect("chip amount overflows")
+    }
+}
+
+impl AddAssign for Chips {
+    fn add_assign(&mut self, other: Chips) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Chips {
+    type Output = Chips;
+
+    fn sub(self, other: Chips) -> Chips {
+        self.checked_sub(other).expect("chip amount overflows")
+    }
+}
+
+impl SubAssign for Chips {
+    fn sub_assign(&mut self, other: Chips) {
+        *self = *self - other;
+    }
+}
+
+impl Neg for Chips {
+    type Output = Chips;
+
+    fn neg(self) -> Chips {
+        Chips(self.0.checked_neg().expect("chip amount overflows"))
+    }
+}
+
+impl Mul<i64> for Chips {
+    type Output = Chips;
+
+    fn mul(self, times: i64) -> Chips {
+        self.checked_mul(times).expect("chip amount overflows")
+    }
+}
+
+impl Sum for Chips {
+    fn sum<I: Iterator<Item = Chips>>(iter: I) -> Chips {
+        iter.fold(Chips::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for Chips {
+    /// Whole chips, and the fraction without trailing zeros, like `12`, `12.5` or `-0.000001`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let micros = self.0.unsigned_abs();
+        let (whole, fraction) = (micros / 1_000_000, micros % 1_000_000);
+        if fraction == 0 {
+            write!(f, "{sign}{whole}")
+        } else {
+            let fraction = format!("{fraction:06}");
+            write!(f, "{sign}{whole}.{}", fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Chips {
+    type Err = ChipsError;
+
+    /// Parse a decimal amount with at most 6 decimals, like `12.5` or `-3`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_error = || ChipsError::Parse(s.to_owned());
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) {
+            return Err(parse_error());
+        }
+        if digits.ends_with('.') {
+            return Err(parse_error());
+        }
+        if fraction.len() > 6 {
+            return Err(ChipsError::Precision(s.to_owned()));
+        }
+        let overflow = || ChipsError::Overflow(s.to_owned());
+        let whole: i64 = whole.parse().map_err(|_| overflow())?;
+        let fraction: i64 = format!("{fraction:0<6}")
+            .parse()
+            .map_err(|_| parse_error())?;
+        let micros = whole
+            .checked_mul(MICROS_PER_CHIP)
+            .and_then(|micros| micros.checked_add(fraction))
+            .ok_or_else(overflow)?;
+        Ok(Chips(if negative { -micros } else { micros }))
+    }
+}
+
+/// Split the `amount` into as few chips of the `denominations` as taking the largest first gives,
+/// as pairs of denomination and count, from the largest. Denominations with a count of 0 are left
+/// out.
+pub fn breakdown(amount: Chips, denominations: &[Chips]) -> Result<Vec<(Chips, u64)>, ChipsError> {
+    if denominations.iter().any(|&d| d <= Chips::ZERO) {
+        return Err(ChipsError::Denomination);
+    }
+    if amount < Chips::ZERO {
+        return Err(ChipsError::Breakdown(amount));
+    }
+    let mut denominations = denominations.to_vec();
+    denominations.sort_unstable_by(|a, b| b.cmp(a));
+    let mut left = amount.0;
+    let mut chips = Vec::new();
+    for denomination in denominations {
+        let count = left / denomination.0;
+        if count > 0 {
+            left -= count * denomination.0;
+            chips.push((denomination, count.unsigned_abs()));
+        }
+    }
+    if left != 0 {
+        return Err(ChipsError::Breakdown(amount));
+    }
+    Ok(chips)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amounts_are_shown_and_parsed_exactly() {
+        for (text, micros) in [
+            ("12", 12_000_000),
+            ("12.5", 12_500_000),
+            ("-0.000001", -1),
+            ("0.1", 100_000),
+        ] {
+            let chips: Chips = text.parse().unwrap();
+            assert_eq!(chips, Chips::from_micros(micros), "{text}");
+            assert_eq!(chips.to_string(), text);
+        }
+        // Tenths add up exactly, unlike floats.
+        let tenth: Chips = "0.1".parse().unwrap();
+        assert_eq!(tenth + tenth + tenth, "0.3".parse().unwrap());
+        assert_eq!(serde_json::to_string(&tenth).unwrap(), "100000");
+    }
+
+    #[test]
+    fn invalid_amounts_are_rejected() {
+        for text in ["", "1.", ".5", "1e3", "--1", "1.2.3", "twelve"] {
+            assert_eq!(
+                text.parse::<Chips>(),
+                Err(ChipsError::Parse(text.to_owned())),
+                "{text}"
+            );
+        }
+        assert!(matches!(
+            "0.0000001".parse::<Chips>(),
+            Err(ChipsError::Precision(_))
+        ));
+        assert!(matches!(
+            "99999999999999".parse::<Chips>(),
+            Err(ChipsError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "chip amount overflows")]
+    fn overflow_panics() {
+        let _ = Chips::from_micros(i64::MAX) + Chips::from_micros(1);
+    }
+
+    #[test]
+    fn amounts_break_down_into_denominations() {
+        let denominations = [1, 5, 25, 100].map(Chips::whole);
+        assert_eq!(
+            breakdown(Chips::whole(136), &denominations).unwrap(),
+            [
+                (Chips::whole(100), 1),
+                (Chips::whole(25), 1),
+                (Chips::whole(5), 2),
+                (Chips::whole(1), 1)
+            ]
+        );
+        let half: Chips = "0.5".parse().unwrap();
+        assert_eq!(
+            breakdown(half, &denominations),
+            Err(ChipsError::Breakdown(half))
+        );
+        assert_eq!(
+            breakdown(half, &[Chips::ZERO]),
+            Err(ChipsError::Denomination)
+        );
+    }
+}
@@ -9,7 +9,10 @@ use crate::{
     hand::{Hand, HandCategory, RankCount, SuitCount},
 };
 
-use itertools::Itertools;
+/// Version of the classification performed by [`classify`]. It is bumped whenever a change can
+/// give a different [`HandCategory`] for the same [`Hand`], so that results from different versions
+/// can be told apart.
+pub const CLASSIFIER_VERSION: u32 = 1;
 
 /// Analyze the five-card [`Hand`] and returns the highest-ranking category possible with it.
 pub fn classify(hand: &Hand) -> HandCategory {
@@ -59,45 +62,60 @@ pub fn classify(hand: &Hand) -> HandCategory {
 }
 
 fn is_flush(suit_count: &SuitCount) -> bool {
-    suit_count.len() == 1
+    suit_count.contains(&5)
 }
 
 fn is_straight(rank_count: &RankCount) -> bool {
     // Exit early if we don't have five different ranks.
-    if rank_count.len() != 5 {
+    if distinct_ranks(rank_count) != 5 {
         return false;
     }
 
-    let ranks: Vec<_> = rank_count.keys().copied().sorted().collect();
-
-    // Handle special case of Ten through Ace. If "lowest" is Ace and next is Ten, we have a
-    // Ten-through-Ace straight, since we have five different ranks.
-    if ranks[0] == Rank::Ace && ranks[1] == Rank::Ten {
+    let lowest = rank_count
+        .iter()
+        .position(|&v| v > 0)
+        .expect("we have five ranks");
+    let highest = rank_count
+        .iter()
+        .rposition(|&v| v > 0)
+        .expect("we have five ranks");
+
+    // Handle special case of Ten through Ace. If "lowest" is Ace and there is nothing from Two
+    // through Nine, we have a Ten-through-Ace straight, since we have five different ranks.
+    if lowest == Rank::Ace as usize
+        && rank_count[Rank::Two as usize..Rank::Ten as usize]
+            .iter()
+            .all(|&v| v == 0)
+    {
         return true;
     }
 
     // Check distance between lowest and highest rank.
-    ranks[4].numeric() - ranks[0].numeric() == 4
+    highest - lowest == 4
 }
 
 fn is_four_of_a_kind(rank_count: &RankCount) -> bool {
-    rank_count.values().any(|&v| v == 4)
+    rank_count.contains(&4)
 }
 
 fn is_full_house(rank_count: &RankCount) -> bool {
-    rank_count.len() == 2 && rank_count.values().any(|&v| v == 2 || v == 3)
+    distinct_ranks(rank_count) == 2 && rank_count.iter().any(|&v| v == 2 || v == 3)
 }
 
 fn is_three_of_a_kind(rank_count: &RankCount) -> bool {
-    rank_count.values().any(|&v| v == 3)
+    rank_count.contains(&3)
 }
 
 fn is_two_pair(rank_count: &RankCount) -> bool {
-    rank_count.values().filter(|&&v| v == 2).count() == 2
+    rank_count.iter().filter(|&&v| v == 2).count() == 2
 }
 
 fn is_one_pair(rank_count: &RankCount) -> bool {
-    rank_count.values().any(|&v| v == 2)
+    rank_count.contains(&2)
+}
+
+fn distinct_ranks(rank_count: &RankCount) -> usize {
+    rank_count.iter().filter(|&&v| v > 0).count()
 }
 
 #[cfg(test)]
@@ -0,0 +1,129 @@
+//! Compare five-card [`Hand`]s by their poker [`Strength`].
+//!
+//! Two hands are first compared by their [`HandCategory`]. Hands of the same category are compared
+//! by their tie-breaking ranks, e.g., the rank of the pair and then the kickers for
+//! [`HandCategory::OnePair`].
+
+use crate::{
+    card::Rank,
+    classify::classify,
+    hand::{Hand, HandCategory},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// The strength of a five-card [`Hand`].
+///
+/// Strengths are ordered like the hands they come from: a higher [`Strength`] beats a lower one,
+/// and equal strengths split the pot.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Ord, PartialOrd, Serialize)]
+pub struct Strength {
+    pub category: HandCategory,
+    /// Numeric ranks (Ace counted as 14) breaking ties within the category, most significant
+    /// first.
+    pub tie_breakers: Vec<u8>,
+}
+
+/// Compute the [`Strength`] of the [`Hand`].
+pub fn strength(hand: &Hand) -> Strength {
+    use HandCategory::{Straight, StraightFlush};
+
+    let category = classify(hand);
+
+    // Sort ranks by how many times they occur, then by how high they are. This puts e.g. the
+    // three-of-a-kind before the pair in a full house, and the highest card first in a flush.
+    let ranks: Vec<_> = Rank::ALL
+        .into_iter()
+        .zip(hand.count_ranks())
+        .filter(|&(_, count)| count > 0)
+        .map(|(rank, count)| (count, rank.numeric_ace_high()))
+        .sorted()
+        .rev()
+        .map(|(_, rank)| rank)
+        .collect();
+
+    let tie_breakers = match category {
+        // The Ace plays low in an Ace-through-Five straight, making Five the highest card.
+        Straight | StraightFlush if ranks == [14, 5, 4, 3, 2] => vec![5],
+        Straight | StraightFlush => vec![ranks[0]],
+        _ => ranks,
+    };
+
+    Strength {
+        category,
+        tie_breakers,
+    }
+}
+
+/// Compare two [`Hand`]s. The greater hand wins, and equal hands split the pot.
+pub fn compare(a: &Hand, b: &Hand) -> Ordering {
+    strength(a).cmp(&strength(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::Card;
+
+    fn hand(cards: &str) -> Hand {
+        let cards: Vec<Card> = cards.split(',').map(|c| c.parse().unwrap()).collect();
+        Hand::try_from(cards.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn higher_category_wins() {
+        assert_eq!(
+            compare(&hand("2r,2s,5k,9h,jr"), &hand("1r,ks,qk,9h,jr")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn higher_pair_wins() {
+        assert_eq!(
+            compare(&hand("2r,2s,5k,9h,jr"), &hand("3r,3s,4k,6h,8r")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn kicker_breaks_tie() {
+        assert_eq!(
+            compare(&hand("1r,1s,5k,9h,jr"), &hand("1k,1h,5r,9s,qr")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn ace_plays_high_in_high_card() {
+        assert_eq!(
+            compare(&hand("1r,3s,5k,9h,jr"), &hand("2k,3h,5r,9s,kr")),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn wheel_is_lowest_straight() {
+        assert_eq!(
+            compare(&hand("1r,2s,3k,4h,5r"), &hand("2k,3h,4r,5s,6r")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn full_house_compares_triplet_first() {
+        assert_eq!(
+            compare(&hand("2r,2s,2k,1h,1r"), &hand("kk,kh,3r,3s,3h")),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn identical_ranks_tie() {
+        assert_eq!(
+            compare(&hand("1r,3s,5k,9h,jr"), &hand("1k,3h,5r,9s,js")),
+            Ordering::Equal
+        );
+    }
+}
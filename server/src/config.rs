@@ -0,0 +1,499 @@
+//! Configuration of the server binaries, read from environment variables.
+//!
+//! | Variable        | Meaning                                          | Default        |
+//! |-----------------|--------------------------------------------------|----------------|
+//! | `BIND_ADDR`     | TCP address to listen on                         | `0.0.0.0:8080` |
+//! | `BIND_UNIX`     | Unix domain socket path to listen on instead     |                |
+//! | `TLS_CERT_PATH` | PEM certificate chain, enables HTTPS             |                |
+//! | `TLS_KEY_PATH`  | PEM private key, required with the certificate   |                |
+//! | `HTTP2`         | Whether to accept HTTP/2 besides HTTP/1.1        | `true`         |
+//! | `HTTP1_KEEP_ALIVE` | Whether to keep HTTP/1.1 connections open     | `true`         |
+//! | `HTTP2_KEEP_ALIVE_INTERVAL_SECS` | Seconds between HTTP/2 pings    | no pings       |
+//! | `HTTP2_KEEP_ALIVE_TIMEOUT_SECS` | Seconds to wait for a ping reply | `20`           |
+//! | `HTTP2_MAX_CONCURRENT_STREAMS` | Streams per HTTP/2 connection     | `200`          |
+//! | `MAX_CONNECTIONS` | Connections served at once, at least 1; more wait | unlimited     |
+//! | `LOG_FORMAT`    | `text` for people, `json` for log pipelines      | `text`         |
+//! | `LATENCY_THRESHOLD_MS` | Requests slower than this count against the SLO | `100`   |
+//! | `ANALYZE_CACHE_CAPACITY` | Hands whose analysis is cached; 0 disables it | `10000`     |
+//! | `SIMULATION_TIME_LIMIT_MS` | Simulations stop, truncated, after this | `60000`           |
+//! | `MAX_RUNNING_JOBS` | Simulations and expensive requests at once, at least 1; more are refused | `4` |
+//! | `BENCHMARK_ENDPOINT` | Whether to serve `/evaluators/benchmark`      | `false`        |
+//! | `DEMO_EXCITING_RATE` | Share of draws made flushes or better, see [`crate::demo`] | fair draws |
+//! | `SIGNING_KEY` | Base64 32-byte Ed25519 seed signing responses, see [`crate::signing`] | unsigned |
+//! | `ENTROPY_SOURCE` | File to read randomness from, see [`crate::entropy`] | the operating system |
+//!
+//! When started by systemd socket activation, i.e., when `LISTEN_PID` is this process and
+//! `LISTEN_FDS` is at least 1, the server listens on the socket passed by systemd, and the two
+//! `BIND_*` variables are ignored.
+//!
+//! `DEMO_EXCITING_RATE` is only read with the `demo` feature, and `SIGNING_KEY` with the `signing`
+//! feature.
+//!
+//! The lambda binary reads the same configuration, but only `LOG_FORMAT` and the [`RouterConfig`]
+//! apply to it.
+
+use crate::variant::Variants;
+use std::{env, net::SocketAddr, path::PathBuf, process, time::Duration};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    pub listen: Listen,
+    /// Serve HTTPS instead of HTTP when set.
+    pub tls: Option<TlsConfig>,
+    pub http: HttpConfig,
+    pub log_format: LogFormat,
+    pub router: RouterConfig,
+}
+
+/// Where the server listens for connections.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Listen {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+    /// The first socket passed by systemd socket activation, which is either a TCP or a Unix socket.
+    Systemd,
+}
+
+/// Paths to the PEM files used for HTTPS. The server reloads them on `SIGHUP`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+/// Tuning of the HTTP connections.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpConfig {
+    pub http2: bool,
+    pub http1_keep_alive: bool,
+    pub http2_keep_alive_interval: Option<Duration>,
+    pub http2_keep_alive_timeout: Duration,
+    pub http2_max_concurrent_streams: u32,
+    pub max_connections: Option<usize>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            http2: true,
+            http1_keep_alive: true,
+            http2_keep_alive_interval: None,
+            http2_keep_alive_timeout: Duration::from_secs(20),
+            http2_max_concurrent_streams: 200,
+            max_connections: None,
+        }
+    }
+}
+
+/// Configuration of the endpoints, shared by the server and lambda binaries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouterConfig {
+    /// Requests slower than this are counted by the metrics. See [`crate::metrics`].
+    pub latency_threshold: Duration,
+    /// Entries in the cache of analyzed hands. See [`crate::cache`].
+    pub analyze_cache_capacity: usize,
+    /// The variants served under `/variants/:variant`. See [`crate::variant`].
+    pub variants: Variants,
+    /// Simulation jobs stop after this long, with a truncated result. See [`crate::jobs`].
+    pub simulation_time_limit: Duration,
+    /// At most this many simulation jobs and expensive requests run at once. See [`crate::jobs`].
+    pub max_running_jobs: usize,
+    /// Serve `/evaluators/benchmark`, which keeps a core busy for as long as it is asked to, so it
+    /// is for operators tuning their deployment rather than the public. See [`crate::benchmark`].
+    pub benchmark_endpoint: bool,
+    /// Draw from the demo deck source at this rate instead of a fair deck. See [`crate::demo`].
+    #[cfg(feature = "demo")]
+    pub demo_rate: Option<crate::demo::DemoRate>,
+    /// Sign analyze and draw responses with this key. See [`crate::signing`].
+    #[cfg(feature = "signing")]
+    pub signing_key: Option<crate::signing::SigningKey>,
+    /// Draw, deal and create tokens with randomness read from this file, like a hardware random
+    /// number generator, falling back to the operating system while it is unhealthy. See
+    /// [`crate::entropy`].
+    pub entropy_source: Option<PathBuf>,
+}
+
+impl Default for RouterConfig {
+    fn default() -> Self {
+        Self {
+            latency_threshold: Duration::from_millis(100),
+            analyze_cache_capacity: 10_000,
+            variants: Variants::default(),
+            simulation_time_limit: Duration::from_secs(60),
+            max_running_jobs: crate::jobs::DEFAULT_MAX_RUNNING,
+            benchmark_endpoint: false,
+            #[cfg(feature = "demo")]
+            demo_rate: None,
+            #[cfg(feature = "signing")]
+            signing_key: None,
+            entropy_source: None,
+        }
+    }
+}
+
+/// How log lines are written. See [`crate::logging`] for the fields logged per request.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("expected `text` or `json`")]
+pub struct InvalidLogFormat;
+
+impl std::str::FromStr for LogFormat {
+    type Err = InvalidLogFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(InvalidLogFormat),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ConfigError {
+    #[error("{name} ({value}) is invalid: {reason}")]
+    Invalid {
+        name: &'static str,
+        value: String,
+        reason: String,
+    },
+    #[error("{0} is set, but {1} is not")]
+    Missing(&'static str, &'static str),
+    #[error("only one of {0} and {1} can be set")]
+    Conflict(&'static str, &'static str),
+}
+
+impl Config {
+    /// Read the configuration from the process environment.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Read the configuration from `var`, which looks up a variable by name.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let listen = if is_socket_activated(&var)? {
+            Listen::Systemd
+        } else {
+            match (var("BIND_ADDR"), var("BIND_UNIX")) {
+                (Some(_), Some(_)) => return Err(ConfigError::Conflict("BIND_ADDR", "BIND_UNIX")),
+                (Some(value), None) => Listen::Tcp(parse("BIND_ADDR", value)?),
+                (None, Some(path)) => Listen::Unix(path.into()),
+                (None, None) => Listen::Tcp(SocketAddr::from(([0, 0, 0, 0], 8080))),
+            }
+        };
+
+        let tls = match (var("TLS_CERT_PATH"), var("TLS_KEY_PATH")) {
+            (Some(cert_path), Some(key_path)) => Some(TlsConfig {
+                cert_path: cert_path.into(),
+                key_path: key_path.into(),
+            }),
+            (Some(_), None) => return Err(ConfigError::Missing("TLS_CERT_PATH", "TLS_KEY_PATH")),
+            (None, Some(_)) => return Err(ConfigError::Missing("TLS_KEY_PATH", "TLS_CERT_PATH")),
+            (None, None) => None,
+        };
+
+        let defaults = HttpConfig::default();
+        let http = HttpConfig {
+            http2: parse_or("HTTP2", &var, defaults.http2)?,
+            http1_keep_alive: parse_or("HTTP1_KEEP_ALIVE", &var, defaults.http1_keep_alive)?,
+            http2_keep_alive_interval: var("HTTP2_KEEP_ALIVE_INTERVAL_SECS")
+                .map(|value| parse("HTTP2_KEEP_ALIVE_INTERVAL_SECS", value))
+                .transpose()?
+                .map(Duration::from_secs),
+            http2_keep_alive_timeout: Duration::from_secs(parse_or(
+                "HTTP2_KEEP_ALIVE_TIMEOUT_SECS",
+                &var,
+                defaults.http2_keep_alive_timeout.as_secs(),
+            )?),
+            http2_max_concurrent_streams: parse_or(
+                "HTTP2_MAX_CONCURRENT_STREAMS",
+                &var,
+                defaults.http2_max_concurrent_streams,
+            )?,
+            max_connections: var("MAX_CONNECTIONS")
+                .map(|value| at_least_one("MAX_CONNECTIONS", parse("MAX_CONNECTIONS", value)?))
+                .transpose()?,
+        };
+
+        let log_format = parse_or("LOG_FORMAT", &var, LogFormat::default())?;
+
+        let defaults = RouterConfig::default();
+        let router = RouterConfig {
+            latency_threshold: Duration::from_millis(parse_or(
+                "LATENCY_THRESHOLD_MS",
+                &var,
+                defaults.latency_threshold.as_millis().try_into().unwrap(),
+            )?),
+            analyze_cache_capacity: parse_or(
+                "ANALYZE_CACHE_CAPACITY",
+                &var,
+                defaults.analyze_cache_capacity,
+            )?,
+            variants: defaults.variants,
+            simulation_time_limit: Duration::from_millis(parse_or(
+                "SIMULATION_TIME_LIMIT_MS",
+                &var,
+                defaults
+                    .simulation_time_limit
+                    .as_millis()
+                    .try_into()
+                    .unwrap(),
+            )?),
+            max_running_jobs: at_least_one(
+                "MAX_RUNNING_JOBS",
+                parse_or("MAX_RUNNING_JOBS", &var, defaults.max_running_jobs)?,
+            )?,
+            benchmark_endpoint: parse_or("BENCHMARK_ENDPOINT", &var, defaults.benchmark_endpoint)?,
+            #[cfg(feature = "demo")]
+            demo_rate: var("DEMO_EXCITING_RATE")
+                .map(|value| parse("DEMO_EXCITING_RATE", value))
+                .transpose()?,
+            #[cfg(feature = "signing")]
+            signing_key: var("SIGNING_KEY")
+                .map(|value| parse("SIGNING_KEY", value))
+                .transpose()?,
+            entropy_source: var("ENTROPY_SOURCE").map(PathBuf::from),
+        };
+
+        Ok(Self {
+            listen,
+            tls,
+            http,
+            log_format,
+            router,
+        })
+    }
+}
+
+/// Whether systemd passed this process a socket, following `sd_listen_fds(3)`.
+fn is_socket_activated(var: &impl Fn(&str) -> Option<String>) -> Result<bool, ConfigError> {
+    let (Some(pid), Some(fds)) = (var("LISTEN_PID"), var("LISTEN_FDS")) else {
+        return Ok(false);
+    };
+    let pid: u32 = parse("LISTEN_PID", pid)?;
+    let fds: u32 = parse("LISTEN_FDS", fds)?;
+    Ok(pid == process::id() && fds > 0)
+}
+
+fn parse_or<T>(
+    name: &'static str,
+    var: &impl Fn(&str) -> Option<String>,
+    default: T,
+) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    var(name).map_or(Ok(default), |value| parse(name, value))
+}
+
+/// Fail unless the `value` of the variable `name` is at least 1, like a limit which would
+/// otherwise refuse everything.
+fn at_least_one(name: &'static str, value: usize) -> Result<usize, ConfigError> {
+    if value == 0 {
+        return Err(ConfigError::Invalid {
+            name,
+            value: value.to_string(),
+            reason: "must be at least 1".to_owned(),
+        });
+    }
+    Ok(value)
+}
+
+fn parse<T>(name: &'static str, value: String) -> Result<T, ConfigError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| ConfigError::Invalid {
+        name,
+        reason: format!("{e}"),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn from(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<_, _> = vars.iter().copied().collect();
+        Config::from_vars(|name| vars.get(name).map(ToString::to_string))
+    }
+
+    #[test]
+    fn defaults_to_plain_http_on_8080() {
+        let config = from(&[]).unwrap();
+        assert_eq!(config.listen, Listen::Tcp("0.0.0.0:8080".parse().unwrap()));
+        assert_eq!(config.tls, None);
+        assert_eq!(config.http, HttpConfig::default());
+        assert_eq!(config.log_format, LogFormat::Text);
+        assert_eq!(config.router, RouterConfig::default());
+    }
+
+    #[test]
+    fn reads_router_config() {
+        let config = from(&[
+            ("LATENCY_THRESHOLD_MS", "2500"),
+            ("ANALYZE_CACHE_CAPACITY", "0"),
+            ("SIMULATION_TIME_LIMIT_MS", "25000"),
+            ("MAX_RUNNING_JOBS", "16"),
+            ("BENCHMARK_ENDPOINT", "true"),
+            ("ENTROPY_SOURCE", "/dev/hwrng"),
+        ])
+        .unwrap();
+        assert!(config.router.benchmark_endpoint);
+        assert_eq!(config.router.latency_threshold, Duration::from_millis(2500));
+        assert_eq!(config.router.analyze_cache_capacity, 0);
+        assert_eq!(config.router.simulation_time_limit, Duration::from_secs(25));
+        assert_eq!(config.router.max_running_jobs, 16);
+        assert_eq!(
+            config.router.entropy_source,
+            Some(PathBuf::from("/dev/hwrng"))
+        );
+    }
+
+    #[test]
+    fn invalid_router_config_fails() {
+        assert!(matches!(
+            from(&[("MAX_RUNNING_JOBS", "0")]),
+            Err(ConfigError::Invalid {
+                name: "MAX_RUNNING_JOBS",
+                ..
+            })
+        ));
+        assert!(from(&[("MAX_RUNNING_JOBS", "-1")]).is_err());
+    }
+
+    #[cfg(feature = "demo")]
+    #[test]
+    fn reads_demo_rate() {
+        let config = from(&[("DEMO_EXCITING_RATE", "0.5")]).unwrap();
+        assert_eq!(config.router.demo_rate.map(|rate| rate.get()), Some(0.5));
+        assert!(from(&[("DEMO_EXCITING_RATE", "2")]).is_err());
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn reads_signing_key() {
+        let seed = "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=";
+        let config = from(&[("SIGNING_KEY", seed)]).unwrap();
+        assert_eq!(
+            config.router.signing_key,
+            Some(crate::signing::SigningKey::from_seed(&[7; 32]))
+        );
+        assert!(from(&[("SIGNING_KEY", "BwcH")]).is_err());
+    }
+
+    #[test]
+    fn reads_log_format() {
+        assert_eq!(
+            from(&[("LOG_FORMAT", "json")]).unwrap().log_format,
+            LogFormat::Json
+        );
+        assert!(from(&[("LOG_FORMAT", "xml")]).is_err());
+    }
+
+    #[test]
+    fn reads_http_tuning() {
+        let config = from(&[
+            ("HTTP2", "false"),
+            ("HTTP2_KEEP_ALIVE_INTERVAL_SECS", "30"),
+            ("HTTP2_MAX_CONCURRENT_STREAMS", "50"),
+            ("MAX_CONNECTIONS", "1000"),
+        ])
+        .unwrap();
+        assert!(!config.http.http2);
+        assert!(config.http.http1_keep_alive);
+        assert_eq!(
+            config.http.http2_keep_alive_interval,
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(config.http.http2_max_concurrent_streams, 50);
+        assert_eq!(config.http.max_connections, Some(1000));
+    }
+
+    #[test]
+    fn invalid_http_tuning_fails() {
+        assert!(from(&[("HTTP2", "yes")]).is_err());
+        assert!(from(&[("MAX_CONNECTIONS", "-1")]).is_err());
+        assert!(matches!(
+            from(&[("MAX_CONNECTIONS", "0")]),
+            Err(ConfigError::Invalid {
+                name: "MAX_CONNECTIONS",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn reads_address_and_tls_paths() {
+        let config = from(&[
+            ("BIND_ADDR", "127.0.0.1:8443"),
+            ("TLS_CERT_PATH", "/etc/poker/cert.pem"),
+            ("TLS_KEY_PATH", "/etc/poker/key.pem"),
+        ])
+        .unwrap();
+        assert_eq!(
+            config.listen,
+            Listen::Tcp("127.0.0.1:8443".parse().unwrap())
+        );
+        let tls = config.tls.unwrap();
+        assert_eq!(tls.cert_path, PathBuf::from("/etc/poker/cert.pem"));
+        assert_eq!(tls.key_path, PathBuf::from("/etc/poker/key.pem"));
+    }
+
+    #[test]
+    fn invalid_address_fails() {
+        assert!(from(&[("BIND_ADDR", "localhost")]).is_err());
+    }
+
+    #[test]
+    fn reads_unix_socket_path() {
+        let config = from(&[("BIND_UNIX", "/run/poker.sock")]).unwrap();
+        assert_eq!(config.listen, Listen::Unix("/run/poker.sock".into()));
+    }
+
+    #[test]
+    fn tcp_and_unix_conflict() {
+        assert!(from(&[
+            ("BIND_ADDR", "127.0.0.1:80"),
+            ("BIND_UNIX", "/run/poker.sock")
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn socket_activation_takes_precedence() {
+        let pid = process::id().to_string();
+        let config = from(&[
+            ("LISTEN_PID", &pid),
+            ("LISTEN_FDS", "1"),
+            ("BIND_ADDR", "127.0.0.1:80"),
+        ])
+        .unwrap();
+        assert_eq!(config.listen, Listen::Systemd);
+    }
+
+    #[test]
+    fn socket_activation_for_another_process_is_ignored() {
+        let pid = (process::id() + 1).to_string();
+        let config = from(&[("LISTEN_PID", &pid), ("LISTEN_FDS", "1")]).unwrap();
+        assert_eq!(config.listen, Listen::Tcp("0.0.0.0:8080".parse().unwrap()));
+    }
+
+    #[test]
+    fn certificate_without_key_fails() {
+        assert!(from(&[("TLS_CERT_PATH", "/etc/poker/cert.pem")]).is_err());
+        assert!(from(&[("TLS_KEY_PATH", "/etc/poker/key.pem")]).is_err());
+    }
+}
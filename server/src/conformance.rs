@@ -0,0 +1,132 @@
+//! A golden set of hands with their expected [`Strength`], for checking that an evaluator
+//! conforms to this crate's.
+//!
+//! The fixtures are published in `fixtures/conformance.json`, so evaluators and clients in other
+//! languages can check themselves against the same hands. Each fixture has a hand in the notation
+//! of [`crate::card`], its category, its tie-breaking ranks like [`Strength::tie_breakers`], and a
+//! description of what it checks, like the Ace playing low in a straight.
+
+use crate::{
+    card::Card,
+    compare::Strength,
+    evaluate::Evaluator,
+    hand::{Hand, HandCategory},
+};
+use serde::{Deserialize, Serialize};
+
+/// The fixtures as published, in JSON.
+pub const FIXTURES_JSON: &str = include_str!("../fixtures/conformance.json");
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Debug)]
+pub struct Fixture {
+    /// Five comma-separated cards, like `tr,jr,qr,kr,1r`.
+    pub hand: String,
+    pub category: HandCategory,
+    pub tie_breakers: Vec<u8>,
+    /// What the fixture checks.
+    pub description: String,
+}
+
+impl Fixture {
+    /// The [`Strength`] the hand is expected to have.
+    pub fn expected(&self) -> Strength {
+        Strength {
+            category: self.category,
+            tie_breakers: self.tie_breakers.clone(),
+        }
+    }
+}
+
+/// The result of evaluating all fixtures.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Report {
+    pub passed: usize,
+    pub failed: Vec<Failure>,
+}
+
+/// A fixture whose hand was evaluated differently than expected.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Failure {
+    pub fixture: Fixture,
+    pub actual: Strength,
+}
+
+/// All published fixtures.
+pub fn fixtures() -> Vec<Fixture> {
+    serde_json::from_str(FIXTURES_JSON).expect("the published fixtures are valid")
+}
+
+/// Evaluate the hand of every fixture with the `evaluator`, and report the ones which do not get
+/// their expected [`Strength`].
+pub fn run(evaluator: &impl Evaluator) -> Report {
+    let mut report = Report {
+        passed: 0,
+        failed: Vec::new(),
+    };
+    for fixture in fixtures() {
+        let hand: Hand = fixture
+            .hand
+            .parse()
+            .expect("the published fixtures are valid hands");
+        let cards: Vec<Card> = hand.cards().copied().collect();
+        let actual = evaluator.evaluate(&cards);
+        if actual == fixture.expected() {
+            report.passed += 1;
+        } else {
+            report.failed.push(Failure { fixture, actual });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluate::{BitCount, Naive};
+
+    #[test]
+    fn evaluators_conform() {
+        let total = fixtures().len();
+        assert_eq!(
+            run(&Naive),
+            Report {
+                passed: total,
+                failed: vec![]
+            }
+        );
+        assert_eq!(
+            run(&BitCount),
+            Report {
+                passed: total,
+                failed: vec![]
+            }
+        );
+    }
+
+    #[test]
+    fn every_category_is_covered() {
+        let fixtures = fixtures();
+        for category in HandCategory::ALL {
+            assert!(fixtures.iter().any(|fixture| fixture.category == category));
+        }
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        /// Evaluates every hand as the weakest possible.
+        struct Broken;
+
+        impl Evaluator for Broken {
+            fn evaluate(&self, _: &[Card]) -> Strength {
+                Strength {
+                    category: HandCategory::HighCard,
+                    tie_breakers: vec![7, 5, 4, 3, 2],
+                }
+            }
+        }
+
+        let report = run(&Broken);
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.failed.len(), fixtures().len() - 1);
+    }
+}
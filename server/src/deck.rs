@@ -1,47 +1,153 @@
 //! Model a deck of 52 playing cards, and enable drawing five random cards from it.
+//!
+//! For exact results instead of random draws, [`hands`], [`hole_cards`] and [`boards`] enumerate
+//! every combination of cards from the deck, without allocating for each one.
 
 use crate::card::{Card, Rank, Suit};
 use crate::hand::Hand;
 use lazy_static::lazy_static;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 /// Draw a [`Hand`] of five unique [`Card`]s.
 pub fn draw_hand() -> Hand {
+    draw_hand_with(&mut rand::thread_rng())
+}
+
+/// Draw a [`Hand`] of five unique [`Card`]s using the given random number generator.
+pub fn draw_hand_with<R: Rng + ?Sized>(rng: &mut R) -> Hand {
+    use rand::seq::SliceRandom;
+
+    let hand: Vec<_> = DECK.as_slice().choose_multiple(rng, 5).copied().collect();
+    Hand::try_from(hand.as_slice()).expect("we gave five unique cards")
+}
+
+/// Deal `count` [`Hand`]s from the same deck, so no card is in two hands.
+///
+/// # Panics
+///
+/// If `count` is more than 10, as the deck then runs out of cards.
+pub fn deal_hands_with<R: Rng + ?Sized>(count: usize, rng: &mut R) -> Vec<Hand> {
     use rand::seq::SliceRandom;
 
-    let mut rng = &mut rand::thread_rng();
-    let hand: Vec<_> = DECK
+    assert!(
+        count <= DECK.len() / 5,
+        "cannot deal {count} hands from one deck"
+    );
+    let cards: Vec<_> = DECK
         .as_slice()
-        .choose_multiple(&mut rng, 5)
+        .choose_multiple(rng, 5 * count)
         .copied()
         .collect();
-    Hand::try_from(hand.as_slice()).expect("we gave five unique cards")
+    cards
+        .chunks(5)
+        .map(|hand| Hand::try_from(hand).expect("we gave five unique cards"))
+        .collect()
+}
+
+/// Create a random number generator which always gives the same sequence of draws for the same
+/// seed.
+pub fn seeded_rng(seed: u64) -> ChaCha8Rng {
+    ChaCha8Rng::seed_from_u64(seed)
+}
+
+/// Returns the cards of the deck which are not among the `dead` cards.
+pub fn remaining_cards(dead: &[Card]) -> Vec<Card> {
+    DECK.iter()
+        .filter(|card| !dead.contains(card))
+        .copied()
+        .collect()
+}
+
+/// Every combination of `K` cards from the deck without some dead cards.
+///
+/// The combinations are in canonical order: each combination is in the order of the deck, by
+/// [`Card::index`], and the combinations are in lexicographic order of those indices. Each is an
+/// array, so no combination allocates.
+#[derive(Clone, Debug)]
+pub struct Combinations<const K: usize> {
+    cards: Vec<Card>,
+    /// The indices into `cards` of the next combination, or `None` once all are given.
+    next: Option<[usize; K]>,
+    remaining: usize,
+}
+
+impl<const K: usize> Combinations<K> {
+    /// All combinations of `K` cards which are not among the `dead` cards.
+    pub fn new(dead: &[Card]) -> Self {
+        let cards = remaining_cards(dead);
+        let next = (K <= cards.len()).then(|| std::array::from_fn(|i| i));
+        // There are none when more cards are asked for than remain.
+        let remaining = if next.is_some() {
+            (0..K).fold(1, |product, i| product * (cards.len() - i) / (i + 1))
+        } else {
+            0
+        };
+        Self {
+            cards,
+            next,
+            remaining,
+        }
+    }
+}
+
+impl<const K: usize> Iterator for Combinations<K> {
+    type Item = [Card; K];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let indices = self.next?;
+        let n = self.cards.len();
+        // Advance the last index which is not yet as far right as it can go, and put the ones
+        // after it right behind it.
+        self.next = (0..K).rev().find(|&i| indices[i] < n - K + i).map(|i| {
+            let mut next = indices;
+            next[i] += 1;
+            for j in i + 1..K {
+                next[j] = next[j - 1] + 1;
+            }
+            next
+        });
+        self.remaining -= 1;
+        Some(indices.map(|i| self.cards[i]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<const K: usize> ExactSizeIterator for Combinations<K> {}
+
+/// Every one of the 2,598,960 five-card [`Hand`]s, in the canonical order of [`Combinations`].
+pub fn hands() -> impl ExactSizeIterator<Item = Hand> {
+    Combinations::<5>::new(&[])
+        .map(|cards| Hand::try_from(cards.as_slice()).expect("combinations are unique cards"))
+}
+
+/// Every two hole cards of Texas hold'em which are not among the `dead` cards, which is all 1326
+/// without dead cards.
+pub fn hole_cards(dead: &[Card]) -> Combinations<2> {
+    Combinations::new(dead)
 }
 
-const ALL_SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
-
-const ALL_RANKS: [Rank; 13] = [
-    Rank::Ace,
-    Rank::Two,
-    Rank::Three,
-    Rank::Four,
-    Rank::Five,
-    Rank::Six,
-    Rank::Seven,
-    Rank::Eight,
-    Rank::Nine,
-    Rank::Ten,
-    Rank::Jack,
-    Rank::Queen,
-    Rank::King,
-];
+/// Every board of `N` cards, like 3 for the flop or 5 for the river, which is not among the `dead`
+/// cards.
+pub fn boards<const N: usize>(dead: &[Card]) -> Combinations<N> {
+    Combinations::new(dead)
+}
+
+/// Build the deck now, instead of on the first draw, e.g., during the init phase of a lambda.
+pub fn initialize() {
+    lazy_static::initialize(&DECK);
+}
 
 lazy_static! {
     static ref DECK: Vec<Card> = {
         use itertools::Itertools;
-        ALL_SUITS
+        Suit::ALL
             .iter()
             .copied()
-            .cartesian_product(ALL_RANKS.iter().copied())
+            .cartesian_product(Rank::ALL.iter().copied())
             .map(|(suit, rank)| Card::new(rank, suit))
             .collect()
     };
@@ -49,7 +155,10 @@ lazy_static! {
 
 #[cfg(test)]
 mod tests {
-    use crate::deck::draw_hand;
+    use crate::deck::{
+        boards, deal_hands_with, draw_hand, draw_hand_with, hands, hole_cards, remaining_cards,
+        seeded_rng, Combinations,
+    };
 
     #[test]
     pub fn can_draw() {
@@ -57,4 +166,67 @@ mod tests {
             draw_hand();
         }
     }
+
+    #[test]
+    pub fn same_seed_draws_same_hands() {
+        let mut a = seeded_rng(42);
+        let mut b = seeded_rng(42);
+        for _ in 0..100 {
+            assert_eq!(draw_hand_with(&mut a), draw_hand_with(&mut b));
+        }
+    }
+
+    #[test]
+    pub fn remaining_cards_excludes_dead_cards() {
+        let dead = ["1s".parse().unwrap(), "kh".parse().unwrap()];
+        let remaining = remaining_cards(&dead);
+        assert_eq!(remaining.len(), 50);
+        assert!(!remaining.contains(&dead[0]));
+        assert!(!remaining.contains(&dead[1]));
+    }
+
+    #[test]
+    pub fn dealt_hands_share_no_cards() {
+        let hands = deal_hands_with(10, &mut seeded_rng(7));
+        let mut cards: Vec<_> = hands.iter().flat_map(|hand| hand.cards()).collect();
+        cards.sort();
+        cards.dedup();
+        assert_eq!(cards.len(), 50);
+    }
+
+    #[test]
+    pub fn combinations_are_counted_and_in_order() {
+        assert_eq!(hands().len(), 2_598_960);
+        let holes: Vec<_> = hole_cards(&[]).collect();
+        assert_eq!(holes.len(), 1326);
+        assert!(holes
+            .windows(2)
+            .all(|w| w[0].map(|c| c.index()) < w[1].map(|c| c.index())));
+        assert!(holes.iter().all(|[a, b]| a.index() < b.index()));
+        assert_eq!(Combinations::<0>::new(&[]).count(), 1);
+        assert_eq!(Combinations::<53>::new(&[]).count(), 0);
+    }
+
+    #[test]
+    pub fn no_combinations_have_more_cards_than_remain() {
+        let deck = remaining_cards(&[]);
+        let dead = &deck[..49];
+        assert_eq!(boards::<5>(dead).len(), 0);
+        assert_eq!(boards::<5>(dead).next(), None);
+        assert_eq!(boards::<5>(&deck).len(), 0);
+        assert_eq!(boards::<3>(dead).len(), 1);
+    }
+
+    #[test]
+    pub fn boards_leave_out_dead_cards() {
+        let dead = ["1s".parse().unwrap(), "kh".parse().unwrap()];
+        let flops = boards::<3>(&dead);
+        assert_eq!(flops.len(), 19_600);
+        let mut counted = 0;
+        for flop in flops {
+            assert!(!flop.iter().any(|card| dead.contains(card)));
+            counted += 1;
+        }
+        assert_eq!(counted, 19_600);
+    }
 }
@@ -0,0 +1,96 @@
+//! A demo deck source which deals exciting hands, flushes and better, more often than a fair deck,
+//! for kiosk demos which would otherwise show high card half of the time.
+//!
+//! With the `demo` feature and `DEMO_EXCITING_RATE` set, see [`crate::config`], `/draw` and
+//! `/replay/:seed` draw from this source, and label their responses with the rate. Hands drawn this
+//! way are not uniformly random, so the feature must never be enabled where fairness matters.
+
+use crate::{
+    classify::classify,
+    deck::draw_hand_with,
+    hand::{Hand, HandCategory},
+};
+use rand::Rng;
+use std::str::FromStr;
+
+/// The weakest category which counts as exciting.
+pub const EXCITING: HandCategory = HandCategory::Flush;
+
+/// The share of draws, from 0 to 1, which are made exciting.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DemoRate(f64);
+
+#[derive(thiserror::Error, Debug)]
+#[error("expected a rate from 0 to 1")]
+pub struct InvalidDemoRate;
+
+impl DemoRate {
+    pub fn new(rate: f64) -> Result<Self, InvalidDemoRate> {
+        if (0.0..=1.0).contains(&rate) {
+            Ok(Self(rate))
+        } else {
+            Err(InvalidDemoRate)
+        }
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl FromStr for DemoRate {
+    type Err = InvalidDemoRate;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.parse().map_err(|_| InvalidDemoRate)?)
+    }
+}
+
+/// Draw a hand which, with probability `rate`, is exciting, and otherwise is drawn from a fair
+/// deck. Exciting hands are drawn from a fair deck until one comes up, so they keep their relative
+/// frequencies: most are flushes and full houses.
+pub fn draw_demo_hand_with<R: Rng + ?Sized>(rng: &mut R, rate: DemoRate) -> Hand {
+    if !rng.gen_bool(rate.get()) {
+        return draw_hand_with(rng);
+    }
+    loop {
+        let hand = draw_hand_with(rng);
+        if classify(&hand) >= EXCITING {
+            return hand;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::seeded_rng;
+
+    #[test]
+    fn full_rate_draws_only_exciting_hands() {
+        let mut rng = seeded_rng(180);
+        let rate = DemoRate::new(1.0).unwrap();
+        for _ in 0..100 {
+            assert!(classify(&draw_demo_hand_with(&mut rng, rate)) >= EXCITING);
+        }
+    }
+
+    #[test]
+    fn zero_rate_draws_like_a_fair_deck() {
+        let rate = DemoRate::new(0.0).unwrap();
+        let (mut a, mut b) = (seeded_rng(180), seeded_rng(180));
+        for _ in 0..100 {
+            // Deciding against an exciting hand takes one draw of the generator first.
+            let _ = b.gen_bool(0.0);
+            assert_eq!(draw_demo_hand_with(&mut a, rate), draw_hand_with(&mut b));
+        }
+    }
+
+    #[test]
+    fn rate_must_be_a_share() {
+        assert!("0.25".parse::<DemoRate>().is_ok());
+        assert!("1.5".parse::<DemoRate>().is_err());
+        assert!("-0.1".parse::<DemoRate>().is_err());
+        assert!("many".parse::<DemoRate>().is_err());
+    }
+}
@@ -0,0 +1,242 @@
+//! External entropy sources, for deployments which must draw from a certified or audited source,
+//! like a hardware random number generator.
+//!
+//! An [`EntropySource`] gives random numbers which may fail, or take long, unlike a
+//! [`Randomness`]. [`FallbackRandomness`] makes one a [`Randomness`] by reading it on a background
+//! thread into a buffer, off the path of requests, and checking the health of each number. Requests
+//! take numbers from the buffer, and from a fallback [`Randomness`] whenever it is empty, like when
+//! the source is slow, blocked or unhealthy. A source is unhealthy when reading fails, or when it
+//! gives the same number twice in a row, which a working source of 64-bit numbers practically never
+//! does, like the repetition count test of NIST SP 800-90B. The source is tried again
+//! [`RETRY_AFTER`] later.
+//!
+//! [`FileEntropy`] reads numbers from a device or a stream, like `/dev/hwrng`, or a named pipe fed
+//! by a client of a randomness beacon. The server uses it when `ENTROPY_SOURCE` is set, see
+//! [`crate::config`], and exports [`FallbackRandomness::is_healthy`] as a gauge of
+//! [`crate::metrics`].
+
+use crate::environment::Randomness;
+use std::{
+    fmt::Debug,
+    fs::File,
+    io::{self, Read},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use tracing::{info, warn};
+
+/// How long an unhealthy source is left alone before it is tried again.
+pub const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// How many numbers of the source are read ahead.
+const BUFFER: usize = 1024;
+
+/// Something which gives random numbers, or fails to.
+pub trait EntropySource: Send + Debug {
+    fn try_next_u64(&mut self) -> io::Result<u64>;
+}
+
+/// Random numbers read from a device or a stream, 8 bytes at a time.
+///
+/// The file is kept open, and opened again after an error, so a source which was restarted is
+/// picked up. A regular file is not a source: it would give the same numbers each time it is
+/// opened again.
+#[derive(Debug)]
+pub struct FileEntropy {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl FileEntropy {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: None,
+        }
+    }
+}
+
+impl EntropySource for FileEntropy {
+    fn try_next_u64(&mut self) -> io::Result<u64> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => self.file.insert(File::open(&self.path)?),
+        };
+        let mut bytes = [0; 8];
+        if let Err(e) = file.read_exact(&mut bytes) {
+            self.file = None;
+            return Err(e);
+        }
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
+
+/// The numbers of an [`EntropySource`], read ahead on a background thread, or of the `fallback`
+/// while none are read.
+#[derive(Debug)]
+pub struct FallbackRandomness {
+    numbers: Mutex<Receiver<u64>>,
+    fallback: Arc<dyn Randomness>,
+    /// Whether the last number came from the source.
+    healthy: AtomicBool,
+}
+
+impl FallbackRandomness {
+    /// Start reading the source on a background thread, which stops at the next number read after
+    /// this is dropped.
+    pub fn new(source: Box<dyn EntropySource>, fallback: Arc<dyn Randomness>) -> Self {
+        Self::with_retry_after(source, fallback, RETRY_AFTER)
+    }
+
+    fn with_retry_after(
+        source: Box<dyn EntropySource>,
+        fallback: Arc<dyn Randomness>,
+        retry_after: Duration,
+    ) -> Self {
+        let (sender, numbers) = mpsc::sync_channel(BUFFER);
+        thread::Builder::new()
+            .name("entropy".to_owned())
+            .spawn(move || read(source, &sender, retry_after))
+            .expect("a thread can be spawned");
+        Self {
+            numbers: Mutex::new(numbers),
+            fallback,
+            healthy: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the last number came from the source, and not from the fallback.
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    /// The next number read from the source, if there is one.
+    fn try_source(&self) -> Option<u64> {
+        self.numbers
+            .lock()
+            .expect("receiving never panics")
+            .try_recv()
+            .ok()
+    }
+}
+
+impl Randomness for FallbackRandomness {
+    fn next_u64(&self) -> u64 {
+        let number = self.try_source();
+        self.healthy.store(number.is_some(), Ordering::Relaxed);
+        number.unwrap_or_else(|| self.fallback.next_u64())
+    }
+}
+
+/// Read the healthy numbers of the source into the `sender`, until its receiver is dropped.
+fn read(mut source: Box<dyn EntropySource>, sender: &SyncSender<u64>, retry_after: Duration) {
+    let mut last = None;
+    let mut healthy = true;
+    loop {
+        let failure = match source.try_next_u64() {
+            Ok(number) if last == Some(number) => {
+                io::Error::new(io::ErrorKind::InvalidData, "the same number came twice")
+            }
+            Ok(number) => {
+                if !healthy {
+                    info!(?source, "entropy source is healthy again");
+                    healthy = true;
+                }
+                last = Some(number);
+                if sender.send(number).is_err() {
+                    return;
+                }
+                continue;
+            }
+            Err(e) => e,
+        };
+        if healthy {
+            warn!(?source, %failure, "entropy source is unhealthy, falling back");
+            healthy = false;
+        }
+        last = None;
+        thread::sleep(retry_after);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::SeededRandomness;
+    use std::time::Instant;
+
+    /// A source giving the numbers sent by the test, waiting for each, and failing once the test
+    /// stops sending.
+    #[derive(Debug)]
+    struct Numbers(Receiver<io::Result<u64>>);
+
+    impl EntropySource for Numbers {
+        fn try_next_u64(&mut self) -> io::Result<u64> {
+            self.0
+                .recv()
+                .unwrap_or_else(|_| Err(io::ErrorKind::UnexpectedEof.into()))
+        }
+    }
+
+    /// The next number read from the source, waiting at most a second for it.
+    fn wait_for_source(randomness: &FallbackRandomness) -> Option<u64> {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(1) {
+            if let Some(number) = randomness.try_source() {
+                return Some(number);
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        None
+    }
+
+    #[test]
+    fn falls_back_while_the_source_is_slow_or_unhealthy() {
+        let (send, numbers) = mpsc::channel();
+        let randomness = FallbackRandomness::with_retry_after(
+            Box::new(Numbers(numbers)),
+            Arc::new(SeededRandomness::new(230)),
+            Duration::from_millis(10),
+        );
+        // The source has given nothing yet, so requests do not wait for it.
+        assert_eq!(randomness.next_u64(), SeededRandomness::new(230).next_u64());
+        assert!(!randomness.is_healthy());
+
+        for number in [1, 2, 2, 3] {
+            send.send(Ok(number)).unwrap();
+        }
+        // The repeated Two is rejected.
+        assert_eq!(wait_for_source(&randomness), Some(1));
+        assert_eq!(wait_for_source(&randomness), Some(2));
+        assert_eq!(wait_for_source(&randomness), Some(3));
+        send.send(Ok(4)).unwrap();
+        assert_eq!(wait_for_source(&randomness), Some(4));
+        send.send(Ok(5)).unwrap();
+        while randomness.next_u64() != 5 {}
+        assert!(randomness.is_healthy());
+    }
+
+    #[test]
+    fn files_are_read_eight_bytes_at_a_time() {
+        let path = std::env::temp_dir().join(format!("entropy-{}", std::process::id()));
+        let bytes: Vec<u8> = [230_u64, 231]
+            .iter()
+            .flat_map(|n| n.to_le_bytes())
+            .collect();
+        std::fs::write(&path, bytes).unwrap();
+        let mut source = FileEntropy::new(&path);
+        assert_eq!(source.try_next_u64().unwrap(), 230);
+        assert_eq!(source.try_next_u64().unwrap(), 231);
+        assert!(source.try_next_u64().is_err());
+        // A regular file is read from the start again, which is why it is not a source.
+        assert_eq!(source.try_next_u64().unwrap(), 230);
+        std::fs::remove_file(&path).unwrap();
+        assert!(FileEntropy::new(&path).try_next_u64().is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Sources of time and randomness, behind the [`Clock`] and [`Randomness`] traits.
+//!
+//! The router, the quiz and the request metrics take their time and randomness from these, so
+//! tests can swap the system clock and random number generator for a [`ManualClock`] and a
+//! [`SeededRandomness`], which make deals, tokens and latencies deterministic.
+
+use crate::deck::seeded_rng;
+use rand::RngCore;
+use rand_chacha::ChaCha8Rng;
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+/// Something which tells the time.
+pub trait Clock: Send + Sync + Debug {
+    fn now(&self) -> Instant;
+
+    /// The time since the Unix epoch, which, unlike [`Clock::now`], machines agree on.
+    fn since_epoch(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// Something which gives random numbers.
+pub trait Randomness: Send + Sync + Debug {
+    fn next_u64(&self) -> u64;
+
+    /// A random number generator seeded from the next random number, e.g., for shuffling a deck.
+    fn rng(&self) -> ChaCha8Rng {
+        seeded_rng(self.next_u64())
+    }
+
+    /// A random ID of 16 hex digits.
+    fn id(&self) -> String {
+        format!("{:016x}", self.next_u64())
+    }
+}
+
+/// The clock of the operating system.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock which stands still until it is advanced.
+#[derive(Debug)]
+pub struct ManualClock {
+    start: Instant,
+    /// The time since the Unix epoch at the start.
+    epoch: Duration,
+    elapsed: Mutex<Duration>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::starting_at(SystemClock.since_epoch())
+    }
+}
+
+impl ManualClock {
+    /// A clock which starts at the time `since_epoch` after the Unix epoch.
+    pub fn starting_at(since_epoch: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            epoch: since_epoch,
+            elapsed: Mutex::default(),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().expect("the clock never panics") += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().expect("the clock never panics")
+    }
+
+    fn since_epoch(&self) -> Duration {
+        self.epoch + *self.elapsed.lock().expect("the clock never panics")
+    }
+}
+
+/// The thread-local random number generator of [`rand`], seeded by the operating system.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ThreadRandomness;
+
+impl Randomness for ThreadRandomness {
+    fn next_u64(&self) -> u64 {
+        rand::random()
+    }
+}
+
+/// Random numbers which are always the same for the same seed.
+#[derive(Debug)]
+pub struct SeededRandomness(Mutex<ChaCha8Rng>);
+
+impl SeededRandomness {
+    pub fn new(seed: u64) -> Self {
+        Self(Mutex::new(seeded_rng(seed)))
+    }
+}
+
+impl Randomness for SeededRandomness {
+    fn next_u64(&self) -> u64 {
+        self.0
+            .lock()
+            .expect("the generator never panics")
+            .next_u64()
+    }
+}
+
+/// The [`Clock`] and [`Randomness`] of a router. The default is the system's.
+#[derive(Clone, Debug)]
+pub struct Environment {
+    pub clock: Arc<dyn Clock>,
+    pub randomness: Arc<dyn Randomness>,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self {
+            clock: Arc::new(SystemClock),
+            randomness: Arc::new(ThreadRandomness),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_moves_when_advanced() {
+        let clock = ManualClock::default();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::from_millis(250));
+        assert_eq!(clock.now() - start, Duration::from_millis(250));
+
+        let clock = ManualClock::starting_at(Duration::from_secs(3600));
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(clock.since_epoch(), Duration::from_secs(3601));
+    }
+
+    #[test]
+    fn same_seed_gives_same_numbers() {
+        let a = SeededRandomness::new(168);
+        let b = SeededRandomness::new(168);
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert_eq!(a.id(), b.id());
+        assert_ne!(a.next_u64(), a.next_u64());
+    }
+}
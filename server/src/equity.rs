@@ -0,0 +1,127 @@
+//! Estimate the [`Equity`] of one partial hand against another, by dealing the missing cards from
+//! the rest of the deck many times and comparing the completed hands.
+
+use crate::{card::Card, compare::compare, deck::remaining_cards, hand::Hand};
+use rand::{seq::SliceRandom, Rng};
+use serde::Serialize;
+use std::cmp::Ordering;
+
+/// The share of deals which the first hand won, tied, and lost.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
+pub struct Equity {
+    pub win: f64,
+    pub tie: f64,
+    pub lose: f64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum EquityError {
+    #[error("a hand has at most 5 cards, got {0}")]
+    TooManyCards(usize),
+    #[error("{0} is given more than once")]
+    DuplicateCard(Card),
+    #[error("at least one iteration is needed")]
+    NoIterations,
+}
+
+/// Estimate the [`Equity`] of the partial hand `a` against the partial hand `b`, using a Monte Carlo
+/// simulation with the given number of iterations.
+///
+/// Each partial hand has up to five cards, and the two hands cannot share any cards.
+pub fn equity<R: Rng + ?Sized>(
+    a: &[Card],
+    b: &[Card],
+    iterations: u64,
+    rng: &mut R,
+) -> Result<Equity, EquityError> {
+    for hand in [a, b] {
+        if hand.len() > 5 {
+            return Err(EquityError::TooManyCards(hand.len()));
+        }
+    }
+    let known: Vec<_> = a.iter().chain(b).copied().collect();
+    for (i, card) in known.iter().enumerate() {
+        if known[..i].contains(card) {
+            return Err(EquityError::DuplicateCard(*card));
+        }
+    }
+    if iterations == 0 {
+        return Err(EquityError::NoIterations);
+    }
+
+    let deck = remaining_cards(&known);
+    let (mut wins, mut ties) = (0, 0);
+    for _ in 0..iterations {
+        let mut dealt = deck.choose_multiple(rng, 10 - known.len()).copied();
+        let a = complete(a, &mut dealt);
+        let b = complete(b, &mut dealt);
+        match compare(&a, &b) {
+            Ordering::Greater => wins += 1,
+            Ordering::Equal => ties += 1,
+            Ordering::Less => {}
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let share = |count: u64| count as f64 / iterations as f64;
+    Ok(Equity {
+        win: share(wins),
+        tie: share(ties),
+        lose: share(iterations - wins - ties),
+    })
+}
+
+/// Fill up the partial hand with cards from `dealt`.
+fn complete(partial: &[Card], dealt: &mut impl Iterator<Item = Card>) -> Hand {
+    let cards: Vec<_> = partial
+        .iter()
+        .copied()
+        .chain(dealt.take(5 - partial.len()))
+        .collect();
+    Hand::try_from(cards.as_slice()).expect("dealt cards are unique and fill up the hand")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::seeded_rng;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn complete_hands_have_certain_outcome() {
+        let equity = equity(
+            &cards("1r,1s,1k,1h,2r"),
+            &cards("kr,ks,kk,kh,2s"),
+            100,
+            &mut seeded_rng(0),
+        )
+        .unwrap();
+        assert_eq!(equity.win, 1.0);
+        assert_eq!(equity.tie, 0.0);
+        assert_eq!(equity.lose, 0.0);
+    }
+
+    #[test]
+    fn empty_hands_are_even() {
+        let equity = equity(&[], &[], 10_000, &mut seeded_rng(0)).unwrap();
+        assert!((equity.win - equity.lose).abs() < 0.05);
+    }
+
+    #[test]
+    fn shares_sum_to_one() {
+        let equity = equity(&cards("1r,1s"), &cards("kr"), 1000, &mut seeded_rng(0)).unwrap();
+        assert!((equity.win + equity.tie + equity.lose - 1.0).abs() < 1e-9);
+        assert!(equity.win > equity.lose);
+    }
+
+    #[test]
+    fn invalid_input_fails() {
+        let mut rng = seeded_rng(0);
+        assert!(equity(&cards("1r,2r,3r,4r,5r,6r"), &[], 1, &mut rng).is_err());
+        assert!(equity(&cards("1r"), &cards("1r"), 1, &mut rng).is_err());
+        assert!(equity(&[], &[], 0, &mut rng).is_err());
+    }
+}
@@ -0,0 +1,180 @@
+//! Evaluate the [`Strength`] of the best five-card hand among five to seven cards, like the seven
+//! cards of Texas hold'em.
+//!
+//! Evaluators implement the [`Evaluator`] trait. The [`Naive`] evaluator compares every five-card
+//! hand among the cards, which is simple and obviously right. The [`BitCount`] evaluator finds the
+//! best hand directly from bit masks of the ranks and counts of each rank and suit, and is the one
+//! to use where speed matters.
+
+use crate::{
+    best::best_hand,
+    card::Card,
+    compare::{strength, Strength},
+    hand::HandCategory,
+};
+
+/// Something which evaluates the best five-card hand among five to seven cards.
+pub trait Evaluator {
+    /// The [`Strength`] of the best five-card hand among the `cards`.
+    ///
+    /// # Panics
+    ///
+    /// If there are not five to seven unique cards.
+    fn evaluate(&self, cards: &[Card]) -> Strength;
+}
+
+/// Evaluates every five-card hand among the cards, and picks the strongest.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Naive;
+
+impl Evaluator for Naive {
+    fn evaluate(&self, cards: &[Card]) -> Strength {
+        assert!((5..=7).contains(&cards.len()), "need five to seven cards");
+        strength(&best_hand(cards).expect("five unique cards are given"))
+    }
+}
+
+/// Evaluates the cards from bit masks of their ranks, without going through every five-card hand.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BitCount;
+
+/// The bit of a rank in a rank mask, counted with the Ace as 14.
+const fn bit(rank: u8) -> u16 {
+    1 << rank
+}
+
+/// The Ace as the highest rank, which also plays as 1 in the lowest straight.
+const ACE: u8 = 14;
+
+impl Evaluator for BitCount {
+    fn evaluate(&self, cards: &[Card]) -> Strength {
+        use HandCategory::*;
+
+        assert!((5..=7).contains(&cards.len()), "need five to seven cards");
+        let mut suit_masks = [0u16; 4];
+        let mut rank_counts = [0u8; ACE as usize + 1];
+        for card in cards {
+            let rank = card.rank.numeric_ace_high();
+            assert!(
+                suit_masks[card.suit as usize] & bit(rank) == 0,
+                "{card} is given more than once"
+            );
+            suit_masks[card.suit as usize] |= bit(rank);
+            rank_counts[usize::from(rank)] += 1;
+        }
+        let rank_mask = suit_masks.iter().fold(0, |mask, suit| mask | suit);
+        // Ranks with at least `count` cards, from the highest.
+        let ranks_with = |count: u8| {
+            (2..=ACE)
+                .rev()
+                .filter(move |&rank| rank_counts[usize::from(rank)] >= count)
+        };
+        // The highest ranks, except the ones already used.
+        let kickers = |used: &[u8], take: usize| -> Vec<u8> {
+            ranks_with(1)
+                .filter(|rank| !used.contains(rank))
+                .take(take)
+                .collect()
+        };
+        let with = |category, tie_breakers| Strength {
+            category,
+            tie_breakers,
+        };
+
+        let flush = suit_masks.iter().find(|mask| mask.count_ones() >= 5);
+        if let Some(&flush) = flush {
+            if let Some(top) = straight(flush) {
+                return with(StraightFlush, vec![top]);
+            }
+        }
+        if let Some(quads) = ranks_with(4).next() {
+            return with(FourOfAKind, [vec![quads], kickers(&[quads], 1)].concat());
+        }
+        if let Some(trips) = ranks_with(3).next() {
+            if let Some(pair) = ranks_with(2).find(|&rank| rank != trips) {
+                return with(FullHouse, vec![trips, pair]);
+            }
+        }
+        if let Some(&flush) = flush {
+            let ranks = (2..=ACE)
+                .rev()
+                .filter(|&rank| flush & bit(rank) != 0)
+                .take(5)
+                .collect();
+            return with(Flush, ranks);
+        }
+        if let Some(top) = straight(rank_mask) {
+            return with(Straight, vec![top]);
+        }
+        if let Some(trips) = ranks_with(3).next() {
+            return with(ThreeOfAKind, [vec![trips], kickers(&[trips], 2)].concat());
+        }
+        let pairs: Vec<_> = ranks_with(2).take(2).collect();
+        match pairs[..] {
+            [high, low] => with(
+                TwoPair,
+                [vec![high, low], kickers(&[high, low], 1)].concat(),
+            ),
+            [pair] => with(OnePair, [vec![pair], kickers(&[pair], 3)].concat()),
+            _ => with(HighCard, kickers(&[], 5)),
+        }
+    }
+}
+
+/// The top rank of the highest straight in the rank mask, if there is one.
+fn straight(mask: u16) -> Option<u8> {
+    // The Ace also plays as 1, below the Two.
+    let mask = mask | u16::from(mask & bit(ACE) != 0) << 1;
+    let five = 0b11111;
+    (5..=ACE)
+        .rev()
+        .find(|&top| mask >> (top - 4) & five == five)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::{remaining_cards, seeded_rng};
+    use rand::seq::SliceRandom;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn bit_count_agrees_with_naive() {
+        let deck = remaining_cards(&[]);
+        let mut rng = seeded_rng(162);
+        for count in 5..=7 {
+            for _ in 0..20_000 {
+                let cards: Vec<_> = deck.choose_multiple(&mut rng, count).copied().collect();
+                assert_eq!(
+                    BitCount.evaluate(&cards),
+                    Naive.evaluate(&cards),
+                    "{cards:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn finds_straight_flush_over_flush_and_straight() {
+        let strength = BitCount.evaluate(&cards("2s,3s,4s,5s,1s,6h,ks"));
+        assert_eq!(strength.category, HandCategory::StraightFlush);
+        assert_eq!(strength.tie_breakers, [5]);
+    }
+
+    #[test]
+    fn full_house_from_two_trips() {
+        let strength = BitCount.evaluate(&cards("2s,2h,2r,ks,kh,kr,1s"));
+        assert_eq!(strength.category, HandCategory::FullHouse);
+        assert_eq!(strength.tie_breakers, [13, 2]);
+    }
+
+    #[test]
+    fn third_pair_can_be_the_kicker() {
+        let strength = BitCount.evaluate(&cards("2s,2h,5r,5s,kh,kr,3s"));
+        assert_eq!(strength.category, HandCategory::TwoPair);
+        assert_eq!(strength.tie_breakers, [13, 5, 3]);
+    }
+}
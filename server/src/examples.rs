@@ -0,0 +1,84 @@
+//! Example requests with their expected responses, for developer portals and client tests.
+//!
+//! The examples are generated from the [conformance fixtures](crate::conformance), so they cover
+//! the same hands, from the royal flush to the Ace playing low, and are always in step with the
+//! published fixtures. Each fixture gives an example of every endpoint whose response is decided
+//! by the cards of the request alone. Endpoints whose responses are random, like `/draw`, or
+//! depend on earlier requests, like `/quiz/:token/answer`, have no examples.
+
+use crate::{classify::classify, conformance::fixtures, hand::Hand, percentile::percentile};
+use serde::Serialize;
+use serde_json::Value;
+
+/// A request and the response it gets.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Example {
+    /// The route of the request, like `/analyze/:cards`.
+    pub route: &'static str,
+    pub method: &'static str,
+    /// The path and query of the request.
+    pub path: String,
+    /// What the example shows, from its fixture.
+    pub description: String,
+    pub status: u16,
+    /// The JSON body of the response.
+    pub response: Value,
+}
+
+/// The examples of every fixture, in the order of the fixtures.
+pub fn examples() -> Vec<Example> {
+    fixtures()
+        .into_iter()
+        .flat_map(|fixture| {
+            let hand: Hand = fixture
+                .hand
+                .parse()
+                .expect("the published fixtures are valid hands");
+            let category = serde_json::to_value(classify(&hand)).expect("no known fail modes");
+            let query = fixture
+                .hand
+                .split(',')
+                .map(|card| format!("card={card}"))
+                .collect::<Vec<_>>()
+                .join("&");
+            let example = |route, path, response| Example {
+                route,
+                method: "GET",
+                path,
+                description: fixture.description.clone(),
+                status: 200,
+                response,
+            };
+            [
+                example(
+                    "/analyze/:cards",
+                    format!("/analyze/{}", fixture.hand),
+                    category.clone(),
+                ),
+                example("/analyze", format!("/analyze?{query}"), category),
+                example(
+                    "/percentile/:cards",
+                    format!("/percentile/{}", fixture.hand),
+                    serde_json::to_value(percentile(&hand)).expect("no known fail modes"),
+                ),
+            ]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_fixture_has_examples() {
+        let examples = examples();
+        assert_eq!(examples.len(), 3 * fixtures().len());
+        assert_eq!(examples[0].path, "/analyze/tr,jr,qr,kr,1r");
+        assert_eq!(examples[0].response, "StraightFlush");
+        assert_eq!(
+            examples[1].path,
+            "/analyze?card=tr&card=jr&card=qr&card=kr&card=1r"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! A C interface to the evaluator, enabled by the `ffi` feature.
+//!
+//! Cards cross the boundary as their [`Card::index`], and a hand as a [`PokerHand`] of five such
+//! indices. Every function returns a [`PokerStatus`] and writes its result through an out pointer,
+//! which is left untouched unless [`PokerStatus::Ok`] is returned.
+//!
+//! The C header is generated with [cbindgen](https://github.com/mozilla/cbindgen) into
+//! `include/case_poker.h`:
+//!
+//! ```shell
+//! cbindgen --config cbindgen.toml --output include/case_poker.h
+//! ```
+
+use crate::{
+    card::Card,
+    classify::classify,
+    compare::compare,
+    hand::{Hand, HandCategory},
+};
+use std::{cmp::Ordering, ffi::CStr, os::raw::c_char};
+
+/// Outcome of a call into the library.
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PokerStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    InvalidCard = 3,
+    InvalidHand = 4,
+}
+
+/// The category of a hand, mirroring [`HandCategory`].
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum PokerCategory {
+    HighCard = 0,
+    OnePair = 1,
+    TwoPair = 2,
+    ThreeOfAKind = 3,
+    Straight = 4,
+    Flush = 5,
+    FullHouse = 6,
+    FourOfAKind = 7,
+    StraightFlush = 8,
+}
+
+impl From<HandCategory> for PokerCategory {
+    fn from(category: HandCategory) -> Self {
+        match category {
+            HandCategory::HighCard => PokerCategory::HighCard,
+            HandCategory::OnePair => PokerCategory::OnePair,
+            HandCategory::TwoPair => PokerCategory::TwoPair,
+            HandCategory::ThreeOfAKind => PokerCategory::ThreeOfAKind,
+            HandCategory::Straight => PokerCategory::Straight,
+            HandCategory::Flush => PokerCategory::Flush,
+            HandCategory::FullHouse => PokerCategory::FullHouse,
+            HandCategory::FourOfAKind => PokerCategory::FourOfAKind,
+            HandCategory::StraightFlush => PokerCategory::StraightFlush,
+        }
+    }
+}
+
+/// Five cards, each given by its index in `0..52`. See [`Card::index`].
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct PokerHand {
+    pub cards: [u8; 5],
+}
+
+impl TryFrom<&PokerHand> for Hand {
+    type Error = PokerStatus;
+
+    fn try_from(value: &PokerHand) -> Result<Self, Self::Error> {
+        let cards = value
+            .cards
+            .iter()
+            .map(|&index| Card::from_index(index).ok_or(PokerStatus::InvalidCard))
+            .collect::<Result<Vec<_>, _>>()?;
+        Hand::try_from(cards.as_slice()).map_err(|_| PokerStatus::InvalidHand)
+    }
+}
+
+/// Parse a comma-separated list of five cards, like `"tr,jr,qr,kr,1r"`, into `out`.
+///
+/// # Safety
+///
+/// `cards` must be null or point to a nul-terminated string, and `out` must be null or point to
+/// memory valid for writing a [`PokerHand`].
+#[no_mangle]
+pub unsafe extern "C" fn poker_parse_hand(
+    cards: *const c_char,
+    out: *mut PokerHand,
+) -> PokerStatus {
+    if cards.is_null() || out.is_null() {
+        return PokerStatus::NullPointer;
+    }
+    let Ok(cards) = CStr::from_ptr(cards).to_str() else {
+        return PokerStatus::InvalidUtf8;
+    };
+    let Ok(cards) = cards
+        .split(',')
+        .map(str::parse::<Card>)
+        .collect::<Result<Vec<_>, _>>()
+    else {
+        return PokerStatus::InvalidCard;
+    };
+    if Hand::try_from(cards.as_slice()).is_err() {
+        return PokerStatus::InvalidHand;
+    }
+    let mut hand = PokerHand { cards: [0; 5] };
+    for (index, card) in hand.cards.iter_mut().zip(cards) {
+        *index = card.index();
+    }
+    out.write(hand);
+    PokerStatus::Ok
+}
+
+/// Classify `hand` and write its category into `out`.
+///
+/// # Safety
+///
+/// `hand` must be null or point to a valid [`PokerHand`], and `out` must be null or point to
+/// memory valid for writing a [`PokerCategory`].
+#[no_mangle]
+pub unsafe extern "C" fn poker_evaluate(
+    hand: *const PokerHand,
+    out: *mut PokerCategory,
+) -> PokerStatus {
+    if hand.is_null() || out.is_null() {
+        return PokerStatus::NullPointer;
+    }
+    match Hand::try_from(&*hand) {
+        Ok(hand) => {
+            out.write(classify(&hand).into());
+            PokerStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+/// Compare hand `a` with hand `b`, writing -1, 0 or 1 into `out` if `a` loses, ties or wins,
+/// respectively.
+///
+/// # Safety
+///
+/// `a` and `b` must each be null or point to a valid [`PokerHand`], and `out` must be null or
+/// point to memory valid for writing an `int32_t`.
+#[no_mangle]
+pub unsafe extern "C" fn poker_compare(
+    a: *const PokerHand,
+    b: *const PokerHand,
+    out: *mut i32,
+) -> PokerStatus {
+    if a.is_null() || b.is_null() || out.is_null() {
+        return PokerStatus::NullPointer;
+    }
+    let (a, b) = match (Hand::try_from(&*a), Hand::try_from(&*b)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(status), _) | (_, Err(status)) => return status,
+    };
+    out.write(match compare(&a, &b) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    });
+    PokerStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{ffi::CString, ptr};
+
+    fn parse(cards: &str) -> Result<PokerHand, PokerStatus> {
+        let cards = CString::new(cards).unwrap();
+        let mut hand = PokerHand { cards: [0; 5] };
+        match unsafe { poker_parse_hand(cards.as_ptr(), &mut hand) } {
+            PokerStatus::Ok => Ok(hand),
+            status => Err(status),
+        }
+    }
+
+    #[test]
+    fn parse_and_evaluate() {
+        let hand = parse("tr,jr,qr,kr,1r").unwrap();
+        let mut category = PokerCategory::HighCard;
+        assert_eq!(
+            unsafe { poker_evaluate(&hand, &mut category) },
+            PokerStatus::Ok
+        );
+        assert_eq!(category, PokerCategory::StraightFlush);
+    }
+
+    #[test]
+    fn parse_errors_are_reported() {
+        assert_eq!(parse("tr,jr,qr,kr,1p"), Err(PokerStatus::InvalidCard));
+        assert_eq!(parse("tr,jr,qr,kr"), Err(PokerStatus::InvalidHand));
+        assert_eq!(parse("tr,tr,qr,kr,1r"), Err(PokerStatus::InvalidHand));
+    }
+
+    #[test]
+    fn null_pointers_are_rejected() {
+        let mut hand = PokerHand { cards: [0; 5] };
+        assert_eq!(
+            unsafe { poker_parse_hand(ptr::null(), &mut hand) },
+            PokerStatus::NullPointer
+        );
+        assert_eq!(
+            unsafe { poker_evaluate(&hand, ptr::null_mut()) },
+            PokerStatus::NullPointer
+        );
+    }
+
+    #[test]
+    fn invalid_indices_are_rejected() {
+        let hand = PokerHand {
+            cards: [0, 1, 2, 3, 52],
+        };
+        let mut category = PokerCategory::HighCard;
+        assert_eq!(
+            unsafe { poker_evaluate(&hand, &mut category) },
+            PokerStatus::InvalidCard
+        );
+    }
+
+    #[test]
+    fn compare_orders_hands() {
+        let pair = parse("2r,2s,5k,9h,jr").unwrap();
+        let flush = parse("2h,4h,6h,8h,th").unwrap();
+        let mut ordering = 0;
+        assert_eq!(
+            unsafe { poker_compare(&pair, &flush, &mut ordering) },
+            PokerStatus::Ok
+        );
+        assert_eq!(ordering, -1);
+        assert_eq!(
+            unsafe { poker_compare(&flush, &flush, &mut ordering) },
+            PokerStatus::Ok
+        );
+        assert_eq!(ordering, 0);
+    }
+}
@@ -0,0 +1,145 @@
+//! Field selection of JSON responses, so constrained clients receive only what they need.
+//!
+//! The `fields` query parameter is a comma-separated list of fields to keep, like
+//! `/draw?fields=category,strength_name`. A field of a nested object is named by its path, like
+//! `category_rank.ordinal`, and is returned nested like in the full response. Selection is done
+//! on the serialized response by [`select_fields`], so it works the same for every route it
+//! wraps, and handlers need not know of it.
+//!
+//! Only successful responses which are JSON objects are selected from. Errors, and other bodies,
+//! like the bare category of `/analyze/:cards` or text, are returned as they are.
+
+use axum::{
+    body::{self, Full},
+    extract::Query,
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum FieldsError {
+    #[error("the response has no field {0}")]
+    Unknown(String),
+    #[error("fields must not be empty")]
+    Empty,
+}
+
+impl IntoResponse for FieldsError {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+/// Keep only the `fields` of the object, each a path of keys separated by `.`.
+pub fn select<'a>(
+    object: &Map<String, Value>,
+    fields: impl IntoIterator<Item = &'a str>,
+) -> Result<Map<String, Value>, FieldsError> {
+    let mut selected = Map::new();
+    for field in fields {
+        if field.is_empty() {
+            return Err(FieldsError::Empty);
+        }
+        let unknown = || FieldsError::Unknown(field.to_owned());
+        let mut keys = field.split('.').peekable();
+        let (mut from, mut to) = (object, &mut selected);
+        while let Some(key) = keys.next() {
+            let value = from.get(key).ok_or_else(unknown)?;
+            if keys.peek().is_none() {
+                to.insert(key.to_owned(), value.clone());
+                break;
+            }
+            from = value.as_object().ok_or_else(unknown)?;
+            to = to
+                .entry(key)
+                .or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .expect("only objects are inserted before their fields");
+        }
+    }
+    Ok(selected)
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Middleware which keeps only the fields of the `fields` query parameter in the response. Use
+/// with [`axum::middleware::from_fn`].
+pub async fn select_fields<B>(
+    Query(query): Query<FieldsQuery>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let response = next.run(request).await;
+    let Some(fields) = query.fields else {
+        return response;
+    };
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if !response.status().is_success() || !is_json {
+        return response;
+    }
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = hyper::body::to_bytes(body).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let Ok(Value::Object(object)) = serde_json::from_slice(&bytes) else {
+        return Response::from_parts(parts, body::boxed(Full::from(bytes)));
+    };
+    match select(&object, fields.split(',')) {
+        Ok(selected) => {
+            let bytes = serde_json::to_vec(&selected).expect("no known fail modes");
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, body::boxed(Full::from(bytes)))
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(object) => object,
+            _ => panic!("not an object"),
+        }
+    }
+
+    #[test]
+    fn fields_are_selected_by_path() {
+        let response = object(json!({
+            "category": "FullHouse",
+            "seed": 7,
+            "category_rank": {"ordinal": 3, "classes": 156},
+        }));
+        let selected = select(&response, ["category", "category_rank.ordinal"]).unwrap();
+        assert_eq!(
+            Value::Object(selected),
+            json!({"category": "FullHouse", "category_rank": {"ordinal": 3}})
+        );
+    }
+
+    #[test]
+    fn unknown_fields_are_errors() {
+        let response = object(json!({"category": "Flush", "seed": 7}));
+        assert_eq!(
+            select(&response, ["category", "suit"]),
+            Err(FieldsError::Unknown("suit".to_owned()))
+        );
+        assert_eq!(
+            select(&response, ["seed.value"]),
+            Err(FieldsError::Unknown("seed.value".to_owned()))
+        );
+        assert_eq!(select(&response, [""]), Err(FieldsError::Empty));
+    }
+}
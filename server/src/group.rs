@@ -0,0 +1,184 @@
+//! Sort and group cards by rank and suit, with the Ace played high or low.
+//!
+//! The functions take any slice of cards, like the five of a [`Hand`] from [`Hand::as_slice`], or
+//! the seven of a hold'em player. Whether the Ace is above the King or below the Two is given by
+//! [`Ace`], except in [`longest_run`], where it is both, like in a straight.
+//!
+//! [`Hand`]: crate::hand::Hand
+//! [`Hand::as_slice`]: crate::hand::Hand::as_slice
+
+use crate::card::{Card, Rank, Suit};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Where the Ace ranks.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Ace {
+    /// Above the King, like when comparing high hands.
+    #[default]
+    High,
+    /// Below the Two, like in Ace-to-Five low.
+    Low,
+}
+
+impl Ace {
+    /// The numeric value of the rank, with the Ace as 14 or 1.
+    pub fn value(self, rank: Rank) -> u8 {
+        match self {
+            Ace::High => rank.numeric_ace_high(),
+            Ace::Low => rank.numeric(),
+        }
+    }
+}
+
+/// The cards of one suit.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct SuitGroup {
+    pub suit: Suit,
+    /// The cards of the suit, from the highest, with the Ace high.
+    pub cards: Vec<Card>,
+}
+
+/// The cards of one rank.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct RankGroup {
+    pub rank: Rank,
+    /// The cards of the rank, by suit.
+    pub cards: Vec<Card>,
+}
+
+/// A run of consecutive ranks, like the five of a straight.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Run {
+    /// The ranks of the run, from the highest.
+    pub ranks: Vec<Rank>,
+    /// One card of each rank of the run, in the same order.
+    pub cards: Vec<Card>,
+}
+
+/// Sort the cards from the highest rank to the lowest, and cards of the same rank by suit.
+pub fn sort_by_rank(cards: &mut [Card], ace: Ace) {
+    cards.sort_unstable_by_key(|card| (Reverse(ace.value(card.rank)), card.suit));
+}
+
+/// Group the cards by suit, from the suit with the most cards, and then by [`Suit::ALL`]. Suits
+/// without cards are left out.
+pub fn group_by_suit(cards: &[Card]) -> Vec<SuitGroup> {
+    let mut groups: Vec<SuitGroup> = Suit::ALL
+        .into_iter()
+        .map(|suit| {
+            let mut cards: Vec<Card> = cards.iter().filter(|c| c.suit == suit).copied().collect();
+            sort_by_rank(&mut cards, Ace::High);
+            SuitGroup { suit, cards }
+        })
+        .filter(|group| !group.cards.is_empty())
+        .collect();
+    groups.sort_by_key(|group| Reverse(group.cards.len()));
+    groups
+}
+
+/// Group the cards by rank, from the rank with the most cards, and then from the highest rank,
+/// like the pairs and trips of a hand are compared. Ranks without cards are left out.
+pub fn group_by_rank(cards: &[Card], ace: Ace) -> Vec<RankGroup> {
+    let mut groups: Vec<RankGroup> = Rank::ALL
+        .into_iter()
+        .map(|rank| {
+            let mut cards: Vec<Card> = cards.iter().filter(|c| c.rank == rank).copied().collect();
+            cards.sort_unstable();
+            RankGroup { rank, cards }
+        })
+        .filter(|group| !group.cards.is_empty())
+        .collect();
+    groups.sort_by_key(|group| (Reverse(group.cards.len()), Reverse(ace.value(group.rank))));
+    groups
+}
+
+/// The longest run of consecutive ranks among the cards, with the Ace both above the King and
+/// below the Two, or `None` if there are no cards. Of runs equally long, the highest is given.
+pub fn longest_run(cards: &[Card]) -> Option<Run> {
+    // The card of each rank with the highest suit, by its value from 1 to 14, where the Ace is both.
+    let card_of = |value: u8| {
+        let rank = Rank::ALL[usize::from((value - 1) % 13)];
+        cards.iter().filter(|c| c.rank == rank).max().copied()
+    };
+    let mut longest: Option<(u8, u8)> = None;
+    let mut top = None;
+    for value in (1..=14).rev() {
+        if card_of(value).is_none() {
+            top = None;
+            continue;
+        }
+        let high = *top.get_or_insert(value);
+        // Only a strictly longer run replaces one found higher up.
+        if longest
+            .is_none_or(|(longest_high, longest_low)| high - value > longest_high - longest_low)
+        {
+            longest = Some((high, value));
+        }
+    }
+    let (high, low) = longest?;
+    let cards: Vec<Card> = (low..=high)
+        .rev()
+        .map(|value| card_of(value).expect("the run has a card of each rank"))
+        .collect();
+    Some(Run {
+        ranks: cards.iter().map(|card| card.rank).collect(),
+        cards,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    fn names(cards: &[Card]) -> Vec<String> {
+        cards.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn aces_sort_high_or_low() {
+        let mut hand = cards("5s,1h,ks,2r,1s");
+        sort_by_rank(&mut hand, Ace::High);
+        assert_eq!(names(&hand), ["1h", "1s", "ks", "5s", "2r"]);
+        sort_by_rank(&mut hand, Ace::Low);
+        assert_eq!(names(&hand), ["ks", "5s", "2r", "1h", "1s"]);
+    }
+
+    #[test]
+    fn groups_are_ordered_by_size() {
+        let hand = cards("5s,1h,ks,5r,1s,2s,5k");
+        let suits = group_by_suit(&hand);
+        assert_eq!(suits[0].suit, Suit::Spades);
+        assert_eq!(names(&suits[0].cards), ["1s", "ks", "5s", "2s"]);
+        assert_eq!(suits.len(), 4);
+
+        let ranks = group_by_rank(&hand, Ace::High);
+        let order: Vec<_> = ranks.iter().map(|group| group.rank).collect();
+        assert_eq!(order, [Rank::Five, Rank::Ace, Rank::King, Rank::Two]);
+        let ranks = group_by_rank(&cards("1h,ks"), Ace::Low);
+        assert_eq!(ranks[0].rank, Rank::King);
+    }
+
+    #[test]
+    fn runs_wrap_the_ace_at_both_ends() {
+        let wheel = longest_run(&cards("1s,2h,3r,4k,5s,9h,jr")).unwrap();
+        let order = [Rank::Five, Rank::Four, Rank::Three, Rank::Two, Rank::Ace];
+        assert_eq!(wheel.ranks, order);
+        let broadway = longest_run(&cards("qs,kh,1r,2k")).unwrap();
+        assert_eq!(broadway.ranks, [Rank::Ace, Rank::King, Rank::Queen]);
+        assert_eq!(names(&broadway.cards), ["1r", "kh", "qs"]);
+    }
+
+    #[test]
+    fn ties_give_the_highest_run() {
+        let run = longest_run(&cards("2s,3h,8r,9k,9s")).unwrap();
+        assert_eq!(run.ranks, [Rank::Nine, Rank::Eight]);
+        assert_eq!(names(&run.cards), ["9s", "8r"]);
+        assert!(longest_run(&[]).is_none());
+    }
+}
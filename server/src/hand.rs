@@ -1,11 +1,12 @@
 //! Model a [`Hand`] of five [`Card`]s.
 
-use crate::card::{Card, Rank, Suit};
-use serde::Serialize;
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use crate::card::{Card, InvalidConversion, Rank, Suit};
+use core::{fmt, str::FromStr};
+use serde::{Deserialize, Serialize};
 
 /// Represent which classification a [`Hand`] of five cards has.
-#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd, Serialize)]
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Ord, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum HandCategory {
     HighCard,
     OnePair,
@@ -18,17 +19,51 @@ pub enum HandCategory {
     StraightFlush,
 }
 
+impl HandCategory {
+    /// All nine categories, from the weakest to the strongest.
+    pub const ALL: [HandCategory; 9] = [
+        HandCategory::HighCard,
+        HandCategory::OnePair,
+        HandCategory::TwoPair,
+        HandCategory::ThreeOfAKind,
+        HandCategory::Straight,
+        HandCategory::Flush,
+        HandCategory::FullHouse,
+        HandCategory::FourOfAKind,
+        HandCategory::StraightFlush,
+    ];
+}
+
 /// Represents a hand of five unique [`Card`]s.
 ///
 /// Since the only way to construct a [`Hand`] is via the [`TryFrom`] trait, whose function fails if
-/// five unique cards are not given, a [`Hand`] is guaranteed to have five unique [`Card`]s.
-#[derive(Clone, Serialize, Debug)]
+/// five unique cards are not given, a [`Hand`] is guaranteed to have five unique [`Card`]s. The
+/// cards are kept sorted, so two hands with the same cards are equal. Deserializing checks the
+/// cards the same way.
+#[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+#[serde(try_from = "UncheckedHand")]
 pub struct Hand {
-    hand: HashSet<Card>,
+    hand: [Card; 5],
+}
+
+/// A deserialized [`Hand`] whose cards are not yet checked.
+#[derive(Deserialize)]
+struct UncheckedHand {
+    hand: [Card; 5],
+}
+
+impl TryFrom<UncheckedHand> for Hand {
+    type Error = HandConstructionError;
+
+    fn try_from(value: UncheckedHand) -> Result<Self, Self::Error> {
+        Hand::try_from(value.hand.as_slice())
+    }
 }
 
-pub type RankCount = HashMap<Rank, u8>;
-pub type SuitCount = HashMap<Suit, u8>;
+/// The number of cards of each [`Rank`], indexed by `rank as usize`.
+pub type RankCount = [u8; 13];
+/// The number of cards of each [`Suit`], indexed by `suit as usize`.
+pub type SuitCount = [u8; 4];
 
 impl Hand {
     /// Returns an iterator over the five unique cards.
@@ -36,36 +71,55 @@ impl Hand {
         self.hand.iter()
     }
 
+    /// The five cards, sorted.
+    pub fn as_slice(&self) -> &[Card] {
+        &self.hand
+    }
+
     /// Gives a count of each of the ranks on hand.
     pub fn count_ranks(&self) -> RankCount {
-        let mut ranks = HashMap::new();
+        let mut ranks = [0; 13];
         for card in self.cards() {
-            match ranks.entry(card.rank) {
-                Entry::Occupied(e) => {
-                    *e.into_mut() += 1;
-                }
-                Entry::Vacant(e) => {
-                    e.insert(1);
-                }
-            }
+            ranks[card.rank as usize] += 1;
         }
         ranks
     }
 
     /// Gives a count of each of the suits on hand.
     pub fn count_suits(&self) -> SuitCount {
-        let mut suits = HashMap::with_capacity(4);
+        let mut suits = [0; 4];
         for card in self.cards() {
-            match suits.entry(card.suit) {
-                Entry::Occupied(e) => {
-                    *e.into_mut() += 1;
-                }
-                Entry::Vacant(e) => {
-                    e.insert(1);
+            suits[card.suit as usize] += 1;
+        }
+        suits
+    }
+
+    /// The suit-isomorphic canonical form of the hand.
+    ///
+    /// Relabeling the suits of a hand, e.g., swapping all Spades and Hearts, gives a hand of the
+    /// same strength. Hands which are relabelings of each other have the same canonical form, which
+    /// is the least hand among all relabelings.
+    pub fn canonical(&self) -> Hand {
+        let mut canonical = self.hand;
+        for a in Suit::ALL {
+            for b in Suit::ALL.into_iter().filter(|&b| b != a) {
+                for c in Suit::ALL.into_iter().filter(|&c| c != a && c != b) {
+                    for d in Suit::ALL
+                        .into_iter()
+                        .filter(|&d| d != a && d != b && d != c)
+                    {
+                        let relabeling = [a, b, c, d];
+                        let mut hand = self.hand;
+                        for card in &mut hand {
+                            card.suit = relabeling[card.suit as usize];
+                        }
+                        hand.sort_unstable();
+                        canonical = canonical.min(hand);
+                    }
                 }
             }
         }
-        suits
+        Hand { hand: canonical }
     }
 }
 
@@ -77,6 +131,22 @@ pub enum HandConstructionError {
     Uniqueness(usize),
 }
 
+/// Five unique cards, picked from the start of a deck shuffled by the fuzzer's bytes.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Hand {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut deck: [u8; 52] = core::array::from_fn(|index| index as u8);
+        for i in 0..5 {
+            deck.swap(i, u.int_in_range(i..=51)?);
+        }
+        let mut hand: [Card; 5] = core::array::from_fn(|i| {
+            Card::from_index(deck[i]).expect("indices of the deck are below 52")
+        });
+        hand.sort_unstable();
+        Ok(Self { hand })
+    }
+}
+
 impl TryFrom<&[Card]> for Hand {
     type Error = HandConstructionError;
 
@@ -84,21 +154,95 @@ impl TryFrom<&[Card]> for Hand {
     ///
     /// Fails if the slice does not contain exactly five unique cards.
     fn try_from(value: &[Card]) -> Result<Self, Self::Error> {
-        if value.len() != 5 {
+        let Ok(mut hand) = <[Card; 5]>::try_from(value) else {
             return Err(HandConstructionError::Length(value.len()));
-        }
-        let hand: HashSet<_> = value.iter().copied().collect();
-        if hand.len() != 5 {
-            return Err(HandConstructionError::Uniqueness(hand.len()));
+        };
+        hand.sort_unstable();
+        let unique = 1 + hand.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        if unique != 5 {
+            return Err(HandConstructionError::Uniqueness(unique));
         }
         Ok(Self { hand })
     }
 }
 
+impl fmt::Display for HandCategory {
+    /// Formats the category as its English name, e.g., "Full house".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HandCategory::HighCard => "High card",
+            HandCategory::OnePair => "One pair",
+            HandCategory::TwoPair => "Two pair",
+            HandCategory::ThreeOfAKind => "Three of a kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full house",
+            HandCategory::FourOfAKind => "Four of a kind",
+            HandCategory::StraightFlush => "Straight flush",
+        })
+    }
+}
+
+impl fmt::Display for Hand {
+    /// Formats the hand as a comma-separated list of cards, which [`Hand::from_str`] parses.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, card) in self.cards().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{card}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseHandError {
+    #[error("card is invalid: {0}")]
+    Card(#[from] InvalidConversion),
+    #[error("hand is invalid: {0}")]
+    Hand(#[from] HandConstructionError),
+}
+
+impl FromStr for Hand {
+    type Err = ParseHandError;
+
+    /// Parse a comma-separated list of five cards, e.g., `tr,jr,qr,kr,1r`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cards = [Card::new(Rank::Ace, Suit::Clubs); 5];
+        let mut count = 0;
+        for card in s.split(',') {
+            let card = card.parse()?;
+            if let Some(slot) = cards.get_mut(count) {
+                *slot = card;
+            }
+            count += 1;
+        }
+        if count != 5 {
+            return Err(HandConstructionError::Length(count).into());
+        }
+        Ok(Self::try_from(cards.as_slice())?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn arbitrary_hands_are_valid() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let bytes: Vec<u8> = (0..=255).chain((0..=255).rev()).collect();
+        let mut u = Unstructured::new(&bytes);
+        while !u.is_empty() {
+            let hand = Hand::arbitrary(&mut u).unwrap();
+            let cards: Vec<_> = hand.cards().copied().collect();
+            assert_eq!(Hand::try_from(cards.as_slice()).unwrap(), hand);
+        }
+    }
+
     #[test]
     fn too_few_cards_fail() {
         use Rank::*;
@@ -150,6 +294,66 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn parse_and_display_round_trip() {
+        let hand: Hand = "tr,jr,qr,kr,1r".parse().unwrap();
+        assert_eq!(hand, hand.to_string().parse().unwrap());
+    }
+
+    #[test]
+    fn parse_rejects_invalid_cards_and_hands() {
+        assert!(matches!(
+            "tr,jr,qr,kr,1p".parse::<Hand>(),
+            Err(ParseHandError::Card(_))
+        ));
+        assert!(matches!(
+            "tr,jr,qr,kr".parse::<Hand>(),
+            Err(ParseHandError::Hand(_))
+        ));
+    }
+
+    #[test]
+    fn deserializing_checks_cards() {
+        let hand: Hand = "2r,2s,5k,9h,jr".parse().unwrap();
+        let json = serde_json::to_string(&hand).unwrap();
+        assert_eq!(serde_json::from_str::<Hand>(&json).unwrap(), hand);
+        let duplicate = json.replace(
+            r#""rank":"Two","suit":"Spades""#,
+            r#""rank":"Two","suit":"Diamonds""#,
+        );
+        assert_ne!(duplicate, json);
+        assert!(serde_json::from_str::<Hand>(&duplicate).is_err());
+    }
+
+    #[test]
+    fn card_order_does_not_matter() {
+        let a: Hand = "tr,jr,qr,kr,1r".parse().unwrap();
+        let b: Hand = "1r,kr,qr,jr,tr".parse().unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn counts_are_indexed_by_rank_and_suit() {
+        let hand: Hand = "2r,2s,5k,9h,jr".parse().unwrap();
+        let ranks = hand.count_ranks();
+        assert_eq!(ranks[Rank::Two as usize], 2);
+        assert_eq!(ranks[Rank::Five as usize], 1);
+        assert_eq!(ranks[Rank::Ace as usize], 0);
+        let suits = hand.count_suits();
+        assert_eq!(suits[Suit::Diamonds as usize], 2);
+        assert_eq!(suits[Suit::Clubs as usize], 1);
+    }
+
+    #[test]
+    fn relabeled_suits_have_same_canonical_form() {
+        let a: Hand = "2r,2s,5k,9h,jr".parse().unwrap();
+        let b: Hand = "2h,2k,5s,9r,jh".parse().unwrap();
+        let c: Hand = "2r,2s,5k,9h,jk".parse().unwrap();
+        assert_eq!(a.canonical(), b.canonical());
+        assert_ne!(a.canonical(), c.canonical());
+        assert_eq!(a.canonical().canonical(), a.canonical());
+    }
+
     #[test]
     fn five_unique_cards_succeed() {
         use Rank::*;
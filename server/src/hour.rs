@@ -0,0 +1,97 @@
+//! The hand of the hour: one hand per hour, which every instance agrees on.
+//!
+//! The hand is drawn from a fair deck with the seed of its hour, the Unix time in seconds at which
+//! the hour starts, so any instance, or anyone with `/replay/:seed`, draws the same hand for the
+//! same hour without talking to each other. [`HourlyHand`] caches the hand of the current hour and
+//! draws the next one once the hour is over.
+
+use crate::{
+    classify::classify,
+    hand::{Hand, HandCategory},
+    shuffle,
+};
+use serde::Serialize;
+use std::{sync::Mutex, time::Duration};
+
+/// The length of an hour in seconds.
+const HOUR: u64 = 60 * 60;
+
+/// The hand of one hour.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct HandOfTheHour {
+    /// The Unix time in seconds at which the hour starts, which is also the seed of the hand.
+    pub starts_at: u64,
+    /// The Unix time in seconds at which the next hand is drawn.
+    pub ends_at: u64,
+    pub hand: Hand,
+    pub category: HandCategory,
+}
+
+impl HandOfTheHour {
+    /// The hand of the hour which the time `since_epoch` after the Unix epoch is in.
+    pub fn at(since_epoch: Duration) -> Self {
+        let starts_at = since_epoch.as_secs() / HOUR * HOUR;
+        let hand = shuffle::draw(starts_at);
+        Self {
+            starts_at,
+            ends_at: starts_at + HOUR,
+            category: classify(&hand),
+            hand,
+        }
+    }
+
+    /// The time left of the hour at the time `since_epoch` after the Unix epoch.
+    pub fn remaining(&self, since_epoch: Duration) -> Duration {
+        Duration::from_secs(self.ends_at).saturating_sub(since_epoch)
+    }
+}
+
+/// A cache of the hand of the current hour.
+#[derive(Debug, Default)]
+pub struct HourlyHand {
+    current: Mutex<Option<HandOfTheHour>>,
+}
+
+impl HourlyHand {
+    /// The hand of the hour which the time `since_epoch` after the Unix epoch is in, drawn once
+    /// per hour.
+    pub fn get(&self, since_epoch: Duration) -> HandOfTheHour {
+        let mut current = self.current.lock().expect("the cache never panics");
+        match &*current {
+            Some(hand) if (hand.starts_at..hand.ends_at).contains(&since_epoch.as_secs()) => {
+                hand.clone()
+            }
+            _ => current.insert(HandOfTheHour::at(since_epoch)).clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_time_of_the_hour_gets_the_same_hand() {
+        let start = Duration::from_secs(1_700_002_800);
+        let hand = HandOfTheHour::at(start);
+        assert_eq!(hand.starts_at, 1_700_002_800);
+        assert_eq!(HandOfTheHour::at(start + Duration::from_secs(3599)), hand);
+        let next = HandOfTheHour::at(start + Duration::from_secs(3600));
+        assert_eq!(next.starts_at, hand.ends_at);
+        assert_eq!(hand.hand, shuffle::draw(hand.starts_at));
+        assert_eq!(
+            hand.remaining(start + Duration::from_secs(600)),
+            Duration::from_secs(3000)
+        );
+    }
+
+    #[test]
+    fn the_cache_draws_again_when_the_hour_is_over() {
+        let cache = HourlyHand::default();
+        let start = Duration::from_secs(1_700_002_800);
+        assert_eq!(cache.get(start), HandOfTheHour::at(start));
+        let later = start + Duration::from_secs(7200);
+        assert_eq!(cache.get(later), HandOfTheHour::at(later));
+        assert_eq!(cache.get(later).starts_at, 1_700_010_000);
+    }
+}
@@ -0,0 +1,687 @@
+//! Localized names and descriptions of [`HandCategory`]s, names of [`Strength`]s, explanations of
+//! showdowns, and error messages. The English error messages are the same as the errors display.
+//!
+//! Each supported [`Language`] has a message catalog holding all of its text. Messages with values,
+//! like the invalid character of a card, have a `{}` placeholder where each value goes.
+
+use crate::{
+    benchmark::BenchmarkError,
+    card::{InvalidConversion, Rank},
+    compare::Strength,
+    hand::{HandCategory, HandConstructionError},
+    odds::OddsError,
+    paigow::PaiGowError,
+    percentile::PercentileError,
+    possible::PossibleError,
+    rules::RulesError,
+    variant::VariantError,
+};
+use std::fmt::Display;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Norwegian,
+}
+
+/// All text of one language.
+struct Catalog {
+    /// Indexed by `category as usize`.
+    category_names: [&'static str; 9],
+    /// Indexed by `category as usize`.
+    category_descriptions: [&'static str; 9],
+    /// Indexed by `category as usize`. The deciding ranks of the category go in the placeholders,
+    /// see [`Language::strength_name`].
+    strength_names: [&'static str; 9],
+    /// Indexed by `rank as usize`.
+    rank_names: [&'static str; 13],
+    /// Indexed by `rank as usize`.
+    rank_plurals: [&'static str; 13],
+    card_length: &'static str,
+    card_rank: &'static str,
+    card_suit: &'static str,
+    hand_length: &'static str,
+    hand_uniqueness: &'static str,
+    /// The most cards a request may give.
+    too_many_cards: &'static str,
+    /// The name of a parameter, its value, and the most it may be.
+    at_most: &'static str,
+    /// The name of a parameter, its value, and the least and most it may be.
+    from_to: &'static str,
+    /// The name of a parameter, and its value.
+    share: &'static str,
+    /// The timeout given, and the longest it may be in seconds.
+    timeout: &'static str,
+    no_variant: &'static str,
+    not_signed: &'static str,
+    /// The token of the question.
+    no_question: &'static str,
+    /// The token of the deal.
+    no_deal: &'static str,
+    no_job: &'static str,
+    /// The most jobs running at once.
+    busy: &'static str,
+    /// The share of demo hands dealt flushes or better, in percent.
+    demo_rate: &'static str,
+    /// The card given more than once.
+    duplicate_card: &'static str,
+    /// The card missing from the deck of the rules.
+    not_in_deck: &'static str,
+    hole_cards: &'static str,
+    odds_board: &'static str,
+    percentile_board: &'static str,
+    known_cards: &'static str,
+    /// The number of cards, and the fewest and most a variant takes.
+    variant_cards: &'static str,
+    /// The number of players, and the fewest and most the rules deal to.
+    players: &'static str,
+    min_players: &'static str,
+    benchmark_cards: &'static str,
+    paigow_cards: &'static str,
+    /// The cards of the low hand.
+    paigow_foul: &'static str,
+    /// The categories of the winner and the runner-up.
+    beats_category: &'static str,
+    /// The shared category, and the deciding ranks of the winner and the runner-up.
+    beats_rank: &'static str,
+    split_pot: &'static str,
+}
+
+const ENGLISH: Catalog = Catalog {
+    category_names: [
+        "High card",
+        "One pair",
+        "Two pair",
+        "Three of a kind",
+        "Straight",
+        "Flush",
+        "Full house",
+        "Four of a kind",
+        "Straight flush",
+    ],
+    category_descriptions: [
+        "No two cards share a rank, and the hand is neither a straight nor a flush.",
+        "Two cards of the same rank.",
+        "Two pairs of different ranks.",
+        "Three cards of the same rank.",
+        "Five cards of consecutive ranks, not all of the same suit.",
+        "Five cards of the same suit, not of consecutive ranks.",
+        "Three cards of one rank and two of another.",
+        "Four cards of the same rank.",
+        "Five cards of consecutive ranks, all of the same suit.",
+    ],
+    strength_names: [
+        "{} high",
+        "Pair of {}",
+        "Two pair, {} and {}",
+        "Three {}",
+        "{}-high straight",
+        "{}-high flush",
+        "{} over {} full house",
+        "Four {}",
+        "{}-high straight flush",
+    ],
+    rank_names: [
+        "Ace", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack",
+        "Queen", "King",
+    ],
+    rank_plurals: [
+        "Aces", "Twos", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens",
+        "Jacks", "Queens", "Kings",
+    ],
+    card_length: "card is invalid: length of str ({}) must be 2",
+    card_rank: "card is invalid: {} is not a valid rank",
+    card_suit: "card is invalid: {} is not a valid suit",
+    hand_length: "hand is invalid: number of cards in hand ({}) must be 5",
+    hand_uniqueness: "hand is invalid: got {} unique cards, need 5",
+    too_many_cards: "cards are invalid: at most {} cards may be given",
+    at_most: "{} ({}) must be at most {}",
+    from_to: "{} ({}) must be from {} to {}",
+    share: "{} ({}) must be between 0 and 1",
+    timeout: "timeout ({}) must be like 30s or 500ms, and at most {}s",
+    no_variant: "no variant named {}",
+    not_signed: "responses are not signed",
+    no_question: "no open question with token {}",
+    no_deal: "no open deal with token {}",
+    no_job: "no job with ID {}",
+    busy: "{} jobs are already running, which is the most at once",
+    demo_rate: "Demo: {} % of hands are dealt flushes or better",
+    duplicate_card: "{} is given more than once",
+    not_in_deck: "{} is not in the deck",
+    hole_cards: "number of hole cards ({}) must be 2",
+    odds_board: "number of board cards ({}) must be 3 or 4",
+    percentile_board: "number of board cards ({}) must be 3 to 5",
+    known_cards: "number of known cards ({}) must be at most 4",
+    variant_cards: "number of cards ({}) must be from {} to {}",
+    players: "players ({}) must be from {} to {}",
+    min_players: "the minimum of players ({}) must be from 2 to the most the deck deals to",
+    benchmark_cards: "number of cards per hand ({}) must be from 5 to 7",
+    paigow_cards: "number of cards ({}) must be 7",
+    paigow_foul: "the low hand ({}) must be weaker than the high hand",
+    beats_category: "{} beats {}.",
+    beats_rank: "Both hands are {}, and the first differing rank decides: {} beats {}.",
+    split_pot: "The hands are equally strong and split the pot.",
+};
+
+const NORWEGIAN: Catalog = Catalog {
+    category_names: [
+        "Høyeste kort",
+        "Ett par",
+        "To par",
+        "Tre like",
+        "Straight",
+        "Flush",
+        "Hus",
+        "Fire like",
+        "Straight flush",
+    ],
+    category_descriptions: [
+        "Ingen kort har samme verdi, og hånden er verken straight eller flush.",
+        "To kort med samme verdi.",
+        "To par med ulik verdi.",
+        "Tre kort med samme verdi.",
+        "Fem kort med påfølgende verdier, ikke alle i samme farge.",
+        "Fem kort i samme farge, uten påfølgende verdier.",
+        "Tre kort med én verdi og to med en annen.",
+        "Fire kort med samme verdi.",
+        "Fem kort med påfølgende verdier, alle i samme farge.",
+    ],
+    strength_names: [
+        "Høyeste kort: {}",
+        "Par i {}",
+        "To par, {} og {}",
+        "Tre {}",
+        "Straight til {}",
+        "Flush til {}",
+        "Hus, {} over {}",
+        "Fire {}",
+        "Straight flush til {}",
+    ],
+    rank_names: [
+        "ess", "toer", "treer", "firer", "femmer", "sekser", "syver", "åtter", "nier", "tier",
+        "knekt", "dame", "konge",
+    ],
+    rank_plurals: [
+        "ess", "toere", "treere", "firere", "femmere", "seksere", "syvere", "åttere", "niere",
+        "tiere", "knekter", "damer", "konger",
+    ],
+    card_length: "kortet er ugyldig: lengden ({}) må være 2",
+    card_rank: "kortet er ugyldig: {} er ikke en gyldig verdi",
+    card_suit: "kortet er ugyldig: {} er ikke en gyldig farge",
+    hand_length: "hånden er ugyldig: antall kort ({}) må være 5",
+    hand_uniqueness: "hånden er ugyldig: fikk {} unike kort, trenger 5",
+    too_many_cards: "kortene er ugyldige: høyst {} kort kan oppgis",
+    at_most: "{} ({}) må være høyst {}",
+    from_to: "{} ({}) må være fra {} til {}",
+    share: "{} ({}) må være mellom 0 og 1",
+    timeout: "tidsavbruddet ({}) må være som 30s eller 500ms, og høyst {}s",
+    no_variant: "ingen variant heter {}",
+    not_signed: "svarene er ikke signert",
+    no_question: "intet åpent spørsmål har token {}",
+    no_deal: "ingen åpen utdeling har token {}",
+    no_job: "ingen jobb har ID {}",
+    busy: "{} jobber kjører allerede, som er det meste samtidig",
+    demo_rate: "Demo: {} % av hendene deles ut som flush eller bedre",
+    duplicate_card: "{} er oppgitt mer enn én gang",
+    not_in_deck: "{} er ikke i kortstokken",
+    hole_cards: "antall hullkort ({}) må være 2",
+    odds_board: "antall kort på bordet ({}) må være 3 eller 4",
+    percentile_board: "antall kort på bordet ({}) må være fra 3 til 5",
+    known_cards: "antall kjente kort ({}) må være høyst 4",
+    variant_cards: "antall kort ({}) må være fra {} til {}",
+    players: "antall spillere ({}) må være fra {} til {}",
+    min_players: "minste antall spillere ({}) må være fra 2 til det flest kortstokken deler ut til",
+    benchmark_cards: "antall kort per hånd ({}) må være fra 5 til 7",
+    paigow_cards: "antall kort ({}) må være 7",
+    paigow_foul: "den lave hånden ({}) må være svakere enn den høye",
+    beats_category: "{} slår {}.",
+    beats_rank: "Begge hendene er {}, og den første ulike verdien avgjør: {} slår {}.",
+    split_pot: "Hendene er like sterke og deler potten.",
+};
+
+impl Language {
+    /// The language of a language tag like `en`, `en-GB` or `nb-NO`, if it is supported. Bokmål
+    /// (`nb`) and Nynorsk (`nn`) are both taken as Norwegian.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split('-').next().unwrap_or_default().trim();
+        if primary.eq_ignore_ascii_case("en") {
+            Some(Language::English)
+        } else if ["no", "nb", "nn"]
+            .iter()
+            .any(|norwegian| primary.eq_ignore_ascii_case(norwegian))
+        {
+            Some(Language::Norwegian)
+        } else {
+            None
+        }
+    }
+
+    /// The most preferred supported language of an `Accept-Language` header value, like
+    /// `nb-NO, en;q=0.8`.
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut preferences: Vec<(f32, Language)> = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let language = Language::from_tag(parts.next()?)?;
+                let quality = parts
+                    .find_map(|parameter| parameter.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |quality| quality.parse().ok())?;
+                Some((quality, language))
+            })
+            .collect();
+        // The sort is stable, so equally preferred languages keep their order.
+        preferences.sort_by(|a, b| b.0.total_cmp(&a.0));
+        preferences
+            .first()
+            .filter(|(quality, _)| *quality > 0.0)
+            .map(|&(_, language)| language)
+    }
+
+    fn catalog(self) -> &'static Catalog {
+        match self {
+            Language::English => &ENGLISH,
+            Language::Norwegian => &NORWEGIAN,
+        }
+    }
+
+    /// The name of the category, e.g., "Full house" in English.
+    pub fn category_name(self, category: HandCategory) -> &'static str {
+        self.catalog().category_names[category as usize]
+    }
+
+    /// What makes a hand fall into the category.
+    pub fn category_description(self, category: HandCategory) -> &'static str {
+        self.catalog().category_descriptions[category as usize]
+    }
+
+    /// The name of the hand's exact strength by its deciding ranks, e.g., "Queens over Nines full
+    /// house" in English. Kickers are left out.
+    pub fn strength_name(self, strength: &Strength) -> String {
+        use HandCategory::*;
+
+        let catalog = self.catalog();
+        let rank = |i: usize| usize::from(strength.tie_breakers[i] - 1) % 13;
+        let name = |i: usize| catalog.rank_names[rank(i)];
+        let plural = |i: usize| catalog.rank_plurals[rank(i)];
+        let template = catalog.strength_names[strength.category as usize];
+        match strength.category {
+            HighCard | Straight | Flush | StraightFlush => fill(template, &[&name(0)]),
+            OnePair | ThreeOfAKind | FourOfAKind => fill(template, &[&plural(0)]),
+            TwoPair | FullHouse => fill(template, &[&plural(0), &plural(1)]),
+        }
+    }
+
+    pub fn card_error(self, error: &InvalidConversion) -> String {
+        let catalog = self.catalog();
+        match error {
+            InvalidConversion::Length(length) => fill(catalog.card_length, &[length]),
+            InvalidConversion::Rank(rank) => fill(catalog.card_rank, &[rank]),
+            InvalidConversion::Suit(suit) => fill(catalog.card_suit, &[suit]),
+        }
+    }
+
+    pub fn hand_error(self, error: &HandConstructionError) -> String {
+        let catalog = self.catalog();
+        match error {
+            HandConstructionError::Length(length) => fill(catalog.hand_length, &[length]),
+            HandConstructionError::Uniqueness(unique) => fill(catalog.hand_uniqueness, &[unique]),
+        }
+    }
+
+    /// The error of a request giving more than `max` cards.
+    pub fn too_many_cards(self, max: usize) -> String {
+        fill(self.catalog().too_many_cards, &[&max])
+    }
+
+    /// The error of a request giving the parameter `name` a `value` above `max`.
+    pub fn at_most(self, name: &str, value: &dyn Display, max: &dyn Display) -> String {
+        fill(self.catalog().at_most, &[&name, value, max])
+    }
+
+    /// The error of a request giving the parameter `name` a `value` outside `min` to `max`.
+    pub fn from_to(
+        self,
+        name: &str,
+        value: &dyn Display,
+        min: &dyn Display,
+        max: &dyn Display,
+    ) -> String {
+        fill(self.catalog().from_to, &[&name, value, min, max])
+    }
+
+    /// The error of a request giving the parameter `name` a `value` which is not a share strictly
+    /// between 0 and 1.
+    pub fn share(self, name: &str, value: f64) -> String {
+        fill(self.catalog().share, &[&name, &value])
+    }
+
+    /// The error of a request giving an invalid `timeout`, or one longer than `max_secs`.
+    pub fn timeout(self, timeout: &str, max_secs: u64) -> String {
+        fill(self.catalog().timeout, &[&timeout, &max_secs])
+    }
+
+    pub fn no_variant(self, name: &str) -> String {
+        fill(self.catalog().no_variant, &[&name])
+    }
+
+    pub fn not_signed(self) -> String {
+        self.catalog().not_signed.to_owned()
+    }
+
+    pub fn no_question(self, token: &str) -> String {
+        fill(self.catalog().no_question, &[&token])
+    }
+
+    pub fn no_deal(self, token: &str) -> String {
+        fill(self.catalog().no_deal, &[&token])
+    }
+
+    pub fn no_job(self, id: u64) -> String {
+        fill(self.catalog().no_job, &[&id])
+    }
+
+    /// The error of starting a job while `max_running` jobs are running.
+    pub fn busy(self, max_running: usize) -> String {
+        fill(self.catalog().busy, &[&max_running])
+    }
+
+    /// The note on demo hands, dealt flushes or better at the `rate`.
+    pub fn demo_rate(self, rate: f64) -> String {
+        fill(self.catalog().demo_rate, &[&format!("{:.0}", rate * 100.0)])
+    }
+
+    pub fn odds_error(self, error: &OddsError) -> String {
+        let catalog = self.catalog();
+        match error {
+            OddsError::HoleCards(given) => fill(catalog.hole_cards, &[given]),
+            OddsError::Board(given) => fill(catalog.odds_board, &[given]),
+            OddsError::DuplicateCard(card) => fill(catalog.duplicate_card, &[card]),
+        }
+    }
+
+    pub fn possible_error(self, error: &PossibleError) -> String {
+        let catalog = self.catalog();
+        match error {
+            PossibleError::Cards(given) => fill(catalog.known_cards, &[given]),
+            PossibleError::DuplicateCard(card) => fill(catalog.duplicate_card, &[card]),
+        }
+    }
+
+    pub fn variant_error(self, error: &VariantError) -> String {
+        let catalog = self.catalog();
+        match error {
+            VariantError::Cards { given, expected } => fill(
+                catalog.variant_cards,
+                &[given, expected.start(), expected.end()],
+            ),
+            VariantError::DuplicateCard(card) => fill(catalog.duplicate_card, &[card]),
+            VariantError::Rules(error) => self.rules_error(error),
+        }
+    }
+
+    pub fn rules_error(self, error: &RulesError) -> String {
+        let catalog = self.catalog();
+        match error {
+            RulesError::Players { players, min, max } => {
+                fill(catalog.players, &[players, min, max])
+            }
+            RulesError::MinPlayers(min) => fill(catalog.min_players, &[min]),
+            RulesError::NotInDeck(card) => fill(catalog.not_in_deck, &[card]),
+        }
+    }
+
+    pub fn benchmark_error(self, error: &BenchmarkError) -> String {
+        match error {
+            BenchmarkError::Cards(given) => fill(self.catalog().benchmark_cards, &[given]),
+        }
+    }
+
+    pub fn percentile_error(self, error: &PercentileError) -> String {
+        let catalog = self.catalog();
+        match error {
+            PercentileError::HoleCards(given) => fill(catalog.hole_cards, &[given]),
+            PercentileError::Board(given) => fill(catalog.percentile_board, &[given]),
+            PercentileError::DuplicateCard(card) => fill(catalog.duplicate_card, &[card]),
+        }
+    }
+
+    pub fn paigow_error(self, error: &PaiGowError) -> String {
+        let catalog = self.catalog();
+        match error {
+            PaiGowError::Cards(given) => fill(catalog.paigow_cards, &[given]),
+            PaiGowError::DuplicateCard(card) => fill(catalog.duplicate_card, &[card]),
+            PaiGowError::Foul(low) => fill(catalog.paigow_foul, &[low]),
+        }
+    }
+
+    /// Explain why the hand of strength `winner` beats, or ties with, the hand of strength
+    /// `runner_up`.
+    pub fn explain_showdown(self, winner: &Strength, runner_up: &Strength) -> String {
+        let catalog = self.catalog();
+        if winner.category != runner_up.category {
+            return fill(
+                catalog.beats_category,
+                &[
+                    &self.category_name(winner.category),
+                    &self.category_name(runner_up.category).to_lowercase(),
+                ],
+            );
+        }
+        let deciding = winner
+            .tie_breakers
+            .iter()
+            .zip(&runner_up.tie_breakers)
+            .find(|(a, b)| a != b);
+        match deciding {
+            Some((&a, &b)) => fill(
+                catalog.beats_rank,
+                &[
+                    &self.category_name(winner.category).to_lowercase(),
+                    &rank_label(a),
+                    &rank_label(b),
+                ],
+            ),
+            None => catalog.split_pot.to_owned(),
+        }
+    }
+}
+
+/// The label of a rank counted with the Ace as 14, like in [`Strength::tie_breakers`].
+fn rank_label(numeric_ace_high: u8) -> &'static str {
+    Rank::ALL[usize::from(numeric_ace_high - 1) % 13].label()
+}
+
+/// Put the `values` in place of the `{}`s in `template`, in order.
+fn fill(template: &str, values: &[&dyn Display]) -> String {
+    let mut filled = String::new();
+    let mut parts = template.split("{}");
+    filled.push_str(parts.next().unwrap_or_default());
+    for (part, value) in parts.zip(values) {
+        filled.push_str(&value.to_string());
+        filled.push_str(part);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_matches_display() {
+        for category in [HandCategory::HighCard, HandCategory::FullHouse] {
+            assert_eq!(
+                Language::English.category_name(category),
+                category.to_string()
+            );
+        }
+        let error = InvalidConversion::Suit('p');
+        assert_eq!(
+            Language::English.card_error(&error),
+            format!("card is invalid: {error}")
+        );
+        let error = HandConstructionError::Uniqueness(4);
+        assert_eq!(
+            Language::English.hand_error(&error),
+            format!("hand is invalid: {error}")
+        );
+    }
+
+    #[test]
+    fn english_errors_match_display() {
+        let card = "1s".parse().unwrap();
+        let english = Language::English;
+        for error in [
+            OddsError::HoleCards(3),
+            OddsError::Board(5),
+            OddsError::DuplicateCard(card),
+        ] {
+            assert_eq!(english.odds_error(&error), error.to_string());
+        }
+        for error in [PossibleError::Cards(5), PossibleError::DuplicateCard(card)] {
+            assert_eq!(english.possible_error(&error), error.to_string());
+        }
+        let rules_errors = [
+            RulesError::Players {
+                players: 11,
+                min: 2,
+                max: 10,
+            },
+            RulesError::MinPlayers(1),
+            RulesError::NotInDeck(card),
+        ];
+        for error in rules_errors {
+            assert_eq!(english.rules_error(&error), error.to_string());
+            let error = VariantError::Rules(error);
+            assert_eq!(english.variant_error(&error), error.to_string());
+        }
+        for error in [
+            VariantError::Cards {
+                given: 4,
+                expected: 5..=7,
+            },
+            VariantError::DuplicateCard(card),
+        ] {
+            assert_eq!(english.variant_error(&error), error.to_string());
+        }
+        let error = BenchmarkError::Cards(8);
+        assert_eq!(english.benchmark_error(&error), error.to_string());
+        for error in [
+            PercentileError::HoleCards(3),
+            PercentileError::Board(6),
+            PercentileError::DuplicateCard(card),
+        ] {
+            assert_eq!(english.percentile_error(&error), error.to_string());
+        }
+        for error in [
+            PaiGowError::Cards(6),
+            PaiGowError::DuplicateCard(card),
+            PaiGowError::Foul("1s,1h".to_owned()),
+        ] {
+            assert_eq!(english.paigow_error(&error), error.to_string());
+        }
+    }
+
+    #[test]
+    fn norwegian_is_translated() {
+        assert_eq!(
+            Language::Norwegian.category_name(HandCategory::FullHouse),
+            "Hus"
+        );
+        assert_eq!(
+            Language::Norwegian.card_error(&InvalidConversion::Rank('x')),
+            "kortet er ugyldig: x er ikke en gyldig verdi"
+        );
+        assert_eq!(
+            Language::Norwegian.too_many_cards(52),
+            "kortene er ugyldige: høyst 52 kort kan oppgis"
+        );
+        assert_eq!(
+            Language::Norwegian.variant_error(&VariantError::Cards {
+                given: 4,
+                expected: 5..=7
+            }),
+            "antall kort (4) må være fra 5 til 7"
+        );
+        assert_eq!(
+            Language::Norwegian.at_most("count", &1001, &1000),
+            "count (1001) må være høyst 1000"
+        );
+        assert_eq!(
+            Language::English.at_most("hands", &100_001, &100_000),
+            "hands (100001) must be at most 100000"
+        );
+        assert_eq!(
+            Language::English.demo_rate(0.25),
+            "Demo: 25 % of hands are dealt flushes or better"
+        );
+    }
+
+    #[test]
+    fn showdowns_are_explained() {
+        use crate::compare::strength;
+
+        let strength = |cards: &str| strength(&cards.parse().unwrap());
+        let pair = strength("2r,2s,5k,9h,jr");
+        let higher_kicker = strength("2k,2h,5r,9s,qr");
+        let flush = strength("2h,4h,6h,8h,th");
+        assert_eq!(
+            Language::English.explain_showdown(&flush, &pair),
+            "Flush beats one pair."
+        );
+        assert_eq!(
+            Language::English.explain_showdown(&higher_kicker, &pair),
+            "Both hands are one pair, and the first differing rank decides: Q beats J."
+        );
+        assert_eq!(
+            Language::Norwegian.explain_showdown(&pair, &pair),
+            "Hendene er like sterke og deler potten."
+        );
+    }
+
+    #[test]
+    fn strengths_are_named_by_their_ranks() {
+        use crate::compare::strength;
+
+        let name = |language: Language, cards: &str| {
+            language.strength_name(&strength(&cards.parse().unwrap()))
+        };
+        assert_eq!(
+            name(Language::English, "qs,qh,qr,9k,9s"),
+            "Queens over Nines full house"
+        );
+        assert_eq!(
+            name(Language::English, "1s,2h,3r,4k,5s"),
+            "Five-high straight"
+        );
+        assert_eq!(name(Language::English, "1s,kh,8r,4k,2s"), "Ace high");
+        assert_eq!(
+            name(Language::Norwegian, "ts,th,4r,4k,2s"),
+            "To par, tiere og firere"
+        );
+    }
+
+    #[test]
+    fn tags_are_matched_by_primary_language() {
+        assert_eq!(Language::from_tag("en-GB"), Some(Language::English));
+        assert_eq!(Language::from_tag("NB-no"), Some(Language::Norwegian));
+        assert_eq!(Language::from_tag("nn"), Some(Language::Norwegian));
+        assert_eq!(Language::from_tag("sv"), None);
+    }
+
+    #[test]
+    fn accept_language_is_ordered_by_quality() {
+        assert_eq!(
+            Language::from_accept_language("nb-NO, en;q=0.8"),
+            Some(Language::Norwegian)
+        );
+        assert_eq!(
+            Language::from_accept_language("sv, nb;q=0.5, en;q=0.7"),
+            Some(Language::English)
+        );
+        assert_eq!(Language::from_accept_language("sv, de"), None);
+        assert_eq!(Language::from_accept_language("en;q=0"), None);
+    }
+}
@@ -0,0 +1,371 @@
+//! Simulations which take too long for a single request, run as background jobs.
+//!
+//! A job is started with [`Jobs::start_simulation`], which returns its ID right away. The
+//! simulation then runs on a blocking thread of the tokio runtime, and its progress and result can
+//! be looked up by ID with [`Jobs::get`], or followed as it changes with [`Jobs::subscribe`].
+//!
+//! A simulation runs for its iterations, or stops early once it has converged: when the 95 %
+//! confidence interval of the share of every category is narrower than its target, see
+//! [`half_width`]. Convergence is checked between chunks of 10,000 hands, so a simulation with the
+//! same seed and target always stops after the same hands.
+//!
+//! A simulation may also be given a time limit, after which it stops with the hands drawn so far,
+//! marked as truncated. Unlike convergence, where a time limit stops depends on the machine.
+//!
+//! Jobs are kept in the memory of the process which started them. A lambda instance may be frozen
+//! or stopped between requests, so jobs are only reliable in the server binary.
+//!
+//! Each running job takes a core, so at most a limited number run at once, and starting another
+//! fails with [`JobsError::Busy`] until one is done. Expensive requests, which take a core while
+//! they are served, count as jobs too, and are refused by the router while the most are running,
+//! see [`Jobs::reserve`], so cheap endpoints like `/draw` stay responsive under load. A finished
+//! job is kept for [`RETENTION`], and only the [`MAX_FINISHED`] most recently finished ones, so
+//! jobs do not pile up in memory.
+
+use crate::{
+    deck::seeded_rng,
+    hand::HandCategory,
+    simulate::{category_frequencies, half_width},
+};
+use serde::Serialize;
+use std::{
+    collections::{hash_map::Entry, BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{watch, OwnedSemaphorePermit, Semaphore};
+
+/// Hands drawn between each progress update of a simulation.
+const CHUNK: u64 = 10_000;
+
+/// The default of how many jobs run at once.
+pub const DEFAULT_MAX_RUNNING: usize = 4;
+
+/// How long a finished job is kept.
+pub const RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// The most finished jobs kept at once.
+pub const MAX_FINISHED: usize = 1000;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum JobsError {
+    #[error("{0} jobs are already running, which is the most at once")]
+    Busy(usize),
+}
+
+/// The state of a job.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Job {
+    pub id: u64,
+    pub status: JobStatus,
+    /// The seed of the simulation, which reproduces its result.
+    pub seed: u64,
+    /// The most iterations to simulate.
+    pub iterations: u64,
+    /// Stop once the [`half_width`] is at most this, if given.
+    pub target_half_width: Option<f64>,
+    /// Iterations simulated so far.
+    pub completed: u64,
+    /// The [`half_width`] of the iterations so far, once there are any.
+    pub half_width: Option<f64>,
+    /// Whether the job stopped at its time limit, before its iterations or convergence.
+    pub truncated: bool,
+    /// How often each [`HandCategory`] occurred in the iterations so far, once there are any.
+    pub frequencies: Option<BTreeMap<HandCategory, u64>>,
+}
+
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Done,
+}
+
+/// All jobs started by this process, which are running or recently finished.
+#[derive(Debug)]
+pub struct Jobs {
+    next_id: AtomicU64,
+    jobs: Mutex<HashMap<u64, Slot>>,
+    max_running: usize,
+    running: Arc<Semaphore>,
+}
+
+/// A job, and when it finished, if it has.
+#[derive(Debug)]
+struct Slot {
+    job: watch::Sender<Job>,
+    finished_at: Option<Instant>,
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self::with_max_running(DEFAULT_MAX_RUNNING)
+    }
+}
+
+impl Jobs {
+    /// Jobs of which at most `max_running` run at once.
+    pub fn with_max_running(max_running: usize) -> Self {
+        Self {
+            next_id: AtomicU64::default(),
+            jobs: Mutex::default(),
+            max_running,
+            running: Arc::new(Semaphore::new(max_running)),
+        }
+    }
+
+    /// Start simulating up to `iterations` draws with `seed` in the background, stopping early at
+    /// the `target_half_width` or the `time_limit`, if given, and return the job ID.
+    pub fn start_simulation(
+        self: &Arc<Self>,
+        iterations: u64,
+        seed: u64,
+        target_half_width: Option<f64>,
+        time_limit: Option<Duration>,
+    ) -> Result<u64, JobsError> {
+        let permit = self.reserve()?;
+        self.evict_finished(Instant::now());
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let job = Job {
+            id,
+            status: JobStatus::Running,
+            seed,
+            iterations,
+            target_half_width,
+            completed: 0,
+            half_width: None,
+            truncated: false,
+            frequencies: None,
+        };
+        self.update(job.clone());
+        let jobs = Arc::clone(self);
+        let deadline = time_limit.map(|limit| Instant::now() + limit);
+        tokio::task::spawn_blocking(move || jobs.simulate(job, deadline, permit));
+        Ok(id)
+    }
+
+    /// Reserve a place among the running jobs for work which is not a job, like an expensive
+    /// request, until the permit is dropped.
+    pub fn reserve(&self) -> Result<OwnedSemaphorePermit, JobsError> {
+        Arc::clone(&self.running)
+            .try_acquire_owned()
+            .map_err(|_| JobsError::Busy(self.max_running))
+    }
+
+    /// The current state of the job, if it exists.
+    pub fn get(&self, id: u64) -> Option<Job> {
+        self.jobs
+            .lock()
+            .expect("updates never panic")
+            .get(&id)
+            .map(|slot| slot.job.borrow().clone())
+    }
+
+    /// A receiver of the state of the job each time it changes, if the job exists. Changes between
+    /// reads of the receiver are skipped, so a slow reader only sees the latest state.
+    pub fn subscribe(&self, id: u64) -> Option<watch::Receiver<Job>> {
+        self.jobs
+            .lock()
+            .expect("updates never panic")
+            .get(&id)
+            .map(|slot| slot.job.subscribe())
+    }
+
+    /// Run the simulation, holding the `permit` to run until the job is done.
+    fn simulate(&self, mut job: Job, deadline: Option<Instant>, permit: OwnedSemaphorePermit) {
+        // Drawing in chunks from one generator draws the same hands as drawing all at once.
+        let mut rng = seeded_rng(job.seed);
+        let mut frequencies = BTreeMap::new();
+        let converged = |job: &Job| {
+            job.half_width
+                .zip(job.target_half_width)
+                .is_some_and(|(half_width, target)| half_width <= target)
+        };
+        while job.completed < job.iterations && !converged(&job) {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                job.truncated = true;
+                break;
+            }
+            let chunk = CHUNK.min(job.iterations - job.completed);
+            for (category, count) in category_frequencies(chunk, &mut rng) {
+                *frequencies.entry(category).or_insert(0) += count;
+            }
+            job.completed += chunk;
+            job.half_width = Some(half_width(&frequencies, job.completed));
+            job.frequencies = Some(frequencies.clone());
+            self.update(job.clone());
+        }
+        job.status = JobStatus::Done;
+        job.frequencies = Some(frequencies);
+        // Another job may start as soon as this one is seen to be done.
+        drop(permit);
+        self.update(job);
+    }
+
+    fn update(&self, job: Job) {
+        let finished_at = (job.status == JobStatus::Done).then(Instant::now);
+        match self.jobs.lock().expect("updates never panic").entry(job.id) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().finished_at = finished_at;
+                entry.get().job.send_replace(job);
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Slot {
+                    job: watch::channel(job).0,
+                    finished_at,
+                });
+            }
+        }
+    }
+
+    /// Forget the jobs which finished more than [`RETENTION`] before `now`, and the oldest
+    /// finished jobs beyond [`MAX_FINISHED`].
+    fn evict_finished(&self, now: Instant) {
+        let mut jobs = self.jobs.lock().expect("updates never panic");
+        jobs.retain(|_, slot| {
+            slot.finished_at
+                .is_none_or(|finished_at| now.duration_since(finished_at) < RETENTION)
+        });
+        let mut finished: Vec<(Instant, u64)> = jobs
+            .iter()
+            .filter_map(|(&id, slot)| Some((slot.finished_at?, id)))
+            .collect();
+        if finished.len() > MAX_FINISHED {
+            finished.sort_unstable();
+            for (_, id) in &finished[..finished.len() - MAX_FINISHED] {
+                jobs.remove(id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn wait_until_done(jobs: &Jobs, id: u64) -> Job {
+        loop {
+            let job = jobs.get(id).unwrap();
+            if job.status == JobStatus::Done {
+                return job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn simulation_completes() {
+        let jobs = Arc::new(Jobs::default());
+        let id = jobs.start_simulation(25_000, 42, None, None).unwrap();
+        let job = wait_until_done(&jobs, id).await;
+        assert_eq!(job.completed, 25_000);
+        assert_eq!(
+            job.frequencies.unwrap(),
+            category_frequencies(25_000, &mut seeded_rng(42))
+        );
+    }
+
+    #[tokio::test]
+    async fn simulation_stops_once_converged() {
+        let jobs = Arc::new(Jobs::default());
+        // High card, with the widest interval, needs about 94,000 hands for a half-width of 0.0032,
+        // so the simulation stops after the chunk ending at 100,000.
+        let id = jobs
+            .start_simulation(1_000_000, 42, Some(0.0032), None)
+            .unwrap();
+        let job = wait_until_done(&jobs, id).await;
+        assert_eq!(job.completed, 100_000);
+        assert!(job.half_width.unwrap() <= 0.0032);
+        assert_eq!(
+            job.frequencies.unwrap(),
+            category_frequencies(100_000, &mut seeded_rng(42))
+        );
+    }
+
+    #[tokio::test]
+    async fn simulation_is_truncated_at_time_limit() {
+        let jobs = Arc::new(Jobs::default());
+        let id = jobs
+            .start_simulation(u64::MAX, 42, None, Some(Duration::from_millis(20)))
+            .unwrap();
+        let job = wait_until_done(&jobs, id).await;
+        assert!(job.truncated);
+        assert!(job.completed < job.iterations);
+        let frequencies = job.frequencies.unwrap();
+        assert_eq!(frequencies.values().sum::<u64>(), job.completed);
+    }
+
+    #[tokio::test]
+    async fn subscribers_follow_the_job_until_done() {
+        let jobs = Arc::new(Jobs::default());
+        let id = jobs.start_simulation(50_000, 42, None, None).unwrap();
+        let mut receiver = jobs.subscribe(id).unwrap();
+        let mut completed = 0;
+        loop {
+            let job = receiver.borrow_and_update().clone();
+            assert!(job.completed >= completed);
+            completed = job.completed;
+            if job.status == JobStatus::Done {
+                break;
+            }
+            receiver.changed().await.unwrap();
+        }
+        assert_eq!(completed, 50_000);
+        assert!(jobs.subscribe(id + 1).is_none());
+    }
+
+    #[tokio::test]
+    async fn jobs_get_distinct_ids() {
+        let jobs = Arc::new(Jobs::default());
+        let a = jobs.start_simulation(1, 0, None, None).unwrap();
+        let b = jobs.start_simulation(1, 0, None, None).unwrap();
+        assert_ne!(a, b);
+        assert!(jobs.get(a + b + 1).is_none());
+    }
+
+    #[tokio::test]
+    async fn running_jobs_are_limited() {
+        let jobs = Arc::new(Jobs::with_max_running(1));
+        let id = jobs
+            .start_simulation(u64::MAX, 42, None, Some(Duration::from_millis(50)))
+            .unwrap();
+        assert_eq!(
+            jobs.start_simulation(1, 0, None, None),
+            Err(JobsError::Busy(1))
+        );
+        wait_until_done(&jobs, id).await;
+        assert!(jobs.start_simulation(1, 0, None, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn finished_jobs_are_evicted() {
+        let jobs = Arc::new(Jobs::default());
+        let id = jobs.start_simulation(1, 0, None, None).unwrap();
+        wait_until_done(&jobs, id).await;
+        jobs.evict_finished(Instant::now());
+        assert!(jobs.get(id).is_some());
+        jobs.evict_finished(Instant::now() + RETENTION);
+        assert!(jobs.get(id).is_none());
+
+        for seed in 0..=MAX_FINISHED as u64 {
+            jobs.update(Job {
+                id: seed,
+                status: JobStatus::Done,
+                seed,
+                iterations: 0,
+                target_half_width: None,
+                completed: 0,
+                half_width: None,
+                truncated: false,
+                frequencies: None,
+            });
+        }
+        jobs.evict_finished(Instant::now());
+        assert_eq!(jobs.jobs.lock().unwrap().len(), MAX_FINISHED);
+        assert!(jobs.get(0).is_none(), "the oldest is evicted first");
+    }
+}
@@ -0,0 +1,79 @@
+//! Serving the [router](crate::router) as an AWS Lambda function.
+//!
+//! The same function serves the events of every deployment style:
+//!
+//! * Lambda Function URLs and API Gateway HTTP APIs, with the payload format version 2.0.
+//! * API Gateway REST APIs, with the payload format version 1.0.
+//! * Application Load Balancer target groups, with or without multi-value headers enabled.
+//!
+//! The format of each event is recognized from its shape, so nothing needs to be configured. Base64
+//! encoded bodies, like ALBs send for all bodies, are decoded before they reach the router, and the
+//! response is given in the format of the event.
+//!
+//! The router and its shared state are built once, in the init phase of the function, and serve
+//! every invocation of the instance. Subsystems which few requests use, like the quiz, are built
+//! on first use instead. Schedule requests to `/warmup` to keep instances warm.
+
+use crate::{config::RouterConfig, deck, router::create_with};
+
+/// Serve the router with the `config` to the Lambda runtime, until the runtime stops.
+pub async fn run(config: &RouterConfig) -> Result<(), lambda_http::Error> {
+    deck::initialize();
+    let router = create_with(config);
+    lambda_http::run(router).await
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::router::create;
+    use axum::http::StatusCode;
+    use lambda_http::{request::from_str, RequestExt};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    /// Serve a captured event with the router, and give the response status and JSON body.
+    async fn serve(event: &str) -> (StatusCode, Value) {
+        let request = from_str(event).expect("the captured event is valid");
+        let response = create().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn function_url_event_is_served() {
+        let event = include_str!("../fixtures/lambda/function_url.json");
+        let request = from_str(event).unwrap();
+        assert_eq!(request.query_string_parameters().first("lang"), Some("no"));
+
+        let (status, body) = serve(event).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "StraightFlush");
+    }
+
+    #[tokio::test]
+    async fn alb_event_is_served() {
+        let (status, body) = serve(include_str!("../fixtures/lambda/alb.json")).await;
+        assert_eq!(status, StatusCode::OK);
+        // The query string is passed on, and selects the language.
+        let category = serde_json::from_value(body["category"].clone()).unwrap();
+        assert_eq!(
+            body["category_name"],
+            crate::i18n::Language::Norwegian.category_name(category)
+        );
+    }
+
+    #[tokio::test]
+    async fn alb_multi_value_event_with_base64_body_is_served() {
+        let event = include_str!("../fixtures/lambda/alb_multi_value.json");
+        let request = from_str(event).unwrap();
+        assert_eq!(
+            request.headers().get_all("x-forwarded-for").iter().count(),
+            2
+        );
+
+        let (status, body) = serve(event).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(body["id"].is_u64());
+    }
+}
@@ -1,7 +1,97 @@
 //! Collection of functionality for modeling five-card poker hands and a web server to access it.
+//!
+//! The web server lives behind the default `server` feature. Disable default features to depend on
+//! the poker model alone. Without the `std` feature, only the [`card`], [`hand`] and [`classify`]
+//! modules are available, and they neither use the standard library nor allocate.
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "lambda")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod benchmark;
+#[cfg(feature = "std")]
+pub mod best;
+#[cfg(feature = "server")]
+pub mod cache;
+#[cfg(feature = "server")]
+pub mod canonical;
 pub mod card;
+#[cfg(feature = "std")]
+pub mod casino;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+#[cfg(feature = "std")]
+pub mod chips;
 pub mod classify;
+#[cfg(feature = "std")]
+pub mod compare;
+#[cfg(feature = "server")]
+pub mod config;
+#[cfg(feature = "server")]
+pub mod conformance;
+#[cfg(feature = "std")]
 pub mod deck;
+#[cfg(feature = "demo")]
+pub mod demo;
+#[cfg(feature = "server")]
+pub mod entropy;
+#[cfg(feature = "std")]
+pub mod environment;
+#[cfg(feature = "std")]
+pub mod equity;
+#[cfg(feature = "std")]
+pub mod evaluate;
+#[cfg(feature = "server")]
+pub mod examples;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "server")]
+pub mod fields;
+#[cfg(feature = "std")]
+pub mod group;
 pub mod hand;
+#[cfg(feature = "std")]
+pub mod hour;
+#[cfg(feature = "std")]
+pub mod i18n;
+#[cfg(feature = "server")]
+pub mod jobs;
+#[cfg(feature = "lambda")]
+pub mod lambda;
+#[cfg(feature = "server")]
+pub mod logging;
+#[cfg(feature = "std")]
+pub mod low;
+#[cfg(feature = "server")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod odds;
+#[cfg(feature = "std")]
+pub mod paigow;
+#[cfg(feature = "std")]
+pub mod percentile;
+#[cfg(feature = "std")]
+pub mod possible;
+#[cfg(feature = "server")]
+pub mod quiz;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "server")]
 pub mod router;
+#[cfg(feature = "std")]
+pub mod rules;
+#[cfg(feature = "std")]
+pub mod shoe;
+#[cfg(feature = "std")]
+pub mod shuffle;
+#[cfg(feature = "signing")]
+pub mod signing;
+#[cfg(feature = "std")]
+pub mod simulate;
+#[cfg(feature = "test_util")]
+pub mod test_util;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod variant;
@@ -0,0 +1,98 @@
+//! Logging of the requests served by the [router](crate::router).
+//!
+//! Every request is logged once it has been served, within a `request` span, with these fields:
+//!
+//! * `request_id`: the `x-request-id` header of the request, or a random ID if it has none. The ID
+//!   is returned in the `x-request-id` header of the response.
+//! * `method` and `route`, where the route is the matched path pattern, e.g. `/analyze/:cards`.
+//! * `status` and `latency_ms`.
+//! * `category`: the [`HandCategory`] of the classified hand, for endpoints classifying one hand.
+//!
+//! Handlers provide the category by adding it to the response's extensions, e.g. by returning
+//! [`Extension`](axum::Extension)`(category)` as part of the response.
+
+use crate::hand::HandCategory;
+use axum::{
+    extract::MatchedPath,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::{field, info, info_span, Instrument};
+
+pub static REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Middleware which logs each request. Use with [`axum::middleware::from_fn`].
+pub async fn log_request<B>(
+    matched_path: Option<MatchedPath>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID)
+        .cloned()
+        .unwrap_or_else(|| {
+            HeaderValue::from_str(&format!("{:016x}", rand::random::<u64>()))
+                .expect("hex digits are a valid header value")
+        });
+    let route = matched_path.as_ref().map_or_else(
+        || request.uri().path().to_owned(),
+        |path| path.as_str().to_owned(),
+    );
+    let span = info_span!(
+        "request",
+        request_id = request_id.to_str().unwrap_or_default(),
+        method = %request.method(),
+        route,
+    );
+
+    let start = Instant::now();
+    let mut response = next.run(request).instrument(span.clone()).await;
+    let latency = start.elapsed();
+
+    span.in_scope(|| {
+        info!(
+            status = response.status().as_u16(),
+            latency_ms = latency.as_secs_f64() * 1000.0,
+            category = response
+                .extensions()
+                .get::<HandCategory>()
+                .map(field::debug),
+            "served"
+        );
+    });
+    response
+        .headers_mut()
+        .insert(REQUEST_ID.clone(), request_id);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::create;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn generates_request_id() {
+        let response = create()
+            .oneshot(Request::get("/draw").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let request_id = response.headers()[&REQUEST_ID].to_str().unwrap();
+        assert_eq!(request_id.len(), 16);
+    }
+
+    #[tokio::test]
+    async fn echoes_request_id() {
+        let request = Request::get("/analyze/tr,jr,qr,kr,1r")
+            .header(&REQUEST_ID, "abc-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[&REQUEST_ID], "abc-123");
+    }
+}
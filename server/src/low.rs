@@ -0,0 +1,195 @@
+//! Ace-to-Five low hands, for Razz and the low half of Seven Card Stud Hi/Lo.
+//!
+//! In Ace-to-Five low, the Ace is the lowest card, and straights and flushes do not count against a
+//! hand, so the best hand is A-2-3-4-5, "the wheel". Hands with paired ranks are worse than any hand
+//! without, and otherwise compare by their highest card, then the next, and so on.
+//!
+//! * Razz: the best low of the seven cards wins the whole pot, see [`best_low`].
+//! * Stud Hi/Lo: the best high hand and the best low hand split the pot, but only lows of Eight or
+//!   better qualify. The high hand scoops the pot if no hand qualifies for low, see [`hi_lo`] and
+//!   [`split`].
+
+use crate::{
+    card::Card,
+    compare::{strength, Strength},
+    hand::{Hand, HandCategory},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::cmp::{Ordering, Reverse};
+
+/// The strength of an Ace-to-Five low [`Hand`].
+///
+/// Low strengths are ordered like [`Strength`]s: a greater low strength is the better low hand,
+/// even though its cards are lower.
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Serialize)]
+pub struct LowStrength {
+    /// One of the categories made by rank alone: from [`HandCategory::HighCard`], the best, to
+    /// [`HandCategory::FourOfAKind`].
+    pub category: HandCategory,
+    /// Numeric ranks (Ace counted as 1), most significant first.
+    pub ranks: Vec<u8>,
+}
+
+impl Ord for LowStrength {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.category, &other.ranks).cmp(&(self.category, &self.ranks))
+    }
+}
+
+impl PartialOrd for LowStrength {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl LowStrength {
+    /// Whether the low qualifies in Hi/Lo games: five unpaired cards, Eight or lower.
+    pub fn is_eight_or_better(&self) -> bool {
+        self.category == HandCategory::HighCard && self.ranks[0] <= 8
+    }
+}
+
+/// Compute the Ace-to-Five [`LowStrength`] of the [`Hand`].
+pub fn low_strength(hand: &Hand) -> LowStrength {
+    use HandCategory::*;
+
+    // Group the ranks by how many times they occur, then by how high they are, like in
+    // `compare::strength`, but with the Ace low.
+    let counts = hand.cards().map(|card| card.rank.numeric()).counts();
+    let groups: Vec<(usize, u8)> = counts
+        .into_iter()
+        .map(|(rank, count)| (count, rank))
+        .sorted_by_key(|&group| Reverse(group))
+        .collect();
+    let category = match groups.iter().map(|&(count, _)| count).collect_vec()[..] {
+        [4, ..] => FourOfAKind,
+        [3, 2] => FullHouse,
+        [3, ..] => ThreeOfAKind,
+        [2, 2, ..] => TwoPair,
+        [2, ..] => OnePair,
+        _ => HighCard,
+    };
+    LowStrength {
+        category,
+        ranks: groups.into_iter().map(|(_, rank)| rank).collect(),
+    }
+}
+
+/// The five cards which make the best Ace-to-Five low, like in Razz, with its strength, or `None`
+/// if there are fewer than five unique cards.
+pub fn best_low(cards: &[Card]) -> Option<(Hand, LowStrength)> {
+    cards
+        .iter()
+        .copied()
+        .combinations(5)
+        .filter_map(|five| Hand::try_from(five.as_slice()).ok())
+        .map(|hand| {
+            let low = low_strength(&hand);
+            (hand, low)
+        })
+        .max_by(|(_, a), (_, b)| a.cmp(b))
+}
+
+/// The high and low hands of a player in Stud Hi/Lo.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct HiLo {
+    pub high: Hand,
+    pub high_strength: Strength,
+    /// The best low, if it is Eight or better.
+    pub low: Option<(Hand, LowStrength)>,
+}
+
+/// The best high hand and the best qualifying low hand of the cards, which may share cards, or
+/// `None` if there are fewer than five unique cards.
+pub fn hi_lo(cards: &[Card]) -> Option<HiLo> {
+    let high = crate::best::best_hand(cards)?;
+    let low = best_low(cards).filter(|(_, low)| low.is_eight_or_better());
+    Some(HiLo {
+        high_strength: strength(&high),
+        high,
+        low,
+    })
+}
+
+/// The indices of the players winning the high half and the low half of the pot in Stud Hi/Lo.
+/// The low winners are empty if no player qualifies for low, and the high winners scoop the pot.
+pub fn split(players: &[HiLo]) -> (Vec<usize>, Vec<usize>) {
+    let high = players
+        .iter()
+        .map(|player| &player.high_strength)
+        .max()
+        .map_or_else(Vec::new, |best| {
+            players
+                .iter()
+                .positions(|player| player.high_strength == *best)
+                .collect()
+        });
+    let low = players
+        .iter()
+        .filter_map(|player| player.low.as_ref().map(|(_, low)| low))
+        .max()
+        .map_or_else(Vec::new, |best| {
+            players
+                .iter()
+                .positions(|player| player.low.as_ref().map(|(_, low)| low) == Some(best))
+                .collect()
+        });
+    (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    fn low(hand: &str) -> LowStrength {
+        low_strength(&hand.parse().unwrap())
+    }
+
+    #[test]
+    fn wheel_is_the_best_low() {
+        let wheel = low("1s,2s,3s,4s,5s");
+        assert_eq!(wheel.category, HandCategory::HighCard);
+        assert_eq!(wheel.ranks, [5, 4, 3, 2, 1]);
+        assert!(wheel > low("1h,2r,3k,4s,6s"));
+        assert!(low("8h,5r,4k,3s,2s") > low("8h,6r,4k,3s,2s"));
+    }
+
+    #[test]
+    fn pairs_lose_to_any_unpaired_low() {
+        assert!(low("kh,qr,jk,9s,8s") > low("1h,1r,2k,3s,4s"));
+        assert!(low("2h,2r,3k,4s,5s") > low("1h,1r,2k,2s,4s"));
+    }
+
+    #[test]
+    fn razz_plays_the_lowest_five_of_seven() {
+        let (hand, low) = best_low(&cards("kh,1s,7r,1h,4k,2s,5r")).unwrap();
+        assert_eq!(low.ranks, [7, 5, 4, 2, 1]);
+        assert!(!hand.cards().any(|card| card.to_string() == "kh"));
+        assert!(best_low(&cards("kh,1s,7r,1h")).is_none());
+    }
+
+    #[test]
+    fn hi_lo_needs_eight_or_better_for_low() {
+        let player = hi_lo(&cards("1s,2s,3s,5s,8s,kh,kr")).unwrap();
+        assert_eq!(player.high_strength.category, HandCategory::Flush);
+        assert_eq!(player.low.as_ref().unwrap().1.ranks, [8, 5, 3, 2, 1]);
+
+        let player = hi_lo(&cards("1s,2s,3h,9s,ts,kh,kr")).unwrap();
+        assert!(player.low.is_none());
+    }
+
+    #[test]
+    fn split_gives_the_pot_to_high_without_a_low() {
+        let flush = hi_lo(&cards("ks,qs,9s,7s,3s,kh,kr")).unwrap();
+        let six_low = hi_lo(&cards("1h,2r,3k,4h,6r,jk,qh")).unwrap();
+        assert_eq!(split(&[flush.clone(), six_low]), (vec![0], vec![1]));
+
+        let straight = hi_lo(&cards("9h,9r,tk,jh,qr,kk,1h")).unwrap();
+        assert_eq!(split(&[straight, flush]), (vec![1], vec![]));
+    }
+}
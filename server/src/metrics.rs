@@ -0,0 +1,266 @@
+//! Request metrics of the [router](crate::router), exported in the Prometheus text format by the
+//! `/metrics` endpoint.
+//!
+//! Per route, i.e., matched path pattern like `/analyze/:cards`, these metrics are recorded:
+//!
+//! * `http_request_duration_seconds`: a histogram of the latency of served requests.
+//! * `http_requests_over_threshold_total`: requests slower than the configured latency threshold.
+//!   Divided by the request count, this is the share of the error budget used by slow requests.
+//! * `http_request_errors_total`: requests answered with a server error.
+//!
+//! Requests which match no route are recorded under the route `unmatched`.
+//!
+//! Besides, `analyze_cache_hits_total` and `analyze_cache_misses_total` count the lookups in the
+//! cache of `/analyze/:cards` results, and, with an entropy source configured, the gauge
+//! `entropy_source_healthy` is 1 while random numbers come from it, and 0 while they come from
+//! the operating system instead, see [`crate::entropy`].
+
+use crate::{
+    entropy::FallbackRandomness,
+    environment::{Clock, SystemClock},
+};
+use axum::{
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+/// Upper bounds, in seconds, of the latency histogram buckets. A last bucket holds the rest.
+const BUCKETS: [f64; 11] = [
+    0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 1.0, 10.0,
+];
+
+/// Metrics of all routes, shared by the middleware recording them and the endpoint exporting them.
+#[derive(Debug)]
+pub struct Metrics {
+    latency_threshold: Duration,
+    /// Times the requests.
+    clock: Arc<dyn Clock>,
+    routes: Mutex<BTreeMap<String, RouteMetrics>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    /// The randomness whose entropy source is reported on.
+    entropy: Option<Arc<FallbackRandomness>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct RouteMetrics {
+    /// Requests per bucket of [`BUCKETS`], plus the last bucket.
+    buckets: [u64; BUCKETS.len() + 1],
+    latency_sum: Duration,
+    count: u64,
+    over_threshold: u64,
+    errors: u64,
+}
+
+impl Metrics {
+    /// Requests slower than `latency_threshold` are counted as over the threshold.
+    pub fn new(latency_threshold: Duration) -> Self {
+        Self::with_clock(latency_threshold, Arc::new(SystemClock))
+    }
+
+    /// Create metrics like [`Metrics::new`], which time requests with `clock`.
+    pub fn with_clock(latency_threshold: Duration, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            latency_threshold,
+            clock,
+            routes: Mutex::default(),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            entropy: None,
+        }
+    }
+
+    /// Report whether the `entropy` source is healthy, if there is one.
+    #[must_use]
+    pub fn with_entropy(self, entropy: Option<Arc<FallbackRandomness>>) -> Self {
+        Self { entropy, ..self }
+    }
+
+    /// Record a lookup in the analyze cache.
+    pub fn record_cache(&self, hit: bool) {
+        let counter = if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a request to `route` which was served in `latency`.
+    pub fn record(&self, route: &str, latency: Duration, server_error: bool) {
+        let mut routes = self.routes.lock().expect("recording never panics");
+        let metrics = routes.entry(route.to_owned()).or_default();
+        let bucket = BUCKETS
+            .iter()
+            .position(|&bound| latency.as_secs_f64() <= bound)
+            .unwrap_or(BUCKETS.len());
+        metrics.buckets[bucket] += 1;
+        metrics.latency_sum += latency;
+        metrics.count += 1;
+        metrics.over_threshold += u64::from(latency > self.latency_threshold);
+        metrics.errors += u64::from(server_error);
+    }
+
+    /// Render the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let routes = self.routes.lock().expect("recording never panics").clone();
+        let mut out = String::new();
+
+        out.push_str("# HELP http_request_duration_seconds Latency of served requests.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        for (route, metrics) in &routes {
+            let mut cumulative = 0;
+            for (bound, count) in BUCKETS.iter().zip(metrics.buckets) {
+                cumulative += count;
+                writeln!(
+                    out,
+                    "http_request_duration_seconds_bucket{{route=\"{route}\",le=\"{bound}\"}} {cumulative}"
+                )
+                .unwrap();
+            }
+            writeln!(
+                out,
+                "http_request_duration_seconds_bucket{{route=\"{route}\",le=\"+Inf\"}} {}",
+                metrics.count
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "http_request_duration_seconds_sum{{route=\"{route}\"}} {}",
+                metrics.latency_sum.as_secs_f64()
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "http_request_duration_seconds_count{{route=\"{route}\"}} {}",
+                metrics.count
+            )
+            .unwrap();
+        }
+
+        out.push_str("# HELP http_requests_over_threshold_total Requests slower than the latency threshold.\n");
+        out.push_str("# TYPE http_requests_over_threshold_total counter\n");
+        for (route, metrics) in &routes {
+            writeln!(
+                out,
+                "http_requests_over_threshold_total{{route=\"{route}\"}} {}",
+                metrics.over_threshold
+            )
+            .unwrap();
+        }
+
+        out.push_str("# HELP http_request_errors_total Requests answered with a server error.\n");
+        out.push_str("# TYPE http_request_errors_total counter\n");
+        for (route, metrics) in &routes {
+            writeln!(
+                out,
+                "http_request_errors_total{{route=\"{route}\"}} {}",
+                metrics.errors
+            )
+            .unwrap();
+        }
+
+        out.push_str("# HELP http_latency_threshold_seconds Latency above which requests count as over the threshold.\n");
+        out.push_str("# TYPE http_latency_threshold_seconds gauge\n");
+        writeln!(
+            out,
+            "http_latency_threshold_seconds {}",
+            self.latency_threshold.as_secs_f64()
+        )
+        .unwrap();
+
+        out.push_str("# HELP analyze_cache_hits_total Analyses served from the cache.\n");
+        out.push_str("# TYPE analyze_cache_hits_total counter\n");
+        writeln!(
+            out,
+            "analyze_cache_hits_total {}",
+            self.cache_hits.load(Ordering::Relaxed)
+        )
+        .unwrap();
+        out.push_str(
+            "# HELP analyze_cache_misses_total Analyses computed and added to the cache.\n",
+        );
+        out.push_str("# TYPE analyze_cache_misses_total counter\n");
+        writeln!(
+            out,
+            "analyze_cache_misses_total {}",
+            self.cache_misses.load(Ordering::Relaxed)
+        )
+        .unwrap();
+
+        if let Some(entropy) = &self.entropy {
+            out.push_str("# HELP entropy_source_healthy Whether the last random number came from the entropy source.\n");
+            out.push_str("# TYPE entropy_source_healthy gauge\n");
+            writeln!(
+                out,
+                "entropy_source_healthy {}",
+                u8::from(entropy.is_healthy())
+            )
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// Middleware which records each request. Use with [`axum::middleware::from_fn_with_state`].
+pub async fn record_request<B>(
+    State(metrics): State<Arc<Metrics>>,
+    matched_path: Option<MatchedPath>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let start = metrics.clock.now();
+    let response = next.run(request).await;
+    let route = matched_path
+        .as_ref()
+        .map_or("unmatched", MatchedPath::as_str);
+    let latency = metrics.clock.now() - start;
+    metrics.record(route, latency, response.status().is_server_error());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_per_route() {
+        let metrics = Metrics::new(Duration::from_millis(100));
+        metrics.record("/draw", Duration::from_micros(300), false);
+        metrics.record("/draw", Duration::from_millis(200), true);
+        metrics.record("/analyze/:cards", Duration::from_millis(20), false);
+        metrics.record_cache(true);
+        metrics.record_cache(false);
+        metrics.record_cache(false);
+
+        let rendered = metrics.render();
+        for line in [
+            r#"http_request_duration_seconds_bucket{route="/draw",le="0.0005"} 1"#,
+            r#"http_request_duration_seconds_bucket{route="/draw",le="0.1"} 1"#,
+            r#"http_request_duration_seconds_bucket{route="/draw",le="0.25"} 2"#,
+            r#"http_request_duration_seconds_bucket{route="/draw",le="+Inf"} 2"#,
+            r#"http_request_duration_seconds_count{route="/draw"} 2"#,
+            r#"http_requests_over_threshold_total{route="/draw"} 1"#,
+            r#"http_request_errors_total{route="/draw"} 1"#,
+            r#"http_request_duration_seconds_bucket{route="/analyze/:cards",le="0.01"} 0"#,
+            r#"http_request_duration_seconds_bucket{route="/analyze/:cards",le="0.025"} 1"#,
+            r#"http_requests_over_threshold_total{route="/analyze/:cards"} 0"#,
+            "http_latency_threshold_seconds 0.1",
+            "analyze_cache_hits_total 1",
+            "analyze_cache_misses_total 2",
+        ] {
+            assert!(rendered.contains(line), "{line} is missing in:\n{rendered}");
+        }
+    }
+}
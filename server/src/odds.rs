@@ -0,0 +1,192 @@
+//! The [`OddsTable`] of a Texas hold'em hand on the flop or the turn: how likely the hand is to
+//! improve to each [`HandCategory`] by the turn and by the river.
+//!
+//! Trainers show the odds estimated from the number of outs with the rule of 4 and 2: each out is
+//! worth about 2 % per card to come, or 4 % with two cards to come. The table gives these estimates
+//! next to the exact odds, which are found by enumerating every turn and river.
+
+use crate::{
+    card::Card,
+    deck::{remaining_cards, Combinations},
+    evaluate::{BitCount, Evaluator},
+    hand::HandCategory,
+};
+use serde::Serialize;
+
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct OddsTable {
+    /// The category of the best hand with the cards already dealt.
+    pub current: HandCategory,
+    /// One row per category above the current one which can still be made, from the lowest.
+    pub rows: Vec<OddsRow>,
+}
+
+/// The odds of ending up with the category or a better one.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct OddsRow {
+    pub category: HandCategory,
+    /// Cards which give the category or better on the next card.
+    pub outs: usize,
+    /// The odds by the turn, if the turn is still to come.
+    pub by_turn: Option<Odds>,
+    pub by_river: Odds,
+}
+
+/// A probability, from 0 to 1.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize)]
+pub struct Odds {
+    /// Found by enumerating the cards to come.
+    pub exact: f64,
+    /// Estimated with the rule of 4 and 2.
+    pub estimate: f64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum OddsError {
+    #[error("number of hole cards ({0}) must be 2")]
+    HoleCards(usize),
+    #[error("number of board cards ({0}) must be 3 or 4")]
+    Board(usize),
+    #[error("{0} is given more than once")]
+    DuplicateCard(Card),
+}
+
+/// Compute the [`OddsTable`] of the `hole` cards with the `board` of the flop (three cards) or the
+/// turn (four cards).
+pub fn odds_table(hole: &[Card], board: &[Card]) -> Result<OddsTable, OddsError> {
+    if hole.len() != 2 {
+        return Err(OddsError::HoleCards(hole.len()));
+    }
+    if !(3..=4).contains(&board.len()) {
+        return Err(OddsError::Board(board.len()));
+    }
+    let known: Vec<_> = hole.iter().chain(board).copied().collect();
+    for (i, card) in known.iter().enumerate() {
+        if known[..i].contains(card) {
+            return Err(OddsError::DuplicateCard(*card));
+        }
+    }
+
+    let current = BitCount.evaluate(&known).category;
+    let deck = remaining_cards(&known);
+    let with = |extra: &[Card]| {
+        let cards: Vec<_> = known.iter().chain(extra).copied().collect();
+        BitCount.evaluate(&cards).category
+    };
+    // The category after the next card, for each card in the deck.
+    let next: Vec<_> = deck.iter().map(|&card| with(&[card])).collect();
+    // The category by the river, for each pair of cards in the deck, if on the flop.
+    let by_river: Option<Vec<_>> = (known.len() == 5).then(|| {
+        Combinations::<2>::new(&known)
+            .map(|turn_and_river| with(&turn_and_river))
+            .collect()
+    });
+
+    #[allow(clippy::cast_precision_loss)]
+    let share = |categories: &[HandCategory], category: HandCategory| {
+        categories.iter().filter(|&&c| c >= category).count() as f64 / categories.len() as f64
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let estimate =
+        |outs: usize, percent_per_out: usize| ((outs * percent_per_out) as f64 / 100.0).min(1.0);
+
+    let rows = HandCategory::ALL
+        .into_iter()
+        .filter(|&category| category > current)
+        .filter_map(|category| {
+            let outs = next.iter().filter(|&&c| c >= category).count();
+            let row = match &by_river {
+                Some(by_river) => OddsRow {
+                    category,
+                    outs,
+                    by_turn: Some(Odds {
+                        exact: share(&next, category),
+                        estimate: estimate(outs, 2),
+                    }),
+                    by_river: Odds {
+                        exact: share(by_river, category),
+                        estimate: estimate(outs, 4),
+                    },
+                },
+                None => OddsRow {
+                    category,
+                    outs,
+                    by_turn: None,
+                    by_river: Odds {
+                        exact: share(&next, category),
+                        estimate: estimate(outs, 2),
+                    },
+                },
+            };
+            (row.by_river.exact > 0.0).then_some(row)
+        })
+        .collect();
+
+    Ok(OddsTable { current, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn flush_draw_on_the_flop() {
+        let table = odds_table(&cards("1s,ks"), &cards("7s,2s,9h")).unwrap();
+        assert_eq!(table.current, HandCategory::HighCard);
+        let flush = table
+            .rows
+            .iter()
+            .find(|row| row.category == HandCategory::Flush)
+            .unwrap();
+        // Nine spades are left, and none of them makes a full house or better.
+        assert_eq!(flush.outs, 9);
+        let by_turn = flush.by_turn.unwrap();
+        assert!((by_turn.exact - 9.0 / 47.0).abs() < 1e-9);
+        assert!((by_turn.estimate - 0.18).abs() < 1e-9);
+        // The classic 35 % of hitting a flush draw by the river.
+        assert!((flush.by_river.exact - 0.3497).abs() < 1e-3);
+        assert!((flush.by_river.estimate - 0.36).abs() < 1e-9);
+    }
+
+    #[test]
+    fn only_the_river_is_left_on_the_turn() {
+        let table = odds_table(&cards("1s,ks"), &cards("7s,2s,9h,3r")).unwrap();
+        let flush = table
+            .rows
+            .iter()
+            .find(|row| row.category == HandCategory::Flush)
+            .unwrap();
+        assert_eq!(flush.by_turn, None);
+        assert!((flush.by_river.exact - 9.0 / 46.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rows_are_above_current_category() {
+        let table = odds_table(&cards("1s,1h"), &cards("1r,ks,2h")).unwrap();
+        assert_eq!(table.current, HandCategory::ThreeOfAKind);
+        assert!(table
+            .rows
+            .iter()
+            .all(|row| row.category > HandCategory::ThreeOfAKind));
+    }
+
+    #[test]
+    fn invalid_cards_fail() {
+        assert!(matches!(
+            odds_table(&cards("1s"), &cards("7s,2s,9h")),
+            Err(OddsError::HoleCards(1))
+        ));
+        assert!(matches!(
+            odds_table(&cards("1s,ks"), &cards("7s,2s")),
+            Err(OddsError::Board(2))
+        ));
+        assert!(matches!(
+            odds_table(&cards("1s,ks"), &cards("7s,2s,1s")),
+            Err(OddsError::DuplicateCard(_))
+        ));
+    }
+}
@@ -0,0 +1,278 @@
+//! Pai Gow Poker: seven cards are set into a five-card high hand and a two-card low hand.
+//!
+//! The high hand must be stronger than the low hand, or the setting fouls. The low hand can only be
+//! a pair or high card. Hands are ranked as in [`crate::compare`], with the low hand's
+//! [`Strength`] given by its pair, or its two ranks from the highest.
+//!
+//! [`house_way`] sets the cards like a common house way:
+//!
+//! * No pair: play a straight or flush in the high hand, leaving the best low hand. Otherwise, keep
+//!   the highest card in the high hand, and the next two in the low hand.
+//! * One pair: play a straight or flush if the pair can then go in the low hand. Otherwise, keep the
+//!   pair in the high hand, and the two highest other cards in the low hand.
+//! * Two pair: split them, the higher pair in the high hand, unless the higher pair is Sixes or
+//!   lower and there is an Ace to play in the low hand.
+//! * Three pair: the highest pair goes in the low hand.
+//! * Three of a kind: keep it in the high hand, except three Aces, which play a pair of Aces high
+//!   and an Ace low.
+//! * Full house, or two three of a kinds: the highest pair, or a pair of the higher three, goes
+//!   in the low hand.
+//! * Four of a kind: a pair or three of a kind besides it goes in the low hand. Otherwise, four
+//!   Sevens or better are split into two pairs, and four Sixes or lower kept together.
+//!
+//! The deck has no joker, so the joker, which plays as an Ace or to complete a straight or flush,
+//! is not supported.
+
+use crate::{
+    card::Card,
+    compare::{strength, Strength},
+    hand::{Hand, HandCategory},
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+/// A high hand and a low hand, where the high hand is the stronger.
+#[derive(Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct Setting {
+    pub high: Hand,
+    /// The two cards of the low hand, the highest first.
+    pub low: [Card; 2],
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum PaiGowError {
+    #[error("number of cards ({0}) must be 7")]
+    Cards(usize),
+    #[error("{0} is given more than once")]
+    DuplicateCard(Card),
+    #[error("the low hand ({0}) must be weaker than the high hand")]
+    Foul(String),
+}
+
+/// The [`Strength`] of a two-card low hand.
+pub fn low_strength(low: &[Card; 2]) -> Strength {
+    let mut ranks = low.map(|card| card.rank.numeric_ace_high());
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+    if ranks[0] == ranks[1] {
+        Strength {
+            category: HandCategory::OnePair,
+            tie_breakers: vec![ranks[0]],
+        }
+    } else {
+        Strength {
+            category: HandCategory::HighCard,
+            tie_breakers: ranks.to_vec(),
+        }
+    }
+}
+
+impl Setting {
+    /// Set the `high` and `low` hands, which fails if the low hand is not the weaker.
+    pub fn new(high: Hand, mut low: [Card; 2]) -> Result<Self, PaiGowError> {
+        if low_strength(&low) >= strength(&high) {
+            return Err(PaiGowError::Foul(format!("{},{}", low[0], low[1])));
+        }
+        low.sort_unstable_by_key(|card| Reverse(card.rank.numeric_ace_high()));
+        Ok(Self { high, low })
+    }
+
+    pub fn high_strength(&self) -> Strength {
+        strength(&self.high)
+    }
+
+    pub fn low_strength(&self) -> Strength {
+        low_strength(&self.low)
+    }
+}
+
+/// Set the seven `cards` by the house way. See the [module](self) for the rules.
+pub fn house_way(cards: &[Card]) -> Result<Setting, PaiGowError> {
+    if cards.len() != 7 {
+        return Err(PaiGowError::Cards(cards.len()));
+    }
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) {
+            return Err(PaiGowError::DuplicateCard(*card));
+        }
+    }
+
+    let low = low_hand(cards);
+    let high: Vec<_> = cards
+        .iter()
+        .filter(|card| !low.contains(card))
+        .copied()
+        .collect();
+    let high = Hand::try_from(high.as_slice()).expect("five unique cards are left");
+    Ok(Setting::new(high, low).expect("the house way never fouls"))
+}
+
+/// The two cards the house way plays in the low hand.
+fn low_hand(cards: &[Card]) -> [Card; 2] {
+    // The cards grouped by rank, the largest groups and then the highest ranks first.
+    let groups: Vec<Vec<Card>> = cards
+        .iter()
+        .copied()
+        .sorted_by_key(|card| Reverse(card.rank.numeric_ace_high()))
+        .group_by(|card| card.rank)
+        .into_iter()
+        .map(|(_, group)| group.collect::<Vec<_>>())
+        .sorted_by_key(|group| Reverse(group.len()))
+        .collect();
+    let of_size = |size: usize| -> Vec<&Vec<Card>> {
+        groups.iter().filter(|group| group.len() == size).collect()
+    };
+    let (quads, trips, pairs, singles) = (of_size(4), of_size(3), of_size(2), of_size(1));
+    let rank = |group: &Vec<Card>| group[0].rank.numeric_ace_high();
+    let two = |group: &Vec<Card>| [group[0], group[1]];
+    let highest_singles = |skip: usize| [singles[skip][0], singles[skip + 1][0]];
+
+    if let Some(&quad) = quads.first() {
+        return match trips.first().or(pairs.first()) {
+            Some(pair) => two(pair),
+            None if rank(quad) >= 7 => two(quad),
+            None => highest_singles(0),
+        };
+    }
+    match (trips.as_slice(), pairs.as_slice()) {
+        ([higher, _], _) => two(higher),
+        ([_], [pair, ..]) => two(pair),
+        ([trip], []) if rank(trip) == 14 => [trip[0], singles[0][0]],
+        ([_], []) => highest_singles(0),
+        ([], [higher, _, _]) => two(higher),
+        ([], [higher, lower]) => {
+            match singles.first() {
+                Some(single) if rank(higher) <= 6 && rank(single) == 14 => {
+                    // Keep both pairs together, and play the Ace low.
+                    [single[0], singles[1][0]]
+                }
+                _ => two(lower),
+            }
+        }
+        ([], [pair]) => straight_or_flush(cards)
+            .filter(|low| low.iter().all(|card| pair.contains(card)))
+            .unwrap_or_else(|| highest_singles(0)),
+        _ => straight_or_flush(cards).unwrap_or_else(|| highest_singles(1)),
+    }
+}
+
+/// The best low hand left by playing a straight or better in the high hand, if one can be made.
+fn straight_or_flush(cards: &[Card]) -> Option<[Card; 2]> {
+    cards
+        .iter()
+        .copied()
+        .combinations(2)
+        .map(|low| [low[0], low[1]])
+        .filter(|low| {
+            let high: Vec<_> = cards
+                .iter()
+                .filter(|card| !low.contains(card))
+                .copied()
+                .collect();
+            let high = Hand::try_from(high.as_slice()).expect("five unique cards are left");
+            strength(&high).category >= HandCategory::Straight
+        })
+        .max_by_key(low_strength)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    /// The low hand the house way sets, as two ranks.
+    fn low(hand: &str) -> Vec<u8> {
+        let setting = house_way(&cards(hand)).unwrap();
+        setting
+            .low
+            .iter()
+            .map(|card| card.rank.numeric_ace_high())
+            .collect()
+    }
+
+    #[test]
+    fn house_way_never_fouls() {
+        use crate::deck::{remaining_cards, seeded_rng};
+        use rand::seq::SliceRandom;
+
+        let deck = remaining_cards(&[]);
+        let mut rng = seeded_rng(181);
+        for _ in 0..20_000 {
+            let cards: Vec<_> = deck.choose_multiple(&mut rng, 7).copied().collect();
+            house_way(&cards).unwrap();
+        }
+    }
+
+    #[test]
+    fn no_pair_keeps_highest_card_high() {
+        assert_eq!(low("1s,kh,9r,7k,5s,3h,2r"), [13, 9]);
+    }
+
+    #[test]
+    fn straight_is_played_leaving_best_low() {
+        // The Eight through Queen straight leaves the King and Ace.
+        assert_eq!(low("1s,kh,qr,jk,ts,9h,8r"), [14, 13]);
+    }
+
+    #[test]
+    fn one_pair_stays_high() {
+        assert_eq!(low("9s,9h,1r,qk,7s,4h,2r"), [14, 12]);
+    }
+
+    #[test]
+    fn flush_is_played_when_the_pair_can_go_low() {
+        let setting = house_way(&cards("2h,5h,8h,jh,kh,9s,9r")).unwrap();
+        assert_eq!(setting.high_strength().category, HandCategory::Flush);
+        assert_eq!(setting.low_strength().category, HandCategory::OnePair);
+    }
+
+    #[test]
+    fn two_pair_is_split_unless_low_with_an_ace() {
+        assert_eq!(low("js,jh,4r,4k,1s,9h,2r"), [4, 4]);
+        assert_eq!(low("6s,6h,4r,4k,1s,9h,2r"), [14, 9]);
+    }
+
+    #[test]
+    fn three_pair_plays_highest_pair_low() {
+        assert_eq!(low("js,jh,4r,4k,9s,9h,2r"), [11, 11]);
+    }
+
+    #[test]
+    fn three_aces_are_split() {
+        assert_eq!(low("1s,1h,1r,9k,7s,4h,2r"), [14, 9]);
+        assert_eq!(low("ks,kh,kr,9k,7s,4h,2r"), [9, 7]);
+    }
+
+    #[test]
+    fn full_house_plays_the_pair_low() {
+        assert_eq!(low("5s,5h,5r,qk,qs,4h,2r"), [12, 12]);
+        assert_eq!(low("5s,5h,5r,qk,qs,qh,2r"), [12, 12]);
+    }
+
+    #[test]
+    fn four_of_a_kind_is_split_when_high() {
+        assert_eq!(low("9s,9h,9r,9k,qs,4h,2r"), [9, 9]);
+        assert_eq!(low("5s,5h,5r,5k,qs,4h,2r"), [12, 4]);
+    }
+
+    #[test]
+    fn low_hand_must_be_weaker() {
+        let high: Hand = "qs,jh,9r,7k,5s".parse().unwrap();
+        let low = [cards("th")[0], cards("2r")[0]];
+        assert!(Setting::new(high.clone(), low).is_ok());
+        let low = [cards("1h")[0], cards("2r")[0]];
+        assert!(matches!(Setting::new(high, low), Err(PaiGowError::Foul(_))));
+    }
+
+    #[test]
+    fn needs_seven_unique_cards() {
+        assert_eq!(house_way(&cards("1s,kh,9r")), Err(PaiGowError::Cards(3)));
+        assert!(matches!(
+            house_way(&cards("1s,kh,9r,7k,5s,3h,1s")),
+            Err(PaiGowError::DuplicateCard(_))
+        ));
+    }
+}
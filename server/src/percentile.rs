@@ -0,0 +1,271 @@
+//! Where a hand ranks among all hands: "your hand beats 62 % of hands".
+//!
+//! The 2,598,960 five-card hands fall into 7462 classes of equal [`Strength`], e.g., all hands
+//! of a pair of Aces with King, Queen and Jack kickers are one class. The classes are numbered by
+//! strength, from 0 for 7-5-4-3-2 of mixed suits to 7461 for a royal flush, which is the value of
+//! a [`Percentile`].
+//!
+//! A Texas hold'em hand is instead ranked among every two hole cards which could be held on the
+//! same board, see [`holdem_percentile`].
+
+use crate::{
+    card::{Card, Rank, Suit},
+    compare::{strength, Strength},
+    deck::hole_cards,
+    evaluate::{BitCount, Evaluator},
+    hand::Hand,
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{cmp::Ordering, sync::OnceLock};
+
+/// The number of classes of five-card hands with equal strength.
+pub const VALUES: u16 = 7462;
+
+/// The number of five-card hands.
+const HANDS: u32 = 2_598_960;
+
+/// Where a five-card hand ranks among all five-card hands.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct Percentile {
+    /// The class of the hand, from 0 for the weakest to 7461 for the strongest.
+    pub value: u16,
+    /// The share of all hands, from 0 to 1, which the hand beats.
+    pub beats: f64,
+    /// The share of all hands, from 0 to 1, which the hand ties with, including itself.
+    pub ties: f64,
+}
+
+/// Where a five-card hand ranks among the hands of its category.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct CategoryRank {
+    /// The class of the hand within its category, from 0 for the weakest.
+    pub ordinal: u16,
+    /// The number of classes in the category, e.g., 156 full houses of distinct ranks.
+    pub classes: u16,
+}
+
+/// Where two hole cards rank among all hole cards on a board.
+#[derive(Clone, PartialEq, Debug, Serialize)]
+pub struct HoldemPercentile {
+    /// The strength of the best hand of the hole cards and the board.
+    pub strength: Strength,
+    /// The number of two hole cards which could be held on the board.
+    pub combos: usize,
+    /// The share of the combos, from 0 to 1, which the hand beats.
+    pub beats: f64,
+    /// The share of the combos, from 0 to 1, which the hand ties with, including itself.
+    pub ties: f64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PercentileError {
+    #[error("number of hole cards ({0}) must be 2")]
+    HoleCards(usize),
+    #[error("number of board cards ({0}) must be 3 to 5")]
+    Board(usize),
+    #[error("{0} is given more than once")]
+    DuplicateCard(Card),
+}
+
+/// A class of five-card hands with equal strength.
+struct Class {
+    strength: Strength,
+    /// The number of hands in the class.
+    hands: u32,
+    /// The number of hands in the weaker classes.
+    weaker: u32,
+}
+
+/// The classes, from the weakest.
+fn classes() -> &'static [Class] {
+    static CLASSES: OnceLock<Vec<Class>> = OnceLock::new();
+    CLASSES.get_or_init(|| {
+        let suited = |ranks: &[Rank], suits: &[Suit]| -> Strength {
+            let cards: Vec<_> = ranks
+                .iter()
+                .zip(suits)
+                .map(|(&rank, &suit)| Card { rank, suit })
+                .collect();
+            strength(&Hand::try_from(cards.as_slice()).expect("the cards are unique"))
+        };
+        let mut classes: Vec<(Strength, u32)> = Vec::new();
+        for ranks in Rank::ALL.into_iter().combinations_with_replacement(5) {
+            let counts: Vec<usize> = ranks.iter().dedup_with_count().map(|(n, _)| n).collect();
+            if counts.contains(&5) {
+                continue;
+            }
+            if counts.len() == 5 {
+                // Five ranks are a flush in one of the four suits, or else not a flush.
+                classes.push((suited(&ranks, &[Suit::Spades; 5]), 4));
+                let mut mixed = [Suit::Spades; 5];
+                mixed[1] = Suit::Hearts;
+                classes.push((suited(&ranks, &mixed), 4u32.pow(5) - 4));
+            } else {
+                // Cards of the same rank take different suits, which also makes the hand no flush.
+                let suits: Vec<_> = counts
+                    .iter()
+                    .flat_map(|&count| Suit::ALL[..count].iter().copied())
+                    .collect();
+                let hands = counts.iter().map(|&count| binomial(4, count)).product();
+                classes.push((suited(&ranks, &suits), hands));
+            }
+        }
+        classes.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        let mut weaker = 0;
+        classes
+            .into_iter()
+            .map(|(strength, hands)| {
+                let class = Class {
+                    strength,
+                    hands,
+                    weaker,
+                };
+                weaker += hands;
+                class
+            })
+            .collect()
+    })
+}
+
+fn binomial(n: u32, k: usize) -> u32 {
+    (0..k as u32).fold(1, |product, i| product * (n - i) / (i + 1))
+}
+
+/// Where the [`Hand`] ranks among all five-card hands.
+pub fn percentile(hand: &Hand) -> Percentile {
+    let classes = classes();
+    let strength = strength(hand);
+    let value = classes
+        .binary_search_by(|class| class.strength.cmp(&strength))
+        .expect("every strength has a class");
+    let class = &classes[value];
+    Percentile {
+        value: value.try_into().expect("there are 7462 classes"),
+        beats: f64::from(class.weaker) / f64::from(HANDS),
+        ties: f64::from(class.hands) / f64::from(HANDS),
+    }
+}
+
+/// Where the [`Hand`] ranks among the hands of its category, like a Queens over Nines full house
+/// among the 156 full houses.
+pub fn category_rank(hand: &Hand) -> CategoryRank {
+    let classes = classes();
+    let value = usize::from(percentile(hand).value);
+    let category = classes[value].strength.category;
+    let first = classes.partition_point(|class| class.strength.category < category);
+    let end = classes.partition_point(|class| class.strength.category <= category);
+    CategoryRank {
+        ordinal: (value - first).try_into().expect("there are 7462 classes"),
+        classes: (end - first).try_into().expect("there are 7462 classes"),
+    }
+}
+
+/// Where the `hole` cards rank on the `board` of the flop, the turn or the river, among every two
+/// hole cards which are not on the board.
+pub fn holdem_percentile(
+    hole: &[Card],
+    board: &[Card],
+) -> Result<HoldemPercentile, PercentileError> {
+    if hole.len() != 2 {
+        return Err(PercentileError::HoleCards(hole.len()));
+    }
+    if !(3..=5).contains(&board.len()) {
+        return Err(PercentileError::Board(board.len()));
+    }
+    let known: Vec<_> = hole.iter().chain(board).copied().collect();
+    for (i, card) in known.iter().enumerate() {
+        if known[..i].contains(card) {
+            return Err(PercentileError::DuplicateCard(*card));
+        }
+    }
+
+    let evaluate = |hole: &[Card]| {
+        let cards: Vec<_> = hole.iter().chain(board).copied().collect();
+        BitCount.evaluate(&cards)
+    };
+    let strength = evaluate(hole);
+    let (mut combos, mut beaten, mut tied) = (0, 0, 0);
+    for other in hole_cards(board) {
+        combos += 1;
+        match strength.cmp(&evaluate(&other)) {
+            Ordering::Greater => beaten += 1,
+            Ordering::Equal => tied += 1,
+            Ordering::Less => {}
+        }
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let share = |count: usize| count as f64 / combos as f64;
+    Ok(HoldemPercentile {
+        strength,
+        combos,
+        beats: share(beaten),
+        ties: share(tied),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn classes_cover_every_hand() {
+        let classes = classes();
+        assert_eq!(classes.len(), usize::from(VALUES));
+        assert_eq!(classes.iter().map(|class| class.hands).sum::<u32>(), HANDS);
+        assert!(classes.windows(2).all(|w| w[0].strength < w[1].strength));
+    }
+
+    #[test]
+    fn weakest_and_strongest_hands_are_at_the_ends() {
+        let worst = percentile(&"7s,5h,4r,3k,2s".parse().unwrap());
+        assert_eq!(worst.value, 0);
+        assert_eq!(worst.beats, 0.0);
+        let royal = percentile(&"ts,js,qs,ks,1s".parse().unwrap());
+        assert_eq!(royal.value, VALUES - 1);
+        assert!((royal.beats + royal.ties - 1.0).abs() < 1e-12);
+        assert!((royal.ties - 4.0 / f64::from(HANDS)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn one_pair_beats_about_half() {
+        // High card hands are a little over half of all hands.
+        let pair = percentile(&"2s,2h,3r,4k,5s".parse().unwrap());
+        assert!((0.50..0.51).contains(&pair.beats), "{}", pair.beats);
+    }
+
+    #[test]
+    fn hands_are_ranked_within_their_category() {
+        let rank = |hand: &str| category_rank(&hand.parse().unwrap());
+        let full_house = rank("qs,qh,qr,9k,9s");
+        assert_eq!(full_house.classes, 156);
+        // Aces, Kings, and Queens over the Kings, Aces, Jacks and Tens are stronger.
+        assert_eq!(full_house.ordinal, 155 - 12 - 12 - 4);
+        assert_eq!(
+            rank("1s,ks,qs,js,ts"),
+            CategoryRank {
+                ordinal: 9,
+                classes: 10
+            }
+        );
+        assert_eq!(rank("7s,5h,4r,3k,2s").ordinal, 0);
+    }
+
+    #[test]
+    fn holdem_hand_is_ranked_on_the_board() {
+        let nuts = holdem_percentile(&cards("1s,ks"), &cards("qs,js,ts")).unwrap();
+        assert_eq!(nuts.combos, 1176);
+        assert!((nuts.beats + nuts.ties - 1.0).abs() < 1e-12);
+        let air = holdem_percentile(&cards("2h,3r"), &cards("qs,js,8s,7h,6k")).unwrap();
+        assert_eq!(air.combos, 1081);
+        assert!(air.beats < 0.01);
+        assert!(matches!(
+            holdem_percentile(&cards("2h,3r"), &cards("qs,js")),
+            Err(PercentileError::Board(2))
+        ));
+    }
+}
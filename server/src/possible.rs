@@ -0,0 +1,224 @@
+//! The categories a five-card hand can still end up as when only some of its cards are known, like
+//! the face-up cards of a stud hand.
+//!
+//! The unknown cards are assumed to be drawn uniformly from the rest of the deck, so the
+//! [`Possibilities`] are exact: every way of completing the hand is enumerated, at most
+//! 2,598,960 of them when no card is known.
+//!
+//! The same goes for the hands of opponents in five-card stud, where [`opponent_possibilities`]
+//! completes each opponent's face-up cards from the cards no one has seen: not your own cards,
+//! face up or down, the other up-cards, nor the dead cards.
+
+use crate::{
+    card::Card,
+    classify::classify,
+    deck::Combinations,
+    hand::{Hand, HandCategory},
+};
+use serde::Serialize;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum PossibleError {
+    #[error("number of known cards ({0}) must be at most 4")]
+    Cards(usize),
+    #[error("{0} is given more than once")]
+    DuplicateCard(Card),
+}
+
+/// How the known cards can be completed into a hand.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Possibilities {
+    /// The number of ways to complete the hand.
+    pub completions: u64,
+    /// The categories which can still be made, from the weakest.
+    pub categories: Vec<Possibility>,
+}
+
+/// A category which can still be made.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Possibility {
+    pub category: HandCategory,
+    /// The number of completions making the category.
+    pub completions: u64,
+    /// The probability of the category, from 0 to 1.
+    pub probability: f64,
+}
+
+/// The categories which the `known` cards, at most four, can be completed into, with their exact
+/// probabilities.
+pub fn possibilities(known: &[Card]) -> Result<Possibilities, PossibleError> {
+    check_distinct(known)?;
+    completions(known, &[])
+}
+
+/// The categories each opponent in five-card stud can end up with, given `yours`, the cards of
+/// each opponent which are `face_up`, at most four each, and the `dead` cards, like folded
+/// up-cards. The possibilities are in the order of the opponents.
+pub fn opponent_possibilities(
+    yours: &[Card],
+    face_up: &[Vec<Card>],
+    dead: &[Card],
+) -> Result<Vec<Possibilities>, PossibleError> {
+    let seen: Vec<Card> = yours
+        .iter()
+        .chain(face_up.iter().flatten())
+        .chain(dead)
+        .copied()
+        .collect();
+    check_distinct(&seen)?;
+    face_up
+        .iter()
+        .map(|up_cards| {
+            let unseen_elsewhere: Vec<Card> = seen
+                .iter()
+                .filter(|card| !up_cards.contains(card))
+                .copied()
+                .collect();
+            completions(up_cards, &unseen_elsewhere)
+        })
+        .collect()
+}
+
+fn check_distinct(cards: &[Card]) -> Result<(), PossibleError> {
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) {
+            return Err(PossibleError::DuplicateCard(*card));
+        }
+    }
+    Ok(())
+}
+
+/// The possibilities of the `known` cards, completed with cards which are not `dead`.
+fn completions(known: &[Card], dead: &[Card]) -> Result<Possibilities, PossibleError> {
+    let counts = match known.len() {
+        0 => count::<5>(known, dead),
+        1 => count::<4>(known, dead),
+        2 => count::<3>(known, dead),
+        3 => count::<2>(known, dead),
+        4 => count::<1>(known, dead),
+        n => return Err(PossibleError::Cards(n)),
+    };
+    let completions = counts.iter().sum();
+    let categories = HandCategory::ALL
+        .into_iter()
+        .zip(counts)
+        .filter(|&(_, count)| count > 0)
+        .map(|(category, count)| {
+            #[allow(clippy::cast_precision_loss)]
+            let probability = count as f64 / completions as f64;
+            Possibility {
+                category,
+                completions: count,
+                probability,
+            }
+        })
+        .collect();
+    Ok(Possibilities {
+        completions,
+        categories,
+    })
+}
+
+/// The number of completions of the `known` cards with `K` more cards which are not `dead` making
+/// each category, in the order of [`HandCategory::ALL`].
+fn count<const K: usize>(known: &[Card], dead: &[Card]) -> [u64; 9] {
+    let mut counts = [0; 9];
+    let mut cards = known.to_vec();
+    let excluded: Vec<Card> = known.iter().chain(dead).copied().collect();
+    for extra in Combinations::<K>::new(&excluded) {
+        cards.truncate(known.len());
+        cards.extend(extra);
+        let hand = Hand::try_from(cards.as_slice()).expect("the cards are five and distinct");
+        let category = classify(&hand);
+        let index = HandCategory::ALL
+            .iter()
+            .position(|&c| c == category)
+            .expect("every category is in ALL");
+        counts[index] += 1;
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &str) -> Vec<Card> {
+        cards.split(',').map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn four_of_a_kind_up_can_only_get_a_kicker() {
+        let possible = possibilities(&cards("7k,7r,7h,7s")).unwrap();
+        assert_eq!(possible.completions, 48);
+        assert_eq!(possible.categories.len(), 1);
+        assert_eq!(possible.categories[0].category, HandCategory::FourOfAKind);
+        assert_eq!(possible.categories[0].probability, 1.0);
+    }
+
+    #[test]
+    fn probabilities_are_exact() {
+        let possible = possibilities(&cards("2s,7h")).unwrap();
+        // Choosing 3 of the other 50 cards.
+        assert_eq!(possible.completions, 19_600);
+        let straight_flush = possible
+            .categories
+            .iter()
+            .find(|p| p.category == HandCategory::StraightFlush);
+        assert!(straight_flush.is_none(), "the suits differ");
+        let sum: f64 = possible.categories.iter().map(|p| p.probability).sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+        // Pairing the Two or the Seven, 2 * 3 * C(11, 2) * 4 * 4 = 5280 ways, or pairing one of
+        // the 11 other ranks, 11 * C(4, 2) * 10 * 4 = 2640 ways.
+        let one_pair = possible
+            .categories
+            .iter()
+            .find(|p| p.category == HandCategory::OnePair)
+            .unwrap();
+        assert_eq!(one_pair.completions, 7920);
+    }
+
+    #[test]
+    fn opponents_are_completed_from_unseen_cards() {
+        // You hold two Sevens, so the opponent showing the other two cannot make four of a kind.
+        let opponents = opponent_possibilities(
+            &cards("7s,7h,2k,9r"),
+            &[cards("7k,7r,ks,kh"), cards("qs,js,ts,9s")],
+            &cards("kk"),
+        )
+        .unwrap();
+        assert_eq!(opponents.len(), 2);
+        let (two_pair, full_house) = (&opponents[0].categories[0], &opponents[0].categories[1]);
+        assert_eq!(opponents[0].completions, 52 - 13);
+        assert_eq!(
+            (two_pair.category, two_pair.completions),
+            (HandCategory::TwoPair, 38)
+        );
+        assert_eq!(
+            (full_house.category, full_house.completions),
+            (HandCategory::FullHouse, 1)
+        );
+        // Only the Eight of spades makes a straight flush, as the King of spades is face up with
+        // the first opponent.
+        let straight_flush = opponents[1].categories.last().unwrap();
+        assert_eq!(straight_flush.category, HandCategory::StraightFlush);
+        assert_eq!(straight_flush.completions, 1);
+
+        assert_eq!(
+            opponent_possibilities(&cards("7s"), &[cards("7s")], &[]),
+            Err(PossibleError::DuplicateCard("7s".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn invalid_cards_are_rejected() {
+        assert_eq!(
+            possibilities(&cards("2s,3s,4s,5s,6s")),
+            Err(PossibleError::Cards(5))
+        );
+        assert_eq!(
+            possibilities(&cards("2s,2s")),
+            Err(PossibleError::DuplicateCard("2s".parse().unwrap()))
+        );
+    }
+}
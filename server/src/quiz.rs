@@ -0,0 +1,374 @@
+//! A quiz where players guess the [`HandCategory`] of dealt hands, or which of several hands wins,
+//! keeping score per session.
+//!
+//! Each question has a token, which is used to answer it once. A session groups the questions of
+//! one player, of either kind, and is given back when asking the next question to keep counting
+//! the score.
+//!
+//! Hands, tokens and sessions come from the quiz's [`Randomness`], so a seeded one makes the quiz
+//! deterministic.
+//!
+//! Open questions and sessions are kept in memory. The least recently used are forgotten when there
+//! are more than the capacity.
+
+use crate::{
+    classify::classify,
+    compare::Strength,
+    environment::{Randomness, ThreadRandomness},
+    hand::{Hand, HandCategory},
+    rules::{Rules, RulesError},
+    shuffle,
+};
+use lru::LruCache;
+use serde::Serialize;
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+/// A dealt hand waiting for an answer.
+#[derive(Clone, Debug)]
+pub struct Question {
+    pub token: String,
+    pub session: String,
+    pub hand: Hand,
+}
+
+/// The result of answering a [`Question`].
+#[derive(Clone, Debug)]
+pub struct Answer {
+    pub correct: bool,
+    /// The category of the hand.
+    pub category: HandCategory,
+    /// The score of the session, including this answer.
+    pub score: Score,
+}
+
+/// Hands dealt for guessing which wins.
+#[derive(Clone, Debug)]
+pub struct ShowdownQuestion {
+    pub token: String,
+    pub session: String,
+    pub hands: Vec<Hand>,
+    pub rules: Rules,
+}
+
+/// The result of answering a [`ShowdownQuestion`].
+#[derive(Clone, Debug)]
+pub struct ShowdownAnswer {
+    pub correct: bool,
+    /// Indices of the winning hands. More than one hand wins when they tie.
+    pub winners: Vec<usize>,
+    /// The strength of each hand, under the rules.
+    pub strengths: Vec<Strength>,
+    /// The rules the hands were dealt and compared with.
+    pub rules: Rules,
+    /// The score of the session, including this answer.
+    pub score: Score,
+}
+
+#[derive(Serialize, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Score {
+    pub correct: u64,
+    pub answered: u64,
+}
+
+#[derive(Debug)]
+pub struct Quiz {
+    state: Mutex<State>,
+    randomness: Arc<dyn Randomness>,
+}
+
+#[derive(Debug)]
+struct State {
+    /// Open questions by token.
+    questions: LruCache<String, Deal>,
+    /// Scores by session.
+    scores: LruCache<String, Score>,
+}
+
+/// What was dealt for an open question.
+#[derive(Debug)]
+enum Deal {
+    Category {
+        session: String,
+        hand: Hand,
+    },
+    Showdown {
+        session: String,
+        hands: Vec<Hand>,
+        rules: Rules,
+    },
+}
+
+impl State {
+    /// The session, if it is known, or else a new session.
+    fn session(&mut self, session: Option<&str>, randomness: &dyn Randomness) -> String {
+        match session {
+            Some(session) if self.scores.get(session).is_some() => session.to_owned(),
+            _ => {
+                let session = randomness.id();
+                self.scores.put(session.clone(), Score::default());
+                session
+            }
+        }
+    }
+
+    /// Count an answer in the session's score, and return the new score.
+    fn score(&mut self, session: String, correct: bool) -> Score {
+        let score = self.scores.get_or_insert_mut(session, Score::default);
+        score.answered += 1;
+        score.correct += u64::from(correct);
+        *score
+    }
+}
+
+impl Quiz {
+    /// Create a quiz remembering up to `capacity` open questions, and as many sessions.
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self::with_randomness(capacity, Arc::new(ThreadRandomness))
+    }
+
+    /// Create a quiz like [`Quiz::new`], which takes its randomness from `randomness`.
+    pub fn with_randomness(capacity: NonZeroUsize, randomness: Arc<dyn Randomness>) -> Self {
+        Self {
+            state: Mutex::new(State {
+                questions: LruCache::new(capacity),
+                scores: LruCache::new(capacity),
+            }),
+            randomness,
+        }
+    }
+
+    /// Deal a new question in the session, or in a new session if the session is not known.
+    pub fn ask(&self, session: Option<&str>) -> Question {
+        let mut state = self.state.lock().expect("the quiz never panics");
+        let question = Question {
+            token: self.randomness.id(),
+            session: state.session(session, &*self.randomness),
+            hand: shuffle::draw(self.randomness.next_u64()),
+        };
+        state.questions.put(
+            question.token.clone(),
+            Deal::Category {
+                session: question.session.clone(),
+                hand: question.hand.clone(),
+            },
+        );
+        question
+    }
+
+    /// Answer the question with `token` by guessing its category. Returns `None` if there is no
+    /// such open question.
+    pub fn answer(&self, token: &str, guess: HandCategory) -> Option<Answer> {
+        let mut state = self.state.lock().expect("the quiz never panics");
+        let Some(Deal::Category { .. }) = state.questions.peek(token) else {
+            return None;
+        };
+        let Some(Deal::Category { session, hand }) = state.questions.pop(token) else {
+            unreachable!("we just peeked at the question");
+        };
+        let category = classify(&hand);
+        let correct = guess == category;
+        Some(Answer {
+            correct,
+            category,
+            score: state.score(session, correct),
+        })
+    }
+
+    /// Deal `players` hands from one deck of the `rules` in the session, or in a new session if
+    /// the session is not known. Fails if the rules are invalid, or do not deal to the players.
+    pub fn ask_showdown(
+        &self,
+        session: Option<&str>,
+        rules: Rules,
+        players: usize,
+    ) -> Result<ShowdownQuestion, RulesError> {
+        rules.validate()?;
+        let hands = rules.deal(players, self.randomness.next_u64())?;
+        let mut state = self.state.lock().expect("the quiz never panics");
+        let question = ShowdownQuestion {
+            token: self.randomness.id(),
+            session: state.session(session, &*self.randomness),
+            hands,
+            rules,
+        };
+        state.questions.put(
+            question.token.clone(),
+            Deal::Showdown {
+                session: question.session.clone(),
+                hands: question.hands.clone(),
+                rules,
+            },
+        );
+        Ok(question)
+    }
+
+    /// Answer the showdown with `token` by guessing the indices of the winning hands, which are
+    /// several when they tie. Returns `None` if there is no such open showdown.
+    pub fn answer_showdown(&self, token: &str, guess: &[usize]) -> Option<ShowdownAnswer> {
+        let mut state = self.state.lock().expect("the quiz never panics");
+        let Some(Deal::Showdown { .. }) = state.questions.peek(token) else {
+            return None;
+        };
+        let Some(Deal::Showdown {
+            session,
+            hands,
+            rules,
+        }) = state.questions.pop(token)
+        else {
+            unreachable!("we just peeked at the question");
+        };
+        let strengths: Vec<_> = hands
+            .iter()
+            .map(|hand| {
+                rules
+                    .strength(hand)
+                    .expect("hands are dealt from the rules' deck")
+            })
+            .collect();
+        let best = strengths
+            .iter()
+            .max_by(|a, b| rules.compare(a, b))
+            .expect("hands were dealt");
+        let winners: Vec<_> = (0..strengths.len())
+            .filter(|&i| strengths[i] == *best)
+            .collect();
+        let mut guess = guess.to_vec();
+        guess.sort_unstable();
+        guess.dedup();
+        let correct = guess == winners;
+        Some(ShowdownAnswer {
+            correct,
+            winners,
+            strengths,
+            rules,
+            score: state.score(session, correct),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiz() -> Quiz {
+        Quiz::new(NonZeroUsize::new(10).unwrap())
+    }
+
+    #[test]
+    fn answers_are_scored_per_session() {
+        let quiz = quiz();
+        let first = quiz.ask(None);
+        let category = classify(&first.hand);
+        let answer = quiz.answer(&first.token, category).unwrap();
+        assert!(answer.correct);
+        assert_eq!(answer.category, category);
+
+        let second = quiz.ask(Some(&first.session));
+        assert_eq!(second.session, first.session);
+        let wrong = if classify(&second.hand) == HandCategory::Flush {
+            HandCategory::Straight
+        } else {
+            HandCategory::Flush
+        };
+        let answer = quiz.answer(&second.token, wrong).unwrap();
+        assert!(!answer.correct);
+        assert_eq!(
+            answer.score,
+            Score {
+                correct: 1,
+                answered: 2
+            }
+        );
+    }
+
+    #[test]
+    fn question_is_answered_once() {
+        let quiz = quiz();
+        let question = quiz.ask(None);
+        assert!(quiz
+            .answer(&question.token, HandCategory::HighCard)
+            .is_some());
+        assert!(quiz
+            .answer(&question.token, HandCategory::HighCard)
+            .is_none());
+    }
+
+    #[test]
+    fn showdown_is_graded_by_strength() {
+        use crate::compare::strength;
+
+        let quiz = quiz();
+        let question = quiz.ask_showdown(None, Rules::default(), 3).unwrap();
+        assert_eq!(question.hands.len(), 3);
+        let strengths: Vec<_> = question.hands.iter().map(strength).collect();
+        let best = strengths.iter().max().unwrap();
+        let winner = strengths.iter().position(|s| s == best).unwrap();
+
+        let answer = quiz.answer_showdown(&question.token, &[winner]).unwrap();
+        assert!(answer.winners.contains(&winner));
+        assert_eq!(answer.correct, answer.winners == [winner]);
+        assert_eq!(answer.strengths, strengths);
+    }
+
+    #[test]
+    fn kinds_of_question_are_not_mixed_up() {
+        let quiz = quiz();
+        let category = quiz.ask(None);
+        let showdown = quiz
+            .ask_showdown(Some(&category.session), Rules::default(), 2)
+            .unwrap();
+        assert!(quiz.answer_showdown(&category.token, &[0]).is_none());
+        assert!(quiz
+            .answer(&showdown.token, HandCategory::HighCard)
+            .is_none());
+        assert!(quiz
+            .answer(&category.token, HandCategory::HighCard)
+            .is_some());
+        let answer = quiz.answer_showdown(&showdown.token, &[0]).unwrap();
+        assert_eq!(answer.score.answered, 2);
+    }
+
+    #[test]
+    fn showdown_is_dealt_and_graded_by_the_rules() {
+        let quiz = quiz();
+        let rules = Rules {
+            short_deck: true,
+            ..Rules::default()
+        };
+        assert!(quiz.ask_showdown(None, rules, 8).is_err());
+        let question = quiz.ask_showdown(None, rules, 7).unwrap();
+        assert!(question
+            .hands
+            .iter()
+            .flat_map(Hand::cards)
+            .all(|card| card.rank.numeric_ace_high() >= 6));
+        let answer = quiz.answer_showdown(&question.token, &[0]).unwrap();
+        assert_eq!(answer.rules, rules);
+    }
+
+    #[test]
+    fn seeded_quiz_is_deterministic() {
+        use crate::environment::SeededRandomness;
+
+        let quiz = || {
+            Quiz::with_randomness(
+                NonZeroUsize::new(10).unwrap(),
+                Arc::new(SeededRandomness::new(168)),
+            )
+        };
+        let (a, b) = (quiz().ask(None), quiz().ask(None));
+        assert_eq!(a.token, b.token);
+        assert_eq!(a.session, b.session);
+        assert_eq!(a.hand, b.hand);
+    }
+
+    #[test]
+    fn unknown_session_starts_a_new_one() {
+        let quiz = quiz();
+        let question = quiz.ask(Some("made-up"));
+        assert_ne!(question.session, "made-up");
+    }
+}
@@ -0,0 +1,115 @@
+//! Render [`Card`]s and [`Hand`]s for text clients, like chat bots and terminals.
+
+use crate::{
+    card::{Card, Rank, Suit},
+    hand::Hand,
+};
+
+impl Suit {
+    /// The suit symbol, e.g., `♠` for Spades.
+    pub fn symbol(self) -> char {
+        match self {
+            Suit::Clubs => '♣',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+            Suit::Spades => '♠',
+        }
+    }
+}
+
+impl Rank {
+    /// The rank as printed on a card, e.g., `A` for Ace and `10` for Ten.
+    pub fn label(self) -> &'static str {
+        match self {
+            Rank::Ace => "A",
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+        }
+    }
+}
+
+impl Card {
+    /// The card from the Unicode Playing Cards block, e.g., 🂡 for the Ace of Spades.
+    pub fn to_emoji(self) -> char {
+        let suit = match self.suit {
+            Suit::Spades => 0x1F0A0,
+            Suit::Hearts => 0x1F0B0,
+            Suit::Diamonds => 0x1F0C0,
+            Suit::Clubs => 0x1F0D0,
+        };
+        // The block has a Knight between the Jack and the Queen, which is not used in poker.
+        let rank = match self.rank {
+            Rank::Queen | Rank::King => u32::from(self.rank.numeric()) + 1,
+            _ => u32::from(self.rank.numeric()),
+        };
+        char::from_u32(suit + rank).expect("the block has every card")
+    }
+}
+
+impl Hand {
+    /// Draw the cards side by side, five lines high, like this Ace of Spades:
+    ///
+    /// ```text
+    /// .-----.
+    /// |A    |
+    /// |  ♠  |
+    /// |    A|
+    /// '-----'
+    /// ```
+    pub fn to_ascii_art(&self) -> String {
+        let row =
+            |line: &dyn Fn(&Card) -> String| self.cards().map(line).collect::<Vec<_>>().join(" ");
+        [
+            row(&|_| ".-----.".to_owned()),
+            row(&|card| format!("|{:<2}   |", card.rank.label())),
+            row(&|card| format!("|  {}  |", card.suit.symbol())),
+            row(&|card| format!("|   {:>2}|", card.rank.label())),
+            row(&|_| "'-----'".to_owned()),
+        ]
+        .join("\n")
+    }
+
+    /// The cards as Unicode playing cards, e.g., 🂪🂫🂭🂮🂡 for a Royal Flush in Spades.
+    pub fn to_emoji(&self) -> String {
+        self.cards().map(|card| card.to_emoji()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emoji_skips_knight() {
+        let hand: Hand = "ts,js,qs,ks,1s".parse().unwrap();
+        assert_eq!(hand.to_emoji(), "🂡🂪🂫🂭🂮");
+        let card: Card = "qh".parse().unwrap();
+        assert_eq!(card.to_emoji(), '🂽');
+    }
+
+    #[test]
+    fn ascii_art_draws_cards_side_by_side() {
+        let hand: Hand = "1s,2h,5k,9r,tr".parse().unwrap();
+        assert_eq!(
+            hand.to_ascii_art(),
+            [
+                ".-----. .-----. .-----. .-----. .-----.",
+                "|A    | |2    | |5    | |9    | |10   |",
+                "|  ♠  | |  ♥  | |  ♣  | |  ♦  | |  ♦  |",
+                "|    A| |    2| |    5| |    9| |   10|",
+                "'-----' '-----' '-----' '-----' '-----'",
+            ]
+            .join("\n")
+        );
+    }
+}
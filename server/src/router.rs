@@ -1,84 +1,2567 @@
 //! This module provides a [`Router`] which represent the HTTP endpoints.
 
 use crate::{
-    card::InvalidConversion,
-    classify::classify,
-    deck::draw_hand,
-    hand::{Hand, HandCategory, HandConstructionError},
+    benchmark::{benchmark, Benchmark, EVALUATORS},
+    cache::HandCache,
+    card::Card,
+    classify::{classify, CLASSIFIER_VERSION},
+    compare::{self, Strength},
+    config::RouterConfig,
+    conformance::{self, Fixture, Report},
+    deck::seeded_rng,
+    entropy::{FallbackRandomness, FileEntropy},
+    environment::{Clock, Environment, Randomness},
+    evaluate::{BitCount, Naive},
+    examples::{examples, Example},
+    fields::select_fields,
+    hand::{Hand, HandCategory},
+    hour::{HandOfTheHour, HourlyHand},
+    i18n::Language,
+    jobs::{JobStatus, Jobs, JobsError},
+    logging::log_request,
+    metrics::{record_request, Metrics},
+    odds::odds_table,
+    paigow::{house_way, Setting},
+    percentile::{self, category_rank, holdem_percentile, CategoryRank},
+    possible::{possibilities, Possibilities},
+    quiz::{Quiz, Score},
+    rules::{Rules, Straights},
+    shuffle::{self, SHUFFLE_VERSION},
+    variant::{Analysis, Variant, Variants},
 };
 use axum::{
-    extract::Path,
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    routing::get,
-    Json, Router,
+    async_trait,
+    body::Bytes,
+    extract::{DefaultBodyLimit, FromRequestParts, Path, Query, State},
+    http::{header, request::Parts, HeaderMap, Request, StatusCode},
+    middleware::{self, Next},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse, Response,
+    },
+    routing::{get, post},
+    Extension, Json, Router,
 };
-use serde::Serialize;
+use futures_util::{stream, Stream};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tracing::{debug, instrument};
+use std::{
+    convert::Infallible,
+    num::NonZeroUsize,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+use tokio::sync::watch;
+use tracing::{debug, info, instrument};
+
+/// The most hands `/replay/:seed` will draw in one request.
+const MAX_REPLAY_COUNT: usize = 1000;
+
+/// The most cards a request may give, since no endpoint takes more than a deck.
+const MAX_CARDS: usize = 52;
+
+/// The largest request body, which is far more than any endpoint needs.
+const MAX_BODY_BYTES: usize = 16 * 1024;
+
+/// How long `/draw/next` waits for a hand, unless the request says otherwise.
+const DEFAULT_DRAW_NEXT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The longest `/draw/next` will wait for a hand.
+const MAX_DRAW_NEXT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The most deals `/deal/bulk` will deal in one request.
+const MAX_BULK_DEALS: usize = 1000;
+
+/// The most open quiz questions, and quiz sessions, which are remembered.
+const QUIZ_CAPACITY: usize = 10_000;
+
+/// The most hands `/evaluators/benchmark` will evaluate with each evaluator.
+const MAX_BENCHMARK_HANDS: usize = 100_000;
+
+/// How many seconds a client is asked to wait before retrying a request refused as the server is
+/// busy.
+const BUSY_RETRY_AFTER_SECS: u64 = 5;
+
+/// The most iterations a `/simulate` job will run.
+const MAX_SIMULATION_ITERATIONS: u64 = 100_000_000;
+
+/// State shared by the handlers.
+#[derive(Clone)]
+struct AppState {
+    metrics: Arc<Metrics>,
+    jobs: Arc<Jobs>,
+    /// The category of analyzed hands, and its JSON serialization.
+    analyze_cache: Arc<HandCache<(HandCategory, Bytes)>>,
+    /// Created on the first quiz or trainer request, as it allocates room for many questions.
+    quiz: Arc<OnceLock<Quiz>>,
+    variants: Arc<Variants>,
+    /// The longest a simulation job runs.
+    simulation_time_limit: Duration,
+    /// Seeds of draws and simulations.
+    randomness: Arc<dyn Randomness>,
+    /// The time of the hand of the hour.
+    clock: Arc<dyn Clock>,
+    hand_of_the_hour: Arc<HourlyHand>,
+    /// The hand drawn last by `/draw`, with its seed, for `/draw/next`.
+    drawn: Arc<watch::Sender<Option<(Hand, u64)>>>,
+    #[cfg(feature = "demo")]
+    demo_rate: Option<crate::demo::DemoRate>,
+    #[cfg(feature = "signing")]
+    signing_key: Option<crate::signing::SigningKey>,
+}
 
-/// Creates a router with two endpoints.
+impl AppState {
+    /// Draw the hand of the seed from the deck source, which is a fair deck shuffled like
+    /// [`crate::shuffle`] specifies, unless the demo source is configured.
+    fn draw_hand(&self, seed: u64) -> Hand {
+        #[cfg(feature = "demo")]
+        if let Some(rate) = self.demo_rate {
+            return crate::demo::draw_demo_hand_with(&mut seeded_rng(seed), rate);
+        }
+        shuffle::draw(seed)
+    }
+
+    /// The rate of exciting hands, which labels the draws of the demo deck source.
+    fn demo_rate(&self) -> Option<f64> {
+        #[cfg(feature = "demo")]
+        return self.demo_rate.map(crate::demo::DemoRate::get);
+        #[cfg(not(feature = "demo"))]
+        None
+    }
+
+    /// The variant by `name`, or else a `404 Not Found` response.
+    fn variant(&self, lang: Language, name: &str) -> Result<&dyn Variant, (StatusCode, String)> {
+        self.variants
+            .get(name)
+            .ok_or_else(|| (StatusCode::NOT_FOUND, lang.no_variant(name)))
+    }
+
+    fn quiz(&self) -> &Quiz {
+        self.quiz.get_or_init(|| {
+            Quiz::with_randomness(
+                NonZeroUsize::new(QUIZ_CAPACITY).expect("the capacity is not 0"),
+                self.randomness.clone(),
+            )
+        })
+    }
+}
+
+/// Creates a router with the default [`RouterConfig`]. See [`create_with`].
+pub fn create<B>() -> Router<(), B>
+where
+    B: axum::body::HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<axum::BoxError>,
+{
+    create_with(&RouterConfig::default())
+}
+
+/// Creates a router with twenty-seven endpoints, one more when the [`RouterConfig`] enables the benchmark
+/// endpoint, and one more with the `signing` feature.
 ///
 /// The endpoints:
+/// * `/`
+///    * `GET` returns a single-page demo of drawing, analyzing, the quiz and the trainer, built from
+///      `static/index.html`.
 /// * `/draw`
-///    * `GET` generates a hand of five cards, returns a JSON representation of it and a classification of the hand.
+///    * `GET` generates a hand of five cards, returns a JSON representation of it, a classification of the hand, and
+///      the seed it was drawn with, and the name and description of the category. The strength of the hand is named
+///      by its ranks, like "Queens over Nines full house", and ranked among the classes of its category, see
+///      [`crate::percentile::category_rank`]. With `Accept: text/plain`, returns the hand drawn as text instead, see
+///      [`crate::render`].
+///* `/draw/next`
+///    * `GET` waits for the next hand drawn by `/draw`, and returns it like `/draw`, or `204 No Content` if none is
+///      drawn within the `timeout` query parameter, like `30s` or `500ms` (default 30 seconds, at most 60). This is a
+///      long-poll for clients which cannot use server-sent events.
 ///* `/analyze/:cards`
 ///    * `GET` analyzes the provided cards. The `:cards` format is a comma-separated list of rank and suit for five cards.
 ///      Example: `/analyze/tr,jr,qr,kr,1r` would return the JSON string "StraightFlush".
+///* `/analyze`
+///    * `GET` analyzes the cards given as repeated `card` query parameters, like `/analyze/:cards`, for clients which
+///      cannot build the comma-separated path. Example: `/analyze?card=tr&card=jr&card=qr&card=kr&card=1r`.
+///
+/// `/draw`, `/draw/next` and `/analyze` take the `fields` query parameter, which selects the fields of the JSON response to return,
+/// like `/draw?fields=category,strength_name,category_rank.ordinal`, see [`crate::fields`]. With the `cbor` feature,
+/// they return CBOR instead of JSON when the `Accept` header asks for `application/cbor` first, see [`crate::cbor`].
+///* `/variants`
+///    * `GET` returns the names of the variants in the [`RouterConfig`], see [`crate::variant`].
+///* `/variants/:variant/analyze/:cards`
+///    * `GET` ranks the cards of one player in the variant, and returns the cards which play, their category and
+///      tie-breaking ranks. Example: `/variants/omaha/analyze/1s,ks,7h,2h,9s,3s,4s`.
+///* `/variants/:variant/deal`
+///    * `GET` deals the cards of one player in the variant, in the order `/variants/:variant/analyze/:cards` takes
+///      them, with the seed they were dealt with.
+///* `/odds/:hole/:board`
+///    * `GET` returns the odds table of two Texas hold'em hole cards with the three cards of the flop or the four
+///      cards of the turn: the odds of making each better category by the turn and by the river, both exactly and
+///      estimated from the outs. Example: `/odds/1s,ks/7s,2s,9h`.
+///* `/percentile/:cards`
+///    * `GET` returns where five cards rank among all five-card hands: their value among the 7462 classes of equal
+///      strength, and the shares of hands they beat and tie, see [`crate::percentile`]. With the `board` query
+///      parameter of three to five cards, the cards are two hold'em hole cards instead, ranked among all hole cards
+///      on the board. Example: `/percentile/1s,ks?board=7s,2s,9h`.
+///* `/possible/:cards`
+///    * `GET` returns the categories which one to four known cards, like the face-up cards of a stud hand, can still
+///      make as five cards, with their exact probabilities if the other cards are drawn uniformly from the rest of the
+///      deck, see [`crate::possible`]. Example: `/possible/7k,7r,1s`.
+///* `/paigow/set/:cards`
+///    * `GET` sets seven cards into a Pai Gow high hand of five cards and low hand of two by the house way, see
+///      [`crate::paigow`], and returns both hands with their strength. Example: `/paigow/set/1s,kh,9r,9k,5s,3h,2r`.
+///* `/replay/:seed`
+///    * `GET` draws the same hands as were drawn with the seed, along with the classifier version. The optional `count`
+///      query parameter gives the number of hands in the sequence (default 1, at most 1000), where hand `k` is the
+///      first hand of the seed plus `k`. Hands are dealt by the specified shuffle of [`crate::shuffle`], so seeds
+///      logged before its version 1 replay different hands.
+///* `/hand-of-the-hour`
+///    * `GET` returns the hand of the current hour with its category, the Unix times at which the hour starts and
+///      ends, and the seconds left of it. The hand is drawn with the start of the hour as its seed, so every instance
+///      serves the same hand, see [`crate::hour`].
+///* `/deal/bulk`
+///    * `POST` deals `deals` independent deals of `hands` hands each, given in a JSON body like
+///      `{"deals": 30, "hands": 4, "seed": 42}`, where the seed is optional. Each deal is from its own deck, shuffled
+///      with a seed drawn from the request's seed, so the same request deals the same hands. Returns the seed and,
+///      for each deal, its seed and hands as comma-separated cards. The decks are shuffled by the specified shuffle
+///      of [`crate::shuffle`], and the seed of each deal is logged.
+///* `/shuffle/verify`
+///    * `GET` returns the deck shuffled with the `seed` query parameter as comma-separated cards, from the first card
+///      dealt, with the shuffle version, so any seeded deal can be reconstructed from its seed. Example:
+///      `/shuffle/verify?seed=42`.
+///* `/simulate`
+///    * `POST` starts a background job drawing `iterations` hands, given in a JSON body like
+///      `{"iterations": 1000000, "seed": 42}`, where the seed is optional. With a
+///      `target_half_width`, like `0.001`, the job stops early once it has converged, see
+///      [`crate::jobs`]. With a `time_limit_ms`, or the configured limit if that is shorter, the job
+///      stops at the limit with the hands drawn so far, and is marked `truncated`. Returns
+///      `202 Accepted` with the job ID, and the path of the job in the `Location` header, or
+///      `503 Service Unavailable` with a `Retry-After` header while the most jobs are running.
+///      Finished jobs are kept for an hour.
+///* `/jobs/:id`
+///    * `GET` returns the status and progress of a job, including the half-width of its confidence
+///      intervals so far, and how often each category occurred so far.
+///* `/jobs/:id/events`
+///    * `GET` streams the state of a job as server-sent events, like `/jobs/:id` returns it, each time it changes: a
+///      `progress` event after each chunk of hands, and a `done` event, after which the stream ends.
+///* `/quiz`
+///    * `GET` deals a hand for guessing its category, and returns it with a token for answering. Give the `session`
+///      query parameter from an earlier question to keep counting the score of that session.
+///* `/quiz/:token/answer`
+///    * `POST` answers the question with a JSON body like `{"category": "FullHouse"}`. Returns whether the guess was
+///      correct, the category with an explanation, and the score of the session.
+///* `/trainer`
+///    * `GET` deals hands from one deck for guessing which wins, and returns them with a token for answering. The
+///      `players` query parameter gives the number of hands (default 2, at most 10), and `session` works like for
+///      `/quiz`. The `straights`, `short_deck` and `min_players` query parameters give the
+///      [`Rules`] of the deal, which are returned with the hands.
+///* `/trainer/:token/answer`
+///    * `POST` answers with the indices of the winning hands in a JSON body like `{"winners": [1]}`, listing all of
+///      them if they tie. Returns whether the guess was correct, the winners, the category of each hand, an
+///      explanation of the tie-break, and the score of the session.
+///* `/conformance`
+///    * `GET` returns the published conformance fixtures, see [`crate::conformance`], and the
+///      results of running them against this crate's evaluators.
+///* `/examples`
+///    * `GET` returns example requests with their expected responses, generated from the conformance fixtures, for
+///      every endpoint whose response is decided by the cards of the request, see [`crate::examples`].
+///* `/evaluators/benchmark`
+///    * `GET`, only when the [`RouterConfig`] enables the benchmark endpoint, times each evaluator of
+///      [`crate::evaluate`] on the same random hands, and returns their throughput and how often they agree with the
+///      naive evaluator, see [`crate::benchmark`], or `404 Not Found`. The optional `hands` (default 10000, at most
+///      100000), `cards` (default 7) and `seed` query parameters give the workload.
+///* `/.well-known/signing-key`
+///    * `GET`, only with the `signing` feature, returns the public Ed25519 key which `/draw` and `/analyze` responses
+///      are signed with when the [`RouterConfig`] has a signing key, see [`crate::signing`], or `404 Not Found`.
+///* `/warmup`
+///    * `GET` does nothing and returns `204 No Content`, for keeping lambda instances warm.
+///* `/metrics`
+///    * `GET` returns request metrics in the Prometheus text format, see [`crate::metrics`].
+///
+/// Requests may give at most 52 cards, and bodies of at most 16 KiB. Malformed requests, like invalid cards, JSON or
+/// percent-encoding, are answered with a client error.
+///
+/// `/odds`, `/possible` and `/evaluators/benchmark` count as jobs while they are served, so they are refused like
+/// `/simulate` while the most jobs are running, see [`crate::jobs`].
+///
+/// Names, descriptions and error messages are given in the language of the `lang` query parameter, like `lang=no`,
+/// or else of the `Accept-Language` header, if it is one of the [`Language`]s. The default is English.
+///
+/// Randomness comes from the operating system, or from the `entropy_source` of the [`RouterConfig`] while it is
+/// healthy, see [`crate::entropy`].
+///
+/// Each request is logged, see [`crate::logging`].
 #[allow(clippy::doc_markdown)]
-pub fn create<B: axum::body::HttpBody + Send + 'static>() -> Router<(), B> {
-    Router::new()
+pub fn create_with<B>(config: &RouterConfig) -> Router<(), B>
+where
+    B: axum::body::HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<axum::BoxError>,
+{
+    let mut environment = Environment::default();
+    let entropy = config.entropy_source.as_ref().map(|path| {
+        Arc::new(FallbackRandomness::new(
+            Box::new(FileEntropy::new(path)),
+            environment.randomness.clone(),
+        ))
+    });
+    if let Some(entropy) = &entropy {
+        environment.randomness = entropy.clone();
+    }
+    create_with_entropy(config, environment, entropy)
+}
+
+/// Creates a router like [`create_with`], which takes the time and randomness of draws, quiz
+/// questions and request metrics from the `environment`.
+pub fn create_with_environment<B>(config: &RouterConfig, environment: Environment) -> Router<(), B>
+where
+    B: axum::body::HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<axum::BoxError>,
+{
+    create_with_entropy(config, environment, None)
+}
+
+/// Creates a router like [`create_with_environment`], whose metrics report on the `entropy` source
+/// of the randomness, if there is one.
+fn create_with_entropy<B>(
+    config: &RouterConfig,
+    environment: Environment,
+    entropy: Option<Arc<FallbackRandomness>>,
+) -> Router<(), B>
+where
+    B: axum::body::HttpBody + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<axum::BoxError>,
+{
+    let state = AppState {
+        metrics: Arc::new(
+            Metrics::with_clock(config.latency_threshold, environment.clock.clone())
+                .with_entropy(entropy),
+        ),
+        clock: environment.clock,
+        hand_of_the_hour: Arc::default(),
+        jobs: Arc::new(Jobs::with_max_running(config.max_running_jobs)),
+        analyze_cache: Arc::new(HandCache::new(config.analyze_cache_capacity)),
+        quiz: Arc::default(),
+        variants: Arc::new(config.variants.clone()),
+        simulation_time_limit: config.simulation_time_limit,
+        randomness: environment.randomness,
+        drawn: Arc::new(watch::channel(None).0),
+        #[cfg(feature = "demo")]
+        demo_rate: config.demo_rate,
+        #[cfg(feature = "signing")]
+        signing_key: config.signing_key.clone(),
+    };
+    let results = Router::new()
         .route("/draw", get(draw_and_analyze))
+        .route("/draw/next", get(draw_next))
+        .route("/analyze", get(analyze_query))
         .route("/analyze/:cards", get(analyze))
+        .route_layer(middleware::from_fn(select_fields));
+    #[cfg(feature = "cbor")]
+    let results = results.route_layer(middleware::from_fn(crate::cbor::encode_cbor));
+    // Signing wraps the selection and encoding, so what is signed is what is sent.
+    #[cfg(feature = "signing")]
+    let results = match &config.signing_key {
+        Some(key) => results.route_layer(middleware::from_fn_with_state(
+            key.clone(),
+            crate::signing::sign_response,
+        )),
+        None => results,
+    };
+    // These take a core while they are served, so they count against the running jobs.
+    let expensive = Router::new()
+        .route("/odds/:hole/:board", get(odds))
+        .route("/possible/:cards", get(possible));
+    let expensive = if config.benchmark_endpoint {
+        expensive.route("/evaluators/benchmark", get(benchmark_evaluators))
+    } else {
+        expensive
+    };
+    let expensive = expensive.route_layer(middleware::from_fn_with_state(
+        state.jobs.clone(),
+        shed_load,
+    ));
+    let router = Router::new()
+        .route("/", get(index))
+        .merge(results)
+        .route("/variants", get(variants))
+        .route("/variants/:variant/analyze/:cards", get(analyze_variant))
+        .route("/variants/:variant/deal", get(deal))
+        .merge(expensive)
+        .route("/percentile/:cards", get(percentile))
+        .route("/paigow/set/:cards", get(paigow_set))
+        .route("/replay/:seed", get(replay))
+        .route("/hand-of-the-hour", get(hand_of_the_hour))
+        .route("/quiz", get(ask_quiz))
+        .route("/quiz/:token/answer", post(answer_quiz))
+        .route("/trainer", get(ask_trainer))
+        .route("/trainer/:token/answer", post(answer_trainer))
+        .route("/deal/bulk", post(deal_bulk))
+        .route("/shuffle/verify", get(verify_shuffle))
+        .route("/simulate", post(simulate))
+        .route("/jobs/:id", get(job))
+        .route("/jobs/:id/events", get(job_events))
+        .route("/conformance", get(conformance))
+        .route("/examples", get(list_examples))
+        .route("/warmup", get(warmup))
+        .route("/metrics", get(metrics));
+    #[cfg(feature = "signing")]
+    let router = router.route("/.well-known/signing-key", get(signing_key));
+    router
+        .layer(DefaultBodyLimit::max(MAX_BODY_BYTES))
+        .layer(middleware::from_fn_with_state(
+            state.metrics.clone(),
+            record_request,
+        ))
+        .layer(middleware::from_fn(log_request))
+        .with_state(state)
+}
+
+/// Returns the demo page, which is built into the binary, so no frontend is deployed separately.
+async fn index() -> Html<&'static str> {
+    Html(include_str!("../static/index.html"))
+}
+
+/// Does nothing. Scheduled pings of this keep lambda instances warm, without touching the other
+/// endpoints' state.
+async fn warmup() -> StatusCode {
+    StatusCode::NO_CONTENT
+}
+
+/// Returns the request metrics in the Prometheus text format.
+async fn metrics(State(state): State<AppState>) -> String {
+    state.metrics.render()
+}
+
+#[cfg(feature = "signing")]
+#[derive(Serialize)]
+struct SigningKeyResponse {
+    algorithm: &'static str,
+    public_key: String,
+}
+
+/// Returns the public key which analyze and draw responses are signed with.
+#[cfg(feature = "signing")]
+async fn signing_key(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+) -> axum::response::Result<Json<SigningKeyResponse>> {
+    let key = state
+        .signing_key
+        .as_ref()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, lang.not_signed()))?;
+    Ok(Json(SigningKeyResponse {
+        algorithm: "Ed25519",
+        public_key: key.public_key(),
+    }))
+}
+
+#[derive(Serialize)]
+struct ConformanceResponse {
+    classifier_version: u32,
+    fixtures: Vec<Fixture>,
+    naive: Report,
+    bit_count: Report,
+}
+
+/// Runs the conformance fixtures against the evaluators, and returns them with the results.
+#[instrument]
+async fn conformance() -> Json<ConformanceResponse> {
+    debug!("serving");
+    Json(ConformanceResponse {
+        classifier_version: CLASSIFIER_VERSION,
+        fixtures: conformance::fixtures(),
+        naive: conformance::run(&Naive),
+        bit_count: conformance::run(&BitCount),
+    })
+}
+
+#[derive(Serialize)]
+struct ExamplesResponse {
+    classifier_version: u32,
+    examples: Vec<Example>,
+}
+
+/// Returns example requests with their expected responses, generated from the conformance
+/// fixtures.
+#[instrument]
+async fn list_examples() -> Json<ExamplesResponse> {
+    debug!("serving");
+    Json(ExamplesResponse {
+        classifier_version: CLASSIFIER_VERSION,
+        examples: examples(),
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct BenchmarkQuery {
+    hands: Option<usize>,
+    cards: Option<usize>,
+    seed: Option<u64>,
+}
+
+/// Benchmarks the evaluators against each other on random hands.
+#[instrument(skip(state))]
+async fn benchmark_evaluators(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Query(query): Query<BenchmarkQuery>,
+) -> axum::response::Result<Json<Benchmark>> {
+    debug!("serving");
+    let hands = query.hands.unwrap_or(10_000);
+    if hands > MAX_BENCHMARK_HANDS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            lang.at_most("hands", &hands, &MAX_BENCHMARK_HANDS),
+        )
+            .into());
+    }
+    let cards = query.cards.unwrap_or(7);
+    let seed = query.seed.unwrap_or_else(|| state.randomness.next_u64());
+    // Timing on a blocking thread keeps the benchmark from stalling other requests, and them from
+    // skewing it.
+    let benchmark = tokio::task::spawn_blocking(move || benchmark(&EVALUATORS, hands, cards, seed))
+        .await
+        .expect("benchmarks never panic")
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.benchmark_error(&e)))?;
+    Ok(Json(benchmark))
+}
+
+/// The language of the request. See [`create_with`].
+#[derive(Debug)]
+struct Lang(Language);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Lang {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        let from_query = query_values(parts, "lang")
+            .next()
+            .and_then(|tag| Language::from_tag(&tag));
+        let from_header = || {
+            parts
+                .headers
+                .get(header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(Language::from_accept_language)
+        };
+        Ok(Lang(from_query.or_else(from_header).unwrap_or_default()))
+    }
+}
+
+/// The values of the query parameter `name`, in order, decoded like HTML forms encode them.
+fn query_values<'a>(parts: &'a Parts, name: &'a str) -> impl Iterator<Item = String> + 'a {
+    form_urlencoded::parse(parts.uri.query().unwrap_or_default().as_bytes())
+        .filter(move |(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// The cards of the request given as repeated `card` query parameters, like
+/// `?card=1s&card=kr`, in order. See [`create_with`].
+#[derive(Debug)]
+struct CardParams(Vec<String>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for CardParams {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        Ok(CardParams(query_values(parts, "card").collect()))
+    }
 }
 
 #[derive(Serialize)]
 struct DrawAndAnalyzeResponse {
     hand: Hand,
     category: HandCategory,
+    category_name: &'static str,
+    category_description: &'static str,
+    strength: Strength,
+    /// The name of the strength by its deciding ranks, e.g., "Queens over Nines full house".
+    strength_name: String,
+    category_rank: CategoryRank,
+    seed: u64,
+    /// Set when the hand comes from the demo deck source, which is not fair.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    demo_rate: Option<f64>,
 }
 
-/// Draws a hand of five cards from a deck of 52, returns the hand and its classification.
-#[instrument]
-async fn draw_and_analyze() -> Json<Value> {
+/// Draws a hand of five cards from a deck of 52, returns the hand, its classification and the seed
+/// which reproduces it with `/replay/:seed`.
+#[instrument(skip(state, headers))]
+async fn draw_and_analyze(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    headers: HeaderMap,
+) -> Response {
     debug!("serving");
-    let hand = draw_hand();
+    let seed = state.randomness.next_u64();
+    let hand = state.draw_hand(seed);
+    state.drawn.send_replace(Some((hand.clone(), seed)));
     let category = classify(&hand);
-    Json(
-        serde_json::to_value(DrawAndAnalyzeResponse { hand, category })
+    if prefers(&headers, "text/plain") {
+        let mut text = format!(
+            "{}\n{}\n{}\n",
+            hand.to_ascii_art(),
+            hand.to_emoji(),
+            lang.category_name(category)
+        );
+        if let Some(rate) = state.demo_rate() {
+            text += &lang.demo_rate(rate);
+            text.push('\n');
+        }
+        return (Extension(category), text).into_response();
+    }
+    draw_json(&state, lang, hand, seed)
+}
+
+/// The JSON response of a drawn hand, see [`DrawAndAnalyzeResponse`].
+fn draw_json(state: &AppState, lang: Language, hand: Hand, seed: u64) -> Response {
+    let category = classify(&hand);
+    let strength = compare::strength(&hand);
+    (
+        Extension(category),
+        Json(
+            serde_json::to_value(DrawAndAnalyzeResponse {
+                category,
+                category_name: lang.category_name(category),
+                category_description: lang.category_description(category),
+                strength_name: lang.strength_name(&strength),
+                strength,
+                category_rank: category_rank(&hand),
+                hand,
+                seed,
+                demo_rate: state.demo_rate(),
+            })
             .expect("no known fail modes"),
+        ),
+    )
+        .into_response()
+}
+
+#[derive(Serialize)]
+struct HandOfTheHourResponse {
+    #[serde(flatten)]
+    hand: HandOfTheHour,
+    category_name: &'static str,
+    /// The seconds until the next hand is drawn, for counting down.
+    seconds_left: u64,
+}
+
+/// Returns the hand of the current hour, which is the same on every instance, and when the next
+/// is drawn.
+#[instrument(skip(state))]
+async fn hand_of_the_hour(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+) -> Json<HandOfTheHourResponse> {
+    debug!("serving");
+    let now = state.clock.since_epoch();
+    let hand = state.hand_of_the_hour.get(now);
+    Json(HandOfTheHourResponse {
+        category_name: lang.category_name(hand.category),
+        seconds_left: hand.remaining(now).as_secs(),
+        hand,
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct DrawNextQuery {
+    timeout: Option<String>,
+}
+
+/// Parses a timeout like `30s`, `500ms` or `30`, which is in seconds.
+fn parse_timeout(timeout: &str) -> Option<Duration> {
+    if let Some(millis) = timeout.strip_suffix("ms") {
+        return millis.parse().ok().map(Duration::from_millis);
+    }
+    let seconds = timeout.strip_suffix('s').unwrap_or(timeout);
+    seconds.parse().ok().map(Duration::from_secs)
+}
+
+/// Waits for the next hand drawn by `/draw`, and returns it like `/draw` did, or `204 No Content`
+/// if none is drawn before the timeout.
+///
+/// Example request path: /draw/next?timeout=30s
+#[instrument(skip(state))]
+async fn draw_next(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Query(query): Query<DrawNextQuery>,
+) -> axum::response::Result<Response> {
+    debug!("serving");
+    let timeout = match query.timeout {
+        None => DEFAULT_DRAW_NEXT_TIMEOUT,
+        Some(timeout) => parse_timeout(&timeout)
+            .filter(|&timeout| timeout <= MAX_DRAW_NEXT_TIMEOUT)
+            .ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    lang.timeout(&timeout, MAX_DRAW_NEXT_TIMEOUT.as_secs()),
+                )
+            })?,
+    };
+    // A new receiver has seen the hand drawn last, so it only wakes for the next one.
+    let mut drawn = state.drawn.subscribe();
+    match tokio::time::timeout(timeout, drawn.changed()).await {
+        Ok(Ok(())) => {
+            let (hand, seed) = drawn
+                .borrow_and_update()
+                .clone()
+                .expect("only draws are published");
+            Ok(draw_json(&state, lang, hand, seed))
+        }
+        _ => Ok(StatusCode::NO_CONTENT.into_response()),
+    }
+}
+
+/// Whether the `Accept` header asks for the `media_type`, like `text/plain`, before
+/// `application/json`.
+pub(crate) fn prefers(headers: &HeaderMap, media_type: &str) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|media_type| media_type.split(';').next().unwrap_or_default().trim())
+        .find(|&accepted| accepted == media_type || accepted == "application/json")
+        == Some(media_type)
+}
+
+#[derive(Deserialize, Debug)]
+struct ReplayQuery {
+    count: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct ClassifiedHand {
+    hand: Hand,
+    category: HandCategory,
+}
+
+#[derive(Serialize)]
+struct ReplayResponse {
+    seed: u64,
+    classifier_version: u32,
+    hands: Vec<ClassifiedHand>,
+    /// Set when the hands come from the demo deck source, which is not fair.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    demo_rate: Option<f64>,
+}
+
+/// Reproduces the sequence of hands drawn with the seed, and classifies them.
+///
+/// Example request path: /replay/42?count=3
+#[instrument(skip(state))]
+async fn replay(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(seed): Path<u64>,
+    Query(query): Query<ReplayQuery>,
+) -> axum::response::Result<Json<Value>> {
+    debug!("serving");
+    let count = query.count.unwrap_or(1);
+    if count > MAX_REPLAY_COUNT {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            lang.at_most("count", &count, &MAX_REPLAY_COUNT),
+        )
+            .into());
+    }
+    // Like `shuffle::draw_nth`, hand `k` is the hand of the seed plus `k`.
+    let hands = (0..count)
+        .map(|k| {
+            let hand = state.draw_hand(seed.wrapping_add(k as u64));
+            let category = classify(&hand);
+            ClassifiedHand { hand, category }
+        })
+        .collect();
+    Ok(Json(
+        serde_json::to_value(ReplayResponse {
+            seed,
+            classifier_version: CLASSIFIER_VERSION,
+            hands,
+            demo_rate: state.demo_rate(),
+        })
+        .expect("no known fail modes"),
+    ))
+}
+
+#[derive(Deserialize, Debug)]
+struct QuizQuery {
+    session: Option<String>,
+}
+
+#[derive(Serialize)]
+struct QuizQuestionResponse {
+    token: String,
+    session: String,
+    hand: Hand,
+}
+
+/// Deals a hand for the quiz, without its classification.
+#[instrument(skip(state))]
+async fn ask_quiz(
+    State(state): State<AppState>,
+    Query(query): Query<QuizQuery>,
+) -> Json<QuizQuestionResponse> {
+    debug!("serving");
+    let question = state.quiz().ask(query.session.as_deref());
+    Json(QuizQuestionResponse {
+        token: question.token,
+        session: question.session,
+        hand: question.hand,
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct QuizAnswerRequest {
+    category: HandCategory,
+}
+
+#[derive(Serialize)]
+struct QuizAnswerResponse {
+    correct: bool,
+    category: HandCategory,
+    category_name: &'static str,
+    explanation: &'static str,
+    score: Score,
+}
+
+/// Checks the guessed category of a quiz hand, and reveals the right one.
+#[instrument(skip(state))]
+async fn answer_quiz(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(token): Path<String>,
+    Json(request): Json<QuizAnswerRequest>,
+) -> axum::response::Result<(Extension<HandCategory>, Json<QuizAnswerResponse>)> {
+    debug!("serving");
+    let answer = state
+        .quiz()
+        .answer(&token, request.category)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, lang.no_question(&token)))?;
+    Ok((
+        Extension(answer.category),
+        Json(QuizAnswerResponse {
+            correct: answer.correct,
+            category: answer.category,
+            category_name: lang.category_name(answer.category),
+            explanation: lang.category_description(answer.category),
+            score: answer.score,
+        }),
+    ))
+}
+
+#[derive(Deserialize, Debug)]
+struct TrainerQuery {
+    players: Option<usize>,
+    session: Option<String>,
+    straights: Option<Straights>,
+    short_deck: Option<bool>,
+    min_players: Option<usize>,
+}
+
+impl TrainerQuery {
+    /// The rules of the deal, where those not given are the default.
+    fn rules(&self) -> Rules {
+        let default = Rules::default();
+        Rules {
+            straights: self.straights.unwrap_or(default.straights),
+            short_deck: self.short_deck.unwrap_or(default.short_deck),
+            min_players: self.min_players.unwrap_or(default.min_players),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TrainerQuestionResponse {
+    token: String,
+    session: String,
+    hands: Vec<Hand>,
+    rules: Rules,
+}
+
+/// Deals hands for guessing which of them wins.
+#[instrument(skip(state))]
+async fn ask_trainer(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Query(query): Query<TrainerQuery>,
+) -> axum::response::Result<Json<TrainerQuestionResponse>> {
+    debug!("serving");
+    let rules = query.rules();
+    let players = query.players.unwrap_or(rules.min_players);
+    let question = state
+        .quiz()
+        .ask_showdown(query.session.as_deref(), rules, players)
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.rules_error(&e)))?;
+    Ok(Json(TrainerQuestionResponse {
+        token: question.token,
+        session: question.session,
+        hands: question.hands,
+        rules: question.rules,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+struct TrainerAnswerRequest {
+    winners: Vec<usize>,
+}
+
+#[derive(Serialize)]
+struct TrainerAnswerResponse {
+    correct: bool,
+    winners: Vec<usize>,
+    /// The strength of each hand, in the order they were dealt.
+    strengths: Vec<Strength>,
+    explanation: String,
+    score: Score,
+}
+
+/// Checks the guessed winners of a trainer deal, and explains who won.
+#[instrument(skip(state))]
+async fn answer_trainer(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(token): Path<String>,
+    Json(request): Json<TrainerAnswerRequest>,
+) -> axum::response::Result<Json<TrainerAnswerResponse>> {
+    debug!("serving");
+    let answer = state
+        .quiz()
+        .answer_showdown(&token, &request.winners)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, lang.no_deal(&token)))?;
+    let winner = &answer.strengths[answer.winners[0]];
+    // Compare with the best of the losing hands, or with the other winners if all tie.
+    let runner_up = (0..answer.strengths.len())
+        .filter(|i| !answer.winners.contains(i))
+        .map(|i| &answer.strengths[i])
+        .max_by(|a, b| answer.rules.compare(a, b))
+        .unwrap_or(winner);
+    Ok(Json(TrainerAnswerResponse {
+        correct: answer.correct,
+        explanation: lang.explain_showdown(winner, runner_up),
+        winners: answer.winners,
+        strengths: answer.strengths,
+        score: answer.score,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+struct BulkDealRequest {
+    deals: usize,
+    hands: usize,
+    seed: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct BulkDealResponse {
+    seed: u64,
+    deals: Vec<BulkDeal>,
+}
+
+#[derive(Serialize)]
+struct BulkDeal {
+    seed: u64,
+    /// Each hand as comma-separated cards, like `/analyze/:cards` takes them.
+    hands: Vec<String>,
+}
+
+/// Deals many independent deals of several hands, e.g., exercises for a class.
+#[instrument(skip(state))]
+async fn deal_bulk(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Json(request): Json<BulkDealRequest>,
+) -> axum::response::Result<Json<BulkDealResponse>> {
+    debug!("serving");
+    if request.deals > MAX_BULK_DEALS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            lang.at_most("deals", &request.deals, &MAX_BULK_DEALS),
+        )
+            .into());
+    }
+    if !(1..=10).contains(&request.hands) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            lang.from_to("hands", &request.hands, &1, &10),
+        )
+            .into());
+    }
+    let seed = request.seed.unwrap_or_else(|| state.randomness.next_u64());
+    let mut seeds = seeded_rng(seed);
+    let deals = (0..request.deals)
+        .map(|_| {
+            let seed = seeds.next_u64();
+            let hands = shuffle::deal(seed, request.hands);
+            // The seed is enough to reconstruct the deal, see `/shuffle/verify`.
+            info!(seed, shuffle_version = SHUFFLE_VERSION, "dealt");
+            BulkDeal {
+                seed,
+                hands: hands.iter().map(ToString::to_string).collect(),
+            }
+        })
+        .collect();
+    Ok(Json(BulkDealResponse { seed, deals }))
+}
+
+#[derive(Deserialize, Debug)]
+struct VerifyShuffleQuery {
+    seed: u64,
+}
+
+#[derive(Serialize)]
+struct VerifyShuffleResponse {
+    shuffle_version: u32,
+    seed: u64,
+    /// The shuffled deck as comma-separated cards, from the first card dealt.
+    cards: String,
+}
+
+/// Reconstructs the order of the deck shuffled with a seed, see [`crate::shuffle`].
+#[instrument]
+async fn verify_shuffle(Query(query): Query<VerifyShuffleQuery>) -> Json<VerifyShuffleResponse> {
+    debug!("serving");
+    let cards: Vec<String> = shuffle::shuffle(query.seed)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    Json(VerifyShuffleResponse {
+        shuffle_version: SHUFFLE_VERSION,
+        seed: query.seed,
+        cards: cards.join(","),
+    })
+}
+
+#[derive(Deserialize, Debug)]
+struct SimulateRequest {
+    iterations: u64,
+    seed: Option<u64>,
+    target_half_width: Option<f64>,
+    time_limit_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct SimulateResponse {
+    id: u64,
+}
+
+/// Starts a background job simulating draws.
+#[instrument(skip(state))]
+async fn simulate(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Json(request): Json<SimulateRequest>,
+) -> axum::response::Result<impl IntoResponse> {
+    debug!("serving");
+    if request.iterations > MAX_SIMULATION_ITERATIONS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            lang.at_most(
+                "iterations",
+                &request.iterations,
+                &MAX_SIMULATION_ITERATIONS,
+            ),
+        )
+            .into());
+    }
+    if let Some(target) = request.target_half_width {
+        if !(target > 0.0 && target < 1.0) {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                lang.share("target_half_width", target),
+            )
+                .into());
+        }
+    }
+    let seed = request.seed.unwrap_or_else(|| state.randomness.next_u64());
+    // The request may ask for less time than the configured limit, but not more.
+    let time_limit = request
+        .time_limit_ms
+        .map_or(state.simulation_time_limit, |limit| {
+            Duration::from_millis(limit).min(state.simulation_time_limit)
+        });
+    let id = state
+        .jobs
+        .start_simulation(
+            request.iterations,
+            seed,
+            request.target_half_width,
+            Some(time_limit),
+        )
+        .map_err(|e| busy(lang, e))?;
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/jobs/{id}"))],
+        Json(SimulateResponse { id }),
+    ))
+}
+
+/// Returns the state of a job started by `/simulate`.
+#[instrument(skip(state))]
+async fn job(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(id): Path<u64>,
+) -> axum::response::Result<Json<Value>> {
+    debug!("serving");
+    let job = state
+        .jobs
+        .get(id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, lang.no_job(id)))?;
+    Ok(Json(
+        serde_json::to_value(job).expect("no known fail modes"),
+    ))
+}
+
+/// Streams the state of a job started by `/simulate` until it is done.
+#[instrument(skip(state))]
+async fn job_events(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(id): Path<u64>,
+) -> axum::response::Result<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    debug!("serving");
+    let receiver = state
+        .jobs
+        .subscribe(id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, lang.no_job(id)))?;
+    // The current state is sent right away, and then each change, until the job is done.
+    let events = stream::unfold(Some((receiver, false)), |next| async move {
+        let (mut receiver, wait) = next?;
+        if wait {
+            receiver.changed().await.ok()?;
+        }
+        let job = receiver.borrow_and_update().clone();
+        let done = job.status == JobStatus::Done;
+        let event = Event::default()
+            .event(if done { "done" } else { "progress" })
+            .json_data(&job)
+            .expect("no known fail modes");
+        Some((Ok(event), (!done).then_some((receiver, true))))
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+/// Returns the odds table of hole cards on a board.
+///
+/// Example request path: /odds/1s,ks/7s,2s,9h
+#[instrument]
+async fn odds(
+    Lang(lang): Lang,
+    Path((hole, board)): Path<(String, String)>,
+) -> axum::response::Result<Json<Value>> {
+    debug!("serving");
+    let parse = |cards: &str| parse_cards(lang, cards.split(','));
+    let table = odds_table(&parse(&hole)?, &parse(&board)?)
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.odds_error(&e)))?;
+    Ok(Json(
+        serde_json::to_value(table).expect("no known fail modes"),
+    ))
+}
+
+/// Returns the names of the variants.
+#[instrument(skip(state))]
+async fn variants(State(state): State<AppState>) -> Json<Vec<String>> {
+    debug!("serving");
+    Json(
+        state
+            .variants
+            .names()
+            .into_iter()
+            .map(String::from)
+            .collect(),
     )
 }
 
+/// Ranks the cards of one player in a variant.
+///
+/// Example request path: /variants/holdem/analyze/1s,ks,7s,2s,9s,3r,4k
+#[instrument(skip(state))]
+async fn analyze_variant(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path((variant, cards)): Path<(String, String)>,
+) -> axum::response::Result<Json<Analysis>> {
+    debug!("serving");
+    let variant = state.variant(lang, &variant)?;
+    let cards = parse_cards(lang, cards.split(','))?;
+    let analysis = variant
+        .analyze(&cards)
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.variant_error(&e)))?;
+    Ok(Json(analysis))
+}
+
+#[derive(Serialize)]
+struct DealResponse {
+    cards: Vec<Card>,
+    seed: u64,
+}
+
+/// Deals the cards of one player in a variant.
+#[instrument(skip(state))]
+async fn deal(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(variant): Path<String>,
+) -> axum::response::Result<Json<DealResponse>> {
+    debug!("serving");
+    let variant = state.variant(lang, &variant)?;
+    let seed = state.randomness.next_u64();
+    Ok(Json(DealResponse {
+        cards: variant.deal(seed),
+        seed,
+    }))
+}
+
+#[derive(Deserialize, Debug)]
+struct PercentileQuery {
+    board: Option<String>,
+}
+
+/// Returns where a hand ranks among all hands, or among all hole cards on a board.
+///
+/// Example request path: /percentile/1s,ks?board=7s,2s,9h
+#[instrument]
+async fn percentile(
+    Lang(lang): Lang,
+    Path(cards): Path<String>,
+    Query(query): Query<PercentileQuery>,
+) -> axum::response::Result<Json<Value>> {
+    debug!("serving");
+    let parse = |cards: &str| parse_cards(lang, cards.split(','));
+    let cards = parse(&cards)?;
+    let percentile = match query.board {
+        Some(board) => serde_json::to_value(
+            holdem_percentile(&cards, &parse(&board)?)
+                .map_err(|e| (StatusCode::BAD_REQUEST, lang.percentile_error(&e)))?,
+        ),
+        None => {
+            let hand = Hand::try_from(cards.as_slice())
+                .map_err(|e| (StatusCode::BAD_REQUEST, lang.hand_error(&e)))?;
+            serde_json::to_value(percentile::percentile(&hand))
+        }
+    };
+    Ok(Json(percentile.expect("no known fail modes")))
+}
+
+/// Returns the categories which up to four known cards can still be completed into, with their
+/// exact probabilities.
+///
+/// Example request path: /possible/7k,7r,1s
+#[instrument]
+async fn possible(
+    Lang(lang): Lang,
+    Path(cards): Path<String>,
+) -> axum::response::Result<Json<Possibilities>> {
+    debug!("serving");
+    let cards = parse_cards(lang, cards.split(','))?;
+    // Enumerating up to 249,900 completions is kept off the request threads.
+    let possibilities = tokio::task::spawn_blocking(move || possibilities(&cards))
+        .await
+        .expect("enumerating never panics")
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.possible_error(&e)))?;
+    Ok(Json(possibilities))
+}
+
+#[derive(Serialize)]
+struct PaiGowResponse {
+    #[serde(flatten)]
+    setting: Setting,
+    high_strength: Strength,
+    low_strength: Strength,
+}
+
+/// Sets seven cards into a Pai Gow high and low hand by the house way.
+///
+/// Example request path: /paigow/set/1s,kh,9r,9k,5s,3h,2r
+#[instrument]
+async fn paigow_set(
+    Lang(lang): Lang,
+    Path(cards): Path<String>,
+) -> axum::response::Result<Json<PaiGowResponse>> {
+    debug!("serving");
+    let cards = parse_cards(lang, cards.split(','))?;
+    let setting =
+        house_way(&cards).map_err(|e| (StatusCode::BAD_REQUEST, lang.paigow_error(&e)))?;
+    Ok(Json(PaiGowResponse {
+        high_strength: setting.high_strength(),
+        low_strength: setting.low_strength(),
+        setting,
+    }))
+}
+
 /// Analyzes the provided hand of five cards and returns its classification. Cards are
 /// comma-separated.
 ///
 /// Example request path: /tr,jr,qr,kr,1r
-#[instrument]
-async fn analyze(Path(cards): Path<String>) -> axum::response::Result<Json<Value>> {
+#[instrument(skip(state))]
+async fn analyze(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    Path(cards): Path<String>,
+) -> axum::response::Result<Response> {
+    debug!("serving");
+    Ok(analyze_cards(&state, lang, cards.split(','))?)
+}
+
+/// Analyzes the hand of five cards given as repeated `card` query parameters, like
+/// [`analyze`].
+///
+/// Example request path: /analyze?card=tr&card=jr&card=qr&card=kr&card=1r
+#[instrument(skip(state))]
+async fn analyze_query(
+    State(state): State<AppState>,
+    Lang(lang): Lang,
+    CardParams(cards): CardParams,
+) -> axum::response::Result<Response> {
     debug!("serving");
+    Ok(analyze_cards(
+        &state,
+        lang,
+        cards.iter().map(String::as_str),
+    )?)
+}
 
-    // Convert each card to a Card. Return error if conversion fails.
-    let cards = cards
-        .split(',')
+/// Parses the cards of a request, failing with `400 Bad Request` for an invalid card, or for more
+/// than [`MAX_CARDS`] without parsing them all.
+fn parse_cards<'a>(
+    lang: Language,
+    cards: impl IntoIterator<Item = &'a str>,
+) -> Result<Vec<Card>, (StatusCode, String)> {
+    let cards: Vec<&str> = cards.into_iter().take(MAX_CARDS + 1).collect();
+    if cards.len() > MAX_CARDS {
+        return Err((StatusCode::BAD_REQUEST, lang.too_many_cards(MAX_CARDS)));
+    }
+    cards
+        .into_iter()
         .map(str::parse)
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect::<Result<_, _>>()
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.card_error(&e)))
+}
+
+fn analyze_cards<'a>(
+    state: &AppState,
+    lang: Language,
+    cards: impl Iterator<Item = &'a str>,
+) -> Result<Response, (StatusCode, String)> {
+    // Convert each card to a Card. Return error if conversion fails.
+    let cards = parse_cards(lang, cards)?;
     // Convert Vec of Card to Hand, return error if conversion fails.
-    let hand = Hand::try_from(cards.as_slice())?;
-    let category = classify(&hand);
-    Ok(Json(
-        serde_json::to_value(category).expect("no known fail modes"),
-    ))
+    let hand = Hand::try_from(cards.as_slice())
+        .map_err(|e| (StatusCode::BAD_REQUEST, lang.hand_error(&e)))?;
+    let ((category, body), hit) = state.analyze_cache.get_or_insert_with(&hand, || {
+        let category = classify(&hand);
+        let body = serde_json::to_vec(&category).expect("no known fail modes");
+        (category, body.into())
+    });
+    state.metrics.record_cache(hit);
+    Ok((
+        Extension(category),
+        [(header::CONTENT_TYPE, "application/json")],
+        body,
+    )
+        .into_response())
 }
 
-impl IntoResponse for InvalidConversion {
-    fn into_response(self) -> Response {
-        (StatusCode::BAD_REQUEST, format!("card is invalid: {self}")).into_response()
+/// `503 Service Unavailable` while the most jobs are running, with when to try again.
+fn busy(lang: Language, error: JobsError) -> Response {
+    let JobsError::Busy(max_running) = error;
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, BUSY_RETRY_AFTER_SECS.to_string())],
+        lang.busy(max_running),
+    )
+        .into_response()
+}
+
+/// Middleware which refuses a request while the most jobs are running, and counts it as a job
+/// while it is served. See [`Jobs::reserve`].
+async fn shed_load<B>(
+    State(jobs): State<Arc<Jobs>>,
+    Lang(lang): Lang,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    match jobs.reserve() {
+        Ok(_permit) => next.run(request).await,
+        Err(e) => busy(lang, e),
     }
 }
 
-impl IntoResponse for HandConstructionError {
-    fn into_response(self) -> Response {
-        (StatusCode::BAD_REQUEST, format!("hand is invalid: {self}")).into_response()
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        card::InvalidConversion, odds::OddsError, paigow::PaiGowError, variant::VariantError,
+    };
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    async fn get_json(uri: &str) -> (StatusCode, Value) {
+        get_json_with(&RouterConfig::default(), uri).await
+    }
+
+    async fn get_json_with(config: &RouterConfig, uri: &str) -> (StatusCode, Value) {
+        let response = create_with(config)
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn replay_reproduces_draw() {
+        let (status, drawn) = get_json("/draw").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, replayed) = get_json(&format!("/replay/{}", drawn["seed"])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed["hands"][0]["hand"], drawn["hand"]);
+        assert_eq!(replayed["hands"][0]["category"], drawn["category"]);
+        assert_eq!(replayed["classifier_version"], CLASSIFIER_VERSION);
+    }
+
+    #[tokio::test]
+    async fn draw_names_and_ranks_the_strength() {
+        let (_, drawn) = get_json("/draw?lang=no").await;
+        let hand: Hand = serde_json::from_value(drawn["hand"].clone()).unwrap();
+        let strength = compare::strength(&hand);
+        assert_eq!(
+            drawn["strength_name"],
+            Language::Norwegian.strength_name(&strength)
+        );
+        assert_eq!(
+            drawn["category_rank"],
+            serde_json::to_value(category_rank(&hand)).unwrap()
+        );
+        assert!(
+            drawn["category_rank"]["ordinal"].as_u64() < drawn["category_rank"]["classes"].as_u64()
+        );
+    }
+
+    #[tokio::test]
+    async fn draw_returns_selected_fields() {
+        let (status, drawn) = get_json("/draw?fields=category,category_rank.ordinal").await;
+        assert_eq!(status, StatusCode::OK);
+        let drawn = drawn.as_object().unwrap();
+        assert_eq!(
+            drawn.keys().collect::<Vec<_>>(),
+            ["category", "category_rank"]
+        );
+        assert_eq!(
+            drawn["category_rank"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["ordinal"]
+        );
+
+        let (status, _) = get_json("/draw?fields=category,suit").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, analyzed) = get_json("/analyze/tr,jr,qr,kr,1r?fields=category").await;
+        assert_eq!(
+            (status, analyzed),
+            (StatusCode::OK, Value::from("StraightFlush"))
+        );
+    }
+
+    #[tokio::test]
+    async fn index_serves_the_demo_page() {
+        let response = create()
+            .oneshot(Request::get("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let page = std::str::from_utf8(&body).unwrap();
+        for path in ["/draw", "/analyze/", "/quiz", "/trainer"] {
+            assert!(page.contains(path), "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn examples_get_their_responses() {
+        let (status, examples) = get_json("/examples").await;
+        assert_eq!(status, StatusCode::OK);
+        for example in examples["examples"].as_array().unwrap() {
+            let path = example["path"].as_str().unwrap();
+            let (status, response) = get_json(path).await;
+            assert_eq!(status.as_u16(), example["status"], "{path}");
+            assert_eq!(response, example["response"], "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn draw_next_waits_for_the_next_draw() {
+        let router = create();
+        let next = tokio::spawn(
+            router.clone().oneshot(
+                Request::get("/draw/next?timeout=5s")
+                    .body(Body::empty())
+                    .unwrap(),
+            ),
+        );
+        let mut seeds = Vec::new();
+        while !next.is_finished() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            let response = router
+                .clone()
+                .oneshot(Request::get("/draw").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            seeds.push(serde_json::from_slice::<Value>(&body).unwrap()["seed"].clone());
+        }
+        let response = next.await.unwrap().unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let drawn: Value = serde_json::from_slice(&body).unwrap();
+        assert!(seeds.contains(&drawn["seed"]));
+    }
+
+    #[tokio::test]
+    async fn draw_next_times_out() {
+        let (status, _) = get_json("/draw/next?timeout=10ms").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        for timeout in ["61s", "soon"] {
+            let (status, _) = get_json(&format!("/draw/next?timeout={timeout}")).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{timeout}");
+        }
+        assert_eq!(parse_timeout("30"), Some(Duration::from_secs(30)));
+    }
+
+    #[tokio::test]
+    async fn possible_categories_of_known_cards() {
+        let (status, possible) = get_json("/possible/7k,7r,7h,7s").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(possible["completions"], 48);
+        assert_eq!(possible["categories"][0]["category"], "FourOfAKind");
+        for uri in [
+            "/possible/7k,7k",
+            "/possible/1s,2s,3s,4s,5s",
+            "/possible/7x",
+        ] {
+            let (status, _) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_draws_requested_count() {
+        let (status, replayed) = get_json("/replay/42?count=3").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed["hands"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn metrics_are_recorded_per_route() {
+        let router = create();
+        for uri in ["/draw", "/analyze/tr,jr,qr,kr,1r", "/metrics"] {
+            router
+                .clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+        let response = router
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains(r#"http_request_duration_seconds_count{route="/draw"} 1"#));
+        assert!(body.contains(r#"http_request_duration_seconds_count{route="/analyze/:cards"} 1"#));
+        assert!(body.contains(r#"http_request_duration_seconds_count{route="/metrics"} 1"#));
+    }
+
+    #[tokio::test]
+    async fn entropy_source_health_is_exported() {
+        let metrics = |router: Router| async move {
+            let response = router
+                .clone()
+                .oneshot(Request::get("/draw").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let response = router
+                .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        };
+        assert!(!metrics(create()).await.contains("entropy_source_healthy"));
+
+        // A missing source is unhealthy, so draws fall back to the operating system.
+        let config = RouterConfig {
+            entropy_source: Some("/nonexistent/entropy".into()),
+            ..RouterConfig::default()
+        };
+        assert!(metrics(create_with(&config))
+            .await
+            .contains("entropy_source_healthy 0"));
+
+        let path = std::env::temp_dir().join(format!("router-entropy-{}", std::process::id()));
+        let numbers: Vec<u8> = (1..=100_u64).flat_map(u64::to_le_bytes).collect();
+        std::fs::write(&path, numbers).unwrap();
+        let config = RouterConfig {
+            entropy_source: Some(path.clone()),
+            ..RouterConfig::default()
+        };
+        let router = create_with(&config);
+        let start = std::time::Instant::now();
+        // The source is read in the background, so the first draws may still fall back.
+        while !metrics(router.clone())
+            .await
+            .contains("entropy_source_healthy 1")
+        {
+            assert!(start.elapsed() < Duration::from_secs(1));
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn simulation_job_runs_to_completion() {
+        let router = create();
+        let request = Request::post("/simulate")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"iterations": 1000, "seed": 42}"#))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let location = response.headers()[header::LOCATION]
+            .to_str()
+            .unwrap()
+            .to_owned();
+
+        let job = loop {
+            let response = router
+                .clone()
+                .oneshot(Request::get(&location).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let job: Value = serde_json::from_slice(&body).unwrap();
+            if job["status"] == "done" {
+                break job;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        assert_eq!(job["completed"], 1000);
+        let total: u64 = job["frequencies"]
+            .as_object()
+            .unwrap()
+            .values()
+            .map(|count| count.as_u64().unwrap())
+            .sum();
+        assert_eq!(total, 1000);
+    }
+
+    #[tokio::test]
+    async fn job_events_stream_until_done() {
+        let router = create();
+        let (status, started) =
+            post_json(&router, "/simulate", r#"{"iterations": 50000, "seed": 42}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let uri = format!("/jobs/{}/events", started["id"]);
+        let response = router
+            .clone()
+            .oneshot(Request::get(&uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        // The stream ends once the job is done, so the whole body can be read.
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let events: Vec<&str> = body
+            .lines()
+            .filter_map(|line| line.strip_prefix("event:"))
+            .collect();
+        assert_eq!(events.last(), Some(&"done"));
+        assert!(events[..events.len() - 1].iter().all(|&e| e == "progress"));
+        let last: Value = serde_json::from_str(
+            body.lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .next_back()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(last["completed"], 50000);
+
+        let (status, _) = get_json("/jobs/12345/events").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn configured_time_limit_truncates_simulations() {
+        let config = RouterConfig {
+            simulation_time_limit: Duration::ZERO,
+            ..RouterConfig::default()
+        };
+        let router = create_with(&config);
+        let (status, started) =
+            post_json(&router, "/simulate", r#"{"iterations": 1000, "seed": 42}"#).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let uri = format!("/jobs/{}", started["id"]);
+        let job = loop {
+            let response = router
+                .clone()
+                .oneshot(Request::get(&uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let job: Value = serde_json::from_slice(&body).unwrap();
+            if job["status"] == "done" {
+                break job;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        assert_eq!(job["truncated"], true);
+        assert_eq!(job["completed"], 0);
+    }
+
+    #[tokio::test]
+    async fn simulations_are_refused_while_busy() {
+        let config = RouterConfig {
+            max_running_jobs: 0,
+            ..RouterConfig::default()
+        };
+        let request = Request::post("/simulate")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"iterations": 1000}"#))
+            .unwrap();
+        let response = create_with(&config).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "5");
+    }
+
+    #[tokio::test]
+    async fn expensive_requests_are_shed_while_busy() {
+        let config = RouterConfig {
+            max_running_jobs: 0,
+            benchmark_endpoint: true,
+            ..RouterConfig::default()
+        };
+        let router = create_with(&config);
+        for (uri, status) in [
+            ("/possible/ks", StatusCode::SERVICE_UNAVAILABLE),
+            ("/odds/ks,kh/2r,7k,9s", StatusCode::SERVICE_UNAVAILABLE),
+            (
+                "/evaluators/benchmark?hands=1",
+                StatusCode::SERVICE_UNAVAILABLE,
+            ),
+            ("/draw", StatusCode::OK),
+        ] {
+            let response = router
+                .clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn simulation_target_must_be_a_share() {
+        let request = Request::post("/simulate")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                r#"{"iterations": 1000, "target_half_width": 0}"#,
+            ))
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn relabeled_hand_is_analyzed_from_cache() {
+        let router = create();
+        for uri in ["/analyze/2r,2s,5k,9h,jr", "/analyze/2h,2k,5s,9r,jh"] {
+            let response = router
+                .clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!(&body[..], br#""OnePair""#);
+        }
+        let response = router
+            .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains("analyze_cache_hits_total 1"));
+        assert!(body.contains("analyze_cache_misses_total 1"));
+    }
+
+    #[tokio::test]
+    async fn cards_can_be_given_as_query_parameters() {
+        let router = create();
+        let response = router
+            .clone()
+            .oneshot(
+                Request::get("/analyze?card=2r&card=2s&lang=no&card=5k&card=9h&card=jr")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(&body[..], br#""OnePair""#);
+
+        // HTML forms percent-encode the values, and the language too.
+        let response = router
+            .clone()
+            .oneshot(
+                Request::get("/analyze?card=%32r&card=2s&card=5k&card=9h&card=xr&lang=n%62-NO")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            body,
+            Language::Norwegian.card_error(&InvalidConversion::Rank('x'))
+        );
+        let response = router
+            .clone()
+            .oneshot(
+                Request::get("/analyze?card=%32r&card=2%73&card=5k&card=9h&card=jr")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for uri in [
+            "/analyze?card=2r&card=2s",
+            "/analyze?card=2r&card=2p",
+            "/analyze",
+        ] {
+            let response = router
+                .clone()
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn draw_renders_text_when_accepted() {
+        let request = Request::get("/draw")
+            .header(header::ACCEPT, "text/plain")
+            .body(Body::empty())
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        assert_eq!(text.lines().count(), 7);
+        assert!(text.starts_with(".-----. .-----."));
+
+        let request = Request::get("/draw?lang=no")
+            .header(header::ACCEPT, "text/plain")
+            .body(Body::empty())
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        let category = *response.extensions().get::<HandCategory>().unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        assert_eq!(
+            text.lines().last(),
+            Some(Language::Norwegian.category_name(category))
+        );
+    }
+
+    #[test]
+    fn json_is_preferred_unless_text_comes_first() {
+        let accept = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, value.parse().unwrap());
+            prefers(&headers, "text/plain")
+        };
+        assert!(accept("text/plain"));
+        assert!(accept("text/plain;q=0.9, application/json"));
+        assert!(!accept("application/json, text/plain"));
+        assert!(!accept("*/*"));
+        assert!(!prefers(&HeaderMap::new(), "text/plain"));
+    }
+
+    #[tokio::test]
+    async fn errors_are_localized() {
+        let request = Request::get("/analyze/tr,jr,qr,kr,1p")
+            .header(header::ACCEPT_LANGUAGE, "nb-NO, en;q=0.5")
+            .body(Body::empty())
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            &body[..],
+            "kortet er ugyldig: p er ikke en gyldig farge".as_bytes()
+        );
+    }
+
+    #[tokio::test]
+    async fn lang_parameter_selects_language() {
+        let (status, drawn) = get_json("/draw?lang=no").await;
+        assert_eq!(status, StatusCode::OK);
+        let category = serde_json::from_value(drawn["category"].clone()).unwrap();
+        assert_eq!(
+            drawn["category_name"],
+            Language::Norwegian.category_name(category)
+        );
+    }
+
+    async fn post_json(router: &Router, uri: &str, body: &str) -> (StatusCode, Value) {
+        let request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_owned()))
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn seeded_environment_draws_deterministically() {
+        use crate::environment::{ManualClock, SeededRandomness};
+
+        let draw = || async {
+            let router = create_with_environment(
+                &RouterConfig::default(),
+                Environment {
+                    clock: Arc::new(ManualClock::default()),
+                    randomness: Arc::new(SeededRandomness::new(168)),
+                },
+            );
+            let response = router
+                .clone()
+                .oneshot(Request::get("/draw").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            let draw: Value = serde_json::from_slice(&body).unwrap();
+            let metrics = router
+                .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let metrics = hyper::body::to_bytes(metrics.into_body()).await.unwrap();
+            (draw, String::from_utf8(metrics.to_vec()).unwrap())
+        };
+        let ((a, metrics), (b, _)) = (draw().await, draw().await);
+        assert_eq!(a, b);
+        // The clock stands still, so the draw took no time.
+        assert!(metrics.contains(r#"http_request_duration_seconds_sum{route="/draw"} 0"#));
+    }
+
+    #[tokio::test]
+    async fn hand_of_the_hour_counts_down_and_replays() {
+        use crate::environment::{ManualClock, ThreadRandomness};
+
+        let clock = Arc::new(ManualClock::starting_at(Duration::from_secs(1_700_003_400)));
+        let router = create_with_environment(
+            &RouterConfig::default(),
+            Environment {
+                clock: clock.clone(),
+                randomness: Arc::new(ThreadRandomness),
+            },
+        );
+        let hour = |router: Router| async move {
+            let response = router
+                .oneshot(
+                    Request::get("/hand-of-the-hour")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            serde_json::from_slice::<Value>(&body).unwrap()
+        };
+        let first = hour(router.clone()).await;
+        assert_eq!(first["starts_at"], 1_700_002_800);
+        assert_eq!(first["seconds_left"], 3000);
+        let (_, replayed) = get_json(&format!("/replay/{}", first["starts_at"])).await;
+        assert_eq!(replayed["hands"][0]["hand"], first["hand"]);
+
+        clock.advance(Duration::from_secs(3000));
+        let next = hour(router).await;
+        assert_eq!(next["starts_at"], first["ends_at"]);
+        assert_eq!(next["seconds_left"], 3600);
+    }
+
+    #[tokio::test]
+    async fn quiz_answer_reveals_category() {
+        let router = create();
+        let response = router
+            .clone()
+            .oneshot(Request::get("/quiz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let question: Value = serde_json::from_slice(&body).unwrap();
+        assert!(question.get("category").is_none());
+        let uri = format!("/quiz/{}/answer", question["token"].as_str().unwrap());
+
+        let (status, answer) = post_json(&router, &uri, r#"{"category": "FullHouse"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(answer["correct"], answer["category"] == "FullHouse");
+        assert_eq!(answer["score"]["answered"], 1);
+
+        let (status, _) = post_json(&router, &uri, r#"{"category": "FullHouse"}"#).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn trainer_grades_winner() {
+        let router = create();
+        let response = router
+            .clone()
+            .oneshot(
+                Request::get("/trainer?players=3")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let question: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(question["hands"].as_array().unwrap().len(), 3);
+        let uri = format!("/trainer/{}/answer", question["token"].as_str().unwrap());
+
+        let (status, answer) = post_json(&router, &uri, r#"{"winners": [0]}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            answer["correct"],
+            answer["winners"] == serde_json::json!([0])
+        );
+        assert_eq!(answer["strengths"].as_array().unwrap().len(), 3);
+        assert!(!answer["explanation"].as_str().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn trainer_players_are_limited() {
+        let (status, _) = get_json("/trainer?players=11").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json("/trainer?players=1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json("/trainer?players=8&short_deck=true").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn trainer_deals_by_the_rules() {
+        let (status, question) = get_json("/trainer?short_deck=true&min_players=3").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(question["hands"].as_array().unwrap().len(), 3);
+        assert_eq!(question["rules"]["short_deck"], true);
+        assert_eq!(question["rules"]["straights"], "ace_both_ends");
+    }
+
+    #[tokio::test]
+    async fn odds_table_is_returned() {
+        let (status, table) = get_json("/odds/1s,ks/7s,2s,9h,3r").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(table["current"], "HighCard");
+        assert!(table["rows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|row| row["category"] == "Flush" && row["outs"] == 9));
+    }
+
+    #[tokio::test]
+    async fn odds_need_a_flop_or_turn() {
+        let (status, _) = get_json("/odds/1s,ks/7s,2s").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn errors_are_given_in_the_language_of_the_request() {
+        for (uri, error) in [
+            (
+                "/odds/1s,ks,qs/7s,2s,9h",
+                Language::Norwegian.odds_error(&OddsError::HoleCards(3)),
+            ),
+            (
+                "/variants/razz/analyze/1s,2h,3r,4k",
+                Language::Norwegian.variant_error(&VariantError::Cards {
+                    given: 4,
+                    expected: 5..=7,
+                }),
+            ),
+            (
+                "/paigow/set/1s,kh,9r",
+                Language::Norwegian.paigow_error(&PaiGowError::Cards(3)),
+            ),
+            (
+                "/replay/1?count=1001",
+                Language::Norwegian.at_most("count", &1001, &MAX_REPLAY_COUNT),
+            ),
+            (
+                "/draw/next?timeout=soon",
+                Language::Norwegian.timeout("soon", MAX_DRAW_NEXT_TIMEOUT.as_secs()),
+            ),
+        ] {
+            let response = create()
+                .oneshot(
+                    Request::get(uri)
+                        .header(header::ACCEPT_LANGUAGE, "nb-NO, en;q=0.8")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!(body, error, "{uri}");
+        }
+
+        let config = RouterConfig {
+            max_running_jobs: 0,
+            ..RouterConfig::default()
+        };
+        for (uri, status, error) in [
+            (
+                "/jobs/404?lang=no",
+                StatusCode::NOT_FOUND,
+                Language::Norwegian.no_job(404),
+            ),
+            (
+                "/variants/pineapple/deal?lang=no",
+                StatusCode::NOT_FOUND,
+                Language::Norwegian.no_variant("pineapple"),
+            ),
+            (
+                "/possible/ks?lang=no",
+                StatusCode::SERVICE_UNAVAILABLE,
+                Language::Norwegian.busy(0),
+            ),
+        ] {
+            let response = create_with(&config)
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status, "{uri}");
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            assert_eq!(body, error, "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn variants_are_resolved_by_name() {
+        let (status, analysis) = get_json("/variants/holdem/analyze/1s,ks,7s,2s,9s,3r,4k").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(analysis["category"], "Flush");
+        let (status, _) = get_json("/variants/three-card/analyze/1s,ks,7s,2s").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json("/variants/pineapple/analyze/1s,ks,7s,2s,9s").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, dealt) = get_json("/variants/omaha/deal").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(dealt["cards"].as_array().unwrap().len(), 9);
+
+        let (_, names) = get_json("/variants").await;
+        assert_eq!(names.as_array().unwrap().len(), 7);
+    }
+
+    #[tokio::test]
+    async fn stud_variants_are_served() {
+        let (status, razz) = get_json("/variants/razz/analyze/1s,2h,3r,4k,5s,2s,kh").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(razz["tie_breakers"], serde_json::json!([5, 4, 3, 2, 1]));
+        assert_eq!(razz["low_wins"], true);
+        assert!(razz.get("low").is_none());
+
+        let (status, split) = get_json("/variants/stud-hi-lo/analyze/1s,2s,3s,4s,8s,kh,kr").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(split["category"], "Flush");
+        assert_eq!(
+            split["low"]["tie_breakers"],
+            serde_json::json!([8, 4, 3, 2, 1])
+        );
+        let (status, _) = get_json("/variants/razz/analyze/1s,2h,3r,4k").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, dealt) = get_json("/variants/stud-hi-lo/deal").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(dealt["cards"].as_array().unwrap().len(), 7);
+    }
+
+    #[tokio::test]
+    async fn custom_variants_are_served() {
+        use crate::variant::Holdem;
+
+        let mut variants = Variants::empty();
+        variants.register("house", Arc::new(Holdem::default()));
+        let config = RouterConfig {
+            variants,
+            ..RouterConfig::default()
+        };
+        let response = create_with(&config)
+            .oneshot(
+                Request::get("/variants/house/deal")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = create_with(&config)
+            .oneshot(
+                Request::get("/variants/holdem/deal")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn percentile_ranks_hands_and_hole_cards() {
+        let (status, royal) = get_json("/percentile/ts,js,qs,ks,1s").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(royal["value"], 7461);
+
+        let (status, hole) = get_json("/percentile/1s,ks?board=qs,js,ts").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(hole["strength"]["category"], "StraightFlush");
+        assert_eq!(hole["combos"], 1176);
+
+        let (status, _) = get_json("/percentile/1s,ks").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json("/percentile/1s,ks?board=qs,ks,ts").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn paigow_sets_by_the_house_way() {
+        let (status, body) = get_json("/paigow/set/9s,9h,1r,qk,7s,4h,2r").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["high_strength"]["category"], "OnePair");
+        assert_eq!(body["low_strength"]["category"], "HighCard");
+        assert_eq!(
+            body["low_strength"]["tie_breakers"],
+            serde_json::json!([14, 12])
+        );
+
+        let (status, _) = get_json("/paigow/set/9s,9h,1r,qk,7s,4h").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn conformance_fixtures_pass() {
+        let (status, body) = get_json("/conformance").await;
+        assert_eq!(status, StatusCode::OK);
+        let total = body["fixtures"].as_array().unwrap().len();
+        assert!(total > 0);
+        for evaluator in ["naive", "bit_count"] {
+            assert_eq!(body[evaluator]["passed"], total);
+            assert!(body[evaluator]["failed"].as_array().unwrap().is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn evaluators_are_benchmarked() {
+        let config = RouterConfig {
+            benchmark_endpoint: true,
+            ..RouterConfig::default()
+        };
+        let get_json = |uri| get_json_with(&config, uri);
+        let (status, body) = get_json("/evaluators/benchmark?hands=200&cards=6&seed=196").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            (body["hands"].clone(), body["cards"].clone()),
+            (200.into(), 6.into())
+        );
+        let names: Vec<_> = body["evaluators"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|evaluator| {
+                assert_eq!(evaluator["agreement"], 1.0);
+                evaluator["name"].as_str().unwrap()
+            })
+            .collect();
+        assert_eq!(names, ["naive", "bit_count"]);
+
+        let (status, _) = get_json("/evaluators/benchmark?cards=8").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get_json("/evaluators/benchmark?hands=100001").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn benchmarks_are_not_served_by_default() {
+        let (status, _) = get_json("/evaluators/benchmark?hands=1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "signing")]
+    #[tokio::test]
+    async fn analyze_responses_are_signed() {
+        use crate::signing::{SigningKey, SIGNATURE_HEADER};
+        use base64::{engine::general_purpose::STANDARD, Engine};
+        use ring::signature::{UnparsedPublicKey, ED25519};
+
+        let config = RouterConfig {
+            signing_key: Some(SigningKey::from_seed(&[7; 32])),
+            ..RouterConfig::default()
+        };
+        let router = create_with(&config);
+        let response = router
+            .clone()
+            .oneshot(
+                Request::get("/analyze/tr,jr,qr,kr,1r")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let signature = STANDARD
+            .decode(response.headers()[SIGNATURE_HEADER].as_bytes())
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        let response = router
+            .oneshot(
+                Request::get("/.well-known/signing-key")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let key = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let key: Value = serde_json::from_slice(&key).unwrap();
+        let public_key = STANDARD
+            .decode(key["public_key"].as_str().unwrap())
+            .unwrap();
+        assert!(UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(&body, &signature)
+            .is_ok());
+
+        let response = create()
+            .oneshot(
+                Request::get("/analyze/tr,jr,qr,kr,1r")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(SIGNATURE_HEADER));
+        let (status, _) = get_json("/.well-known/signing-key").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[cfg(feature = "cbor")]
+    #[tokio::test]
+    async fn cbor_is_returned_when_accepted() {
+        let request = Request::get("/draw?fields=category,seed")
+            .header(header::ACCEPT, "application/cbor")
+            .body(Body::empty())
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/cbor");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let drawn: Value = ciborium::from_reader(body.as_ref()).unwrap();
+        let (_, replayed) = get_json(&format!("/replay/{}", drawn["seed"])).await;
+        assert_eq!(drawn["category"], replayed["hands"][0]["category"]);
+        assert_eq!(drawn.as_object().unwrap().len(), 2);
+
+        let request = Request::get("/analyze/tr,jr,qr,kr,1r")
+            .header(header::ACCEPT, "application/cbor, application/json")
+            .body(Body::empty())
+            .unwrap();
+        let response = create().oneshot(request).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(
+            ciborium::from_reader::<Value, _>(body.as_ref()).unwrap(),
+            "StraightFlush"
+        );
+    }
+
+    #[cfg(feature = "demo")]
+    #[tokio::test]
+    async fn demo_draws_are_exciting_and_labeled() {
+        use crate::demo::{DemoRate, EXCITING};
+
+        let config = RouterConfig {
+            demo_rate: Some(DemoRate::new(1.0).unwrap()),
+            ..RouterConfig::default()
+        };
+        let response = create_with(&config)
+            .oneshot(Request::get("/draw").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["demo_rate"], 1.0);
+        let category: HandCategory = serde_json::from_value(body["category"].clone()).unwrap();
+        assert!(category >= EXCITING);
+
+        let (_, fair) = get_json("/draw").await;
+        assert!(fair.get("demo_rate").is_none());
+    }
+
+    #[tokio::test]
+    async fn warmup_does_nothing() {
+        let (status, _) = get_json("/warmup").await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+
+    #[tokio::test]
+    async fn unknown_job_is_not_found() {
+        let (status, _) = get_json("/jobs/12345").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn bulk_deals_are_reproducible() {
+        let router = create();
+        let body = r#"{"deals": 3, "hands": 4, "seed": 205}"#;
+        let (status, first) = post_json(&router, "/deal/bulk", body).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, second) = post_json(&router, "/deal/bulk", body).await;
+        assert_eq!(first, second);
+        assert_eq!(first["seed"], 205);
+
+        let deals = first["deals"].as_array().unwrap();
+        assert_eq!(deals.len(), 3);
+        assert_ne!(deals[0]["seed"], deals[1]["seed"]);
+        for deal in deals {
+            let hands: Vec<Hand> = deal["hands"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|hand| hand.as_str().unwrap().parse().unwrap())
+                .collect();
+            let mut cards: Vec<_> = hands.iter().flat_map(Hand::cards).collect();
+            cards.sort();
+            cards.dedup();
+            assert_eq!(cards.len(), 20);
+        }
+
+        let (status, _) = post_json(&router, "/deal/bulk", r#"{"deals": 1, "hands": 11}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = post_json(&router, "/deal/bulk", r#"{"deals": 1001, "hands": 2}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn bulk_deals_are_reconstructed_from_their_seeds() {
+        let router = create();
+        let (_, dealt) = post_json(&router, "/deal/bulk", r#"{"deals": 2, "hands": 3}"#).await;
+        for deal in dealt["deals"].as_array().unwrap() {
+            let (status, verified) =
+                get_json(&format!("/shuffle/verify?seed={}", deal["seed"])).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(verified["shuffle_version"], SHUFFLE_VERSION);
+            let cards: Vec<Card> = verified["cards"]
+                .as_str()
+                .unwrap()
+                .split(',')
+                .map(|card| card.parse().unwrap())
+                .collect();
+            assert_eq!(cards.len(), 52);
+            for (i, hand) in deal["hands"].as_array().unwrap().iter().enumerate() {
+                let hand: Hand = hand.as_str().unwrap().parse().unwrap();
+                assert_eq!(hand, Hand::try_from(&cards[5 * i..5 * i + 5]).unwrap());
+            }
+        }
+
+        let (status, _) = get_json("/shuffle/verify?seed=-1").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn replay_count_is_limited() {
+        let (status, _) = get_json("/replay/42?count=1001").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn pathological_requests_are_client_errors() {
+        let many_cards = vec!["ks"; 10_000].join(",");
+        let (status, body) = get_json(&format!("/analyze/{many_cards}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, Value::Null, "the error is plain text");
+        let (status, _) = get_json(&format!("/odds/{many_cards}/ks")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) =
+            get_json(&format!("/analyze?card={}", vec!["ks"; 53].join("&card="))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        for uri in [
+            "/analyze/ks%00,kh,kr,kk,1s",
+            "/percentile/%FF%FE",
+            "/possible/%C3%28",
+        ] {
+            let (status, _) = get_json(uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+        }
+
+        let router = create();
+        let nested = format!("{}{}", "[".repeat(5000), "]".repeat(5000));
+        for uri in ["/deal/bulk", "/simulate", "/quiz/x/answer"] {
+            let (status, _) = post_json(&router, uri, &nested).await;
+            assert!(status.is_client_error(), "{uri}: {status}");
+        }
+        let oversized = format!(
+            r#"{{"hands": 1, "padding": "{}"}}"#,
+            "x".repeat(MAX_BODY_BYTES)
+        );
+        let (status, _) = post_json(&router, "/deal/bulk", &oversized).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn random_paths_are_never_server_errors() {
+        let mut rng = seeded_rng(227);
+        for _ in 0..200 {
+            let mut bytes = [0; 12];
+            rng.fill_bytes(&mut bytes);
+            let encoded: String = bytes.iter().map(|b| format!("%{b:02X}")).collect();
+            for uri in [
+                format!("/analyze/{encoded}"),
+                format!("/analyze?card={encoded}"),
+                format!("/variants/{encoded}/analyze/{encoded}"),
+                format!("/odds/{encoded}/{encoded}"),
+                format!("/possible/{encoded}"),
+                format!("/paigow/set/{encoded}"),
+                format!("/replay/{encoded}"),
+                format!("/jobs/{encoded}"),
+            ] {
+                let (status, _) = get_json(&uri).await;
+                assert!(status.is_client_error(), "{uri}: {status}");
+            }
+        }
     }
 }
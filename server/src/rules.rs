@@ -0,0 +1,309 @@
+//! [`Rules`] which differ between tables, like short-deck hold'em, instead of one behavior for the
+//! whole server.
+//!
+//! Rules are validated with [`Rules::validate`] when a table deals, and then give its deck, the
+//! [`Strength`] of its hands, and how strengths compare. With the [`Default`] rules, these are the
+//! same as [`crate::deck`] and [`crate::compare`].
+
+use crate::{
+    card::{Card, Rank},
+    compare::{strength, Strength},
+    deck::remaining_cards,
+    hand::{Hand, HandCategory},
+    shuffle::shuffle_deck,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Which ranks make a straight, besides five consecutive ranks with the Ace above the King.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Straights {
+    /// The Ace only plays high, so A-2-3-4-5 is not a straight.
+    AceHigh,
+    /// The Ace also plays low, in A-2-3-4-5, or A-6-7-8-9 in short deck, which is the lowest
+    /// straight.
+    #[default]
+    AceBothEnds,
+    /// Straights also go "round the corner" from the King to the Two, like Q-K-A-2-3. These rank
+    /// below the Ace-low straight, the higher the lower cards are, so K-A-2-3-4 is the best of them.
+    WrapAround,
+}
+
+/// The rules of a table.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct Rules {
+    pub straights: Straights,
+    /// Short-deck, or six plus, ranking: the Twos through Fives are taken out of the deck, and a
+    /// flush beats a full house.
+    pub short_deck: bool,
+    /// The fewest players the table deals to.
+    pub min_players: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            straights: Straights::default(),
+            short_deck: false,
+            min_players: 2,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum RulesError {
+    #[error("players ({players}) must be from {min} to {max}")]
+    Players {
+        players: usize,
+        min: usize,
+        max: usize,
+    },
+    #[error("the minimum of players ({0}) must be from 2 to the most the deck deals to")]
+    MinPlayers(usize),
+    #[error("{0} is not in the deck")]
+    NotInDeck(Card),
+}
+
+/// The ranks taken out of a short deck.
+const SHORT_DECK_REMOVED: [Rank; 4] = [Rank::Two, Rank::Three, Rank::Four, Rank::Five];
+
+impl Rules {
+    /// Fail if the rules cannot be played.
+    pub fn validate(&self) -> Result<(), RulesError> {
+        if !(2..=self.max_players()).contains(&self.min_players) {
+            return Err(RulesError::MinPlayers(self.min_players));
+        }
+        Ok(())
+    }
+
+    /// The most players the deck deals five cards to.
+    pub fn max_players(&self) -> usize {
+        self.deck().len() / 5
+    }
+
+    /// Fail if the table cannot deal to the number of `players`.
+    pub fn check_players(&self, players: usize) -> Result<(), RulesError> {
+        let (min, max) = (self.min_players, self.max_players());
+        if (min..=max).contains(&players) {
+            Ok(())
+        } else {
+            Err(RulesError::Players { players, min, max })
+        }
+    }
+
+    /// The cards of the deck.
+    pub fn deck(&self) -> Vec<Card> {
+        let mut deck = remaining_cards(&[]);
+        if self.short_deck {
+            deck.retain(|card| !SHORT_DECK_REMOVED.contains(&card.rank));
+        }
+        deck
+    }
+
+    /// Deal `players` [`Hand`]s from the deck shuffled with the seed, so no card is in two hands.
+    /// See [`crate::shuffle`].
+    pub fn deal(&self, players: usize, seed: u64) -> Result<Vec<Hand>, RulesError> {
+        self.check_players(players)?;
+        Ok(shuffle_deck(self.deck(), seed)
+            .chunks(5)
+            .take(players)
+            .map(|hand| Hand::try_from(hand).expect("we gave five unique cards"))
+            .collect())
+    }
+
+    /// The [`Strength`] of the [`Hand`] under the rules, which fails if a card is not in the deck.
+    ///
+    /// The top rank of a straight breaks ties, where the Ace-low straight tops at its Five, or its
+    /// Nine in short deck, and a wrap-around straight at its highest card below the Ace.
+    pub fn strength(&self, hand: &Hand) -> Result<Strength, RulesError> {
+        use HandCategory::{Flush, HighCard, Straight, StraightFlush};
+
+        let deck = self.deck();
+        if let Some(card) = hand.cards().find(|card| !deck.contains(card)) {
+            return Err(RulesError::NotInDeck(*card));
+        }
+        let mut strength = strength(hand);
+        let mut ranks: Vec<u8> = hand.cards().map(|c| c.rank.numeric_ace_high()).collect();
+        ranks.sort_unstable_by(|a, b| b.cmp(a));
+        ranks.dedup();
+        // Five distinct ranks are a straight, a flush or a high card, which the rules decide.
+        if ranks.len() == 5 {
+            let suited = hand.count_suits().contains(&5);
+            (strength.category, strength.tie_breakers) = match self.straight_top(&ranks) {
+                Some(top) => (if suited { StraightFlush } else { Straight }, vec![top]),
+                None => (if suited { Flush } else { HighCard }, ranks),
+            };
+        }
+        Ok(strength)
+    }
+
+    /// The top rank of the straight of five distinct `ranks`, from the highest, if they make one.
+    fn straight_top(&self, ranks: &[u8]) -> Option<u8> {
+        if ranks[0] - ranks[4] == 4 {
+            return Some(ranks[0]);
+        }
+        // Otherwise, a straight runs down from the Ace and on from the lowest rank of the deck.
+        let lowest = if self.short_deck { 6 } else { 2 };
+        let from_ace = ranks
+            .iter()
+            .zip((0..).map(|i| 14 - i))
+            .take_while(|(r, v)| **r == *v)
+            .count();
+        let from_lowest = ranks
+            .iter()
+            .rev()
+            .zip(lowest..)
+            .take_while(|(r, v)| **r == *v)
+            .count();
+        let wrapping = match self.straights {
+            Straights::AceHigh => 0,
+            Straights::AceBothEnds => 1,
+            Straights::WrapAround => 4,
+        };
+        ((1..=wrapping).contains(&from_ace) && from_ace + from_lowest == 5)
+            .then(|| lowest + u8::try_from(from_lowest).expect("at most five ranks") - 1)
+    }
+
+    /// Compare two strengths under the rules. The greater wins, and equal strengths split the pot.
+    pub fn compare(&self, a: &Strength, b: &Strength) -> Ordering {
+        (self.category_order(a.category), &a.tie_breakers)
+            .cmp(&(self.category_order(b.category), &b.tie_breakers))
+    }
+
+    /// The order of the category among the categories, from the weakest.
+    fn category_order(&self, category: HandCategory) -> u8 {
+        match category {
+            HandCategory::Flush if self.short_deck => HandCategory::FullHouse as u8,
+            HandCategory::FullHouse if self.short_deck => HandCategory::Flush as u8,
+            category => category as u8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strength_of(rules: &Rules, hand: &str) -> Strength {
+        rules.strength(&hand.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn default_rules_are_standard() {
+        let rules = Rules::default();
+        assert_eq!(rules.validate(), Ok(()));
+        assert_eq!(rules.deck().len(), 52);
+        for hand in ["1s,2h,3r,4k,5s", "2s,5s,8s,js,qs", "ts,th,tr,4k,4s"] {
+            let hand: Hand = hand.parse().unwrap();
+            assert_eq!(rules.strength(&hand), Ok(strength(&hand)));
+        }
+    }
+
+    #[test]
+    fn ace_low_straights_can_be_disallowed() {
+        let rules = Rules {
+            straights: Straights::AceHigh,
+            ..Rules::default()
+        };
+        let wheel = strength_of(&rules, "1s,2h,3r,4k,5s");
+        assert_eq!(wheel.category, HandCategory::HighCard);
+        assert_eq!(wheel.tie_breakers, [14, 5, 4, 3, 2]);
+        let steel_wheel = strength_of(&rules, "1s,2s,3s,4s,5s");
+        assert_eq!(steel_wheel.category, HandCategory::Flush);
+        let broadway = strength_of(&rules, "1s,kh,qr,jk,ts");
+        assert_eq!(broadway.category, HandCategory::Straight);
+    }
+
+    #[test]
+    fn short_deck_ranks_flush_over_full_house() {
+        let rules = Rules {
+            short_deck: true,
+            ..Rules::default()
+        };
+        assert_eq!(rules.deck().len(), 36);
+        assert_eq!(rules.max_players(), 7);
+        let flush = strength_of(&rules, "6s,8s,9s,js,qs");
+        let full_house = strength_of(&rules, "ts,th,tr,9k,9s");
+        assert_eq!(rules.compare(&flush, &full_house), Ordering::Greater);
+        assert_eq!(
+            rules.strength(&"1s,2h,7r,8k,9s".parse().unwrap()),
+            Err(RulesError::NotInDeck("2h".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn short_deck_ace_plays_below_the_six() {
+        let rules = Rules {
+            short_deck: true,
+            ..Rules::default()
+        };
+        let straight = strength_of(&rules, "1s,6h,7r,8k,9s");
+        assert_eq!(straight.category, HandCategory::Straight);
+        assert_eq!(straight.tie_breakers, [9]);
+        let rules = Rules {
+            straights: Straights::AceHigh,
+            ..rules
+        };
+        let high_card = strength_of(&rules, "1s,6h,7r,8k,9s");
+        assert_eq!(high_card.category, HandCategory::HighCard);
+    }
+
+    #[test]
+    fn straights_can_wrap_around_the_corner() {
+        let rules = Rules {
+            straights: Straights::WrapAround,
+            ..Rules::default()
+        };
+        let round_the_corner = strength_of(&rules, "qs,kh,1r,2k,3s");
+        assert_eq!(round_the_corner.category, HandCategory::Straight);
+        assert_eq!(round_the_corner.tie_breakers, [3]);
+        let wheel = strength_of(&rules, "1s,2h,3r,4k,5s");
+        let best_wrap = strength_of(&rules, "ks,1h,2r,3k,4s");
+        assert_eq!(rules.compare(&wheel, &best_wrap), Ordering::Greater);
+        assert_eq!(
+            rules.compare(&best_wrap, &round_the_corner),
+            Ordering::Greater
+        );
+        let wrapped_flush = strength_of(&rules, "js,qs,ks,1s,2s");
+        assert_eq!(wrapped_flush.category, HandCategory::StraightFlush);
+        assert_eq!(
+            strength_of(&Rules::default(), "qs,kh,1r,2k,3s").category,
+            HandCategory::HighCard
+        );
+
+        let short_deck = Rules {
+            short_deck: true,
+            ..rules
+        };
+        let wrapped = strength_of(&short_deck, "ks,1h,6r,7k,8s");
+        assert_eq!(
+            (wrapped.category, wrapped.tie_breakers),
+            (HandCategory::Straight, vec![8])
+        );
+    }
+
+    #[test]
+    fn players_are_checked_against_the_rules() {
+        let rules = Rules {
+            short_deck: true,
+            min_players: 3,
+            ..Rules::default()
+        };
+        assert_eq!(rules.deal(3, 188).unwrap().len(), 3);
+        assert_eq!(
+            rules.deal(2, 188),
+            Err(RulesError::Players {
+                players: 2,
+                min: 3,
+                max: 7
+            })
+        );
+        let rules = Rules {
+            min_players: 8,
+            ..rules
+        };
+        assert_eq!(rules.validate(), Err(RulesError::MinPlayers(8)));
+    }
+}
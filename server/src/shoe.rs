@@ -0,0 +1,226 @@
+//! A [`Shoe`] of one or more decks, which deals hand after hand and reshuffles by a
+//! [`ReshufflePolicy`], like the shoe of a casino table.
+//!
+//! Unlike [`crate::deck`], where each draw is from a full deck, the cards dealt from a shoe stay
+//! out until it is reshuffled, so the hands of a session depend on each other like at a real
+//! table. The policy is applied before each hand, so a hand is never dealt across a reshuffle, and
+//! every reshuffle is reported with the hand it came before, see [`Dealt::reshuffle`].
+
+use crate::{card::Card, deck::remaining_cards};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// When a [`Shoe`] is reshuffled, besides when it runs out of cards for a hand.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReshufflePolicy {
+    /// Once the share of the shoe dealt, from 0 to 1, is at least this, e.g., 0.75.
+    Penetration(f64),
+    /// Before every hand but the first.
+    EveryHand,
+    /// Only with [`Shoe::reshuffle`].
+    OnDemand,
+}
+
+/// Why a [`Shoe`] was reshuffled.
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReshuffleReason {
+    Penetration,
+    EveryHand,
+    OnDemand,
+    /// Too few cards were left for the hand.
+    Exhausted,
+}
+
+/// A reshuffle of a [`Shoe`].
+#[derive(Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Reshuffle {
+    /// How many times the shoe has been reshuffled, counting this one.
+    pub number: u64,
+    pub reason: ReshuffleReason,
+    /// The cards dealt since the previous shuffle.
+    pub dealt: usize,
+}
+
+/// The cards of a hand dealt from a [`Shoe`].
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Dealt {
+    pub cards: Vec<Card>,
+    /// The reshuffle before the hand, if there was one.
+    pub reshuffle: Option<Reshuffle>,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum ShoeError {
+    #[error("number of decks ({0}) must be at least 1")]
+    Decks(usize),
+    #[error("penetration ({0}) must be more than 0 and at most 1")]
+    Penetration(f64),
+    #[error("a hand of {cards} cards does not fit in a shoe of {size}")]
+    HandTooLarge { cards: usize, size: usize },
+}
+
+/// One or more decks shuffled together, dealt from until reshuffled.
+#[derive(Clone, Debug)]
+pub struct Shoe<R> {
+    policy: ReshufflePolicy,
+    rng: R,
+    /// The cards of the shoe in the order they are dealt.
+    cards: Vec<Card>,
+    dealt: usize,
+    shuffles: u64,
+}
+
+impl<R: Rng> Shoe<R> {
+    /// Shuffle `decks` decks together into a shoe, which reshuffles by the `policy`.
+    pub fn new(decks: usize, policy: ReshufflePolicy, rng: R) -> Result<Self, ShoeError> {
+        if decks == 0 {
+            return Err(ShoeError::Decks(decks));
+        }
+        if let ReshufflePolicy::Penetration(penetration) = policy {
+            if !(penetration > 0.0 && penetration <= 1.0) {
+                return Err(ShoeError::Penetration(penetration));
+            }
+        }
+        let cards = remaining_cards(&[]).repeat(decks);
+        let mut shoe = Self {
+            policy,
+            rng,
+            cards,
+            dealt: 0,
+            shuffles: 0,
+        };
+        shoe.cards.shuffle(&mut shoe.rng);
+        Ok(shoe)
+    }
+
+    /// The number of cards in the shoe, dealt or not.
+    pub fn size(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// The number of cards left to deal before the shoe runs out.
+    pub fn remaining(&self) -> usize {
+        self.cards.len() - self.dealt
+    }
+
+    /// The share of the shoe dealt since it was shuffled, from 0 to 1.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn penetration(&self) -> f64 {
+        self.dealt as f64 / self.cards.len() as f64
+    }
+
+    /// Deal a hand of `count` cards, reshuffling first if the policy says so, or if there are not
+    /// enough cards left.
+    pub fn deal(&mut self, count: usize) -> Result<Dealt, ShoeError> {
+        if count > self.size() {
+            return Err(ShoeError::HandTooLarge {
+                cards: count,
+                size: self.size(),
+            });
+        }
+        let reason = match self.policy {
+            _ if count > self.remaining() => Some(ReshuffleReason::Exhausted),
+            ReshufflePolicy::Penetration(penetration) if self.penetration() >= penetration => {
+                Some(ReshuffleReason::Penetration)
+            }
+            ReshufflePolicy::EveryHand if self.dealt > 0 => Some(ReshuffleReason::EveryHand),
+            _ => None,
+        };
+        let reshuffle = reason.map(|reason| self.shuffle(reason));
+        let cards = self.cards[self.dealt..self.dealt + count].to_vec();
+        self.dealt += count;
+        Ok(Dealt { cards, reshuffle })
+    }
+
+    /// Gather the dealt cards and reshuffle the whole shoe now.
+    pub fn reshuffle(&mut self) -> Reshuffle {
+        self.shuffle(ReshuffleReason::OnDemand)
+    }
+
+    fn shuffle(&mut self, reason: ReshuffleReason) -> Reshuffle {
+        self.cards.shuffle(&mut self.rng);
+        self.shuffles += 1;
+        let reshuffle = Reshuffle {
+            number: self.shuffles,
+            reason,
+            dealt: self.dealt,
+        };
+        self.dealt = 0;
+        reshuffle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::seeded_rng;
+
+    #[test]
+    fn penetration_reshuffles_before_the_next_hand() {
+        let mut shoe = Shoe::new(1, ReshufflePolicy::Penetration(0.75), seeded_rng(197)).unwrap();
+        // Eight hands deal 40 of 52 cards, which is past 75 %.
+        for _ in 0..8 {
+            assert_eq!(shoe.deal(5).unwrap().reshuffle, None);
+        }
+        let dealt = shoe.deal(5).unwrap();
+        assert_eq!(
+            dealt.reshuffle,
+            Some(Reshuffle {
+                number: 1,
+                reason: ReshuffleReason::Penetration,
+                dealt: 40
+            })
+        );
+        assert_eq!(shoe.remaining(), 47);
+    }
+
+    #[test]
+    fn cards_stay_out_until_reshuffled() {
+        let mut shoe = Shoe::new(2, ReshufflePolicy::OnDemand, seeded_rng(197)).unwrap();
+        let mut cards: Vec<_> = (0..20).flat_map(|_| shoe.deal(5).unwrap().cards).collect();
+        assert_eq!(shoe.remaining(), 4);
+        cards.sort();
+        assert!(
+            cards.windows(3).all(|w| w[0] != w[2]),
+            "at most two of each card"
+        );
+
+        let dealt = shoe.deal(5).unwrap();
+        assert_eq!(dealt.reshuffle.unwrap().reason, ReshuffleReason::Exhausted);
+        assert_eq!(shoe.reshuffle().number, 2);
+        assert_eq!(shoe.remaining(), 104);
+    }
+
+    #[test]
+    fn every_hand_policy_deals_from_a_fresh_shoe() {
+        let mut shoe = Shoe::new(1, ReshufflePolicy::EveryHand, seeded_rng(197)).unwrap();
+        assert_eq!(shoe.deal(10).unwrap().reshuffle, None);
+        let reshuffle = shoe.deal(10).unwrap().reshuffle.unwrap();
+        assert_eq!(
+            (reshuffle.reason, reshuffle.dealt),
+            (ReshuffleReason::EveryHand, 10)
+        );
+    }
+
+    #[test]
+    fn invalid_shoes_are_rejected() {
+        assert_eq!(
+            Shoe::new(0, ReshufflePolicy::EveryHand, seeded_rng(0)).unwrap_err(),
+            ShoeError::Decks(0)
+        );
+        assert_eq!(
+            Shoe::new(1, ReshufflePolicy::Penetration(1.5), seeded_rng(0)).unwrap_err(),
+            ShoeError::Penetration(1.5)
+        );
+        let mut shoe = Shoe::new(1, ReshufflePolicy::OnDemand, seeded_rng(0)).unwrap();
+        assert_eq!(
+            shoe.deal(53).unwrap_err(),
+            ShoeError::HandTooLarge {
+                cards: 53,
+                size: 52
+            }
+        );
+    }
+}
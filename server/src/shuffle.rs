@@ -0,0 +1,181 @@
+//! A specified shuffle of the deck, which anyone can reproduce from its seed.
+//!
+//! Shuffles with [`rand`] depend on algorithms which may change between its versions. This one is
+//! pinned down, so an auditor can reconstruct any deal from its seed alone, in any language, and
+//! only the seed needs to be logged. Every seeded deal of the server uses it: `/draw`,
+//! `/replay/:seed`, the hand of the hour, `/variants/:variant/deal`, `/deal/bulk`, the quiz and the
+//! trainer. Only the demo deck source, which is not fair, does not. Version [`SHUFFLE_VERSION`] 1
+//! is:
+//!
+//! 1. The key is 32 bytes: the seed as 8 little-endian bytes, followed by 24 zero bytes.
+//! 2. The generator is ChaCha with 8 rounds, the key, a nonce of zero and a block counter starting
+//!    at zero. Its keystream is read as 32-bit little-endian words, in order.
+//! 3. The deck starts in the order of [`Card::index`], from 0 to 51. A smaller deck, like the
+//!    short deck, starts with its cards in the same order.
+//! 4. For each position `i` from the last down to 1, a position `j` from 0 to `i` is drawn, and the
+//!    cards at `i` and `j` are swapped. This is the Fisher–Yates shuffle, as given by Durstenfeld.
+//! 5. To draw `j`, with `n = i + 1`, words `x` are read until one is less than
+//!    `2^32 - (2^32 mod n)`, and `j = x mod n`. Rejecting the rest keeps `j` exactly uniform.
+//! 6. Cards are dealt from position 0, five cards to a hand, or as many as a variant deals.
+//! 7. A sequence of single hands, like `/replay/:seed?count=3`, draws its hand `k`, from 0, as the
+//!    first hand of the seed plus `k`, wrapping around at `2^64`.
+//!
+//! The order of the full deck for any seed is served by `/shuffle/verify`.
+
+use crate::{card::Card, deck::remaining_cards, hand::Hand};
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// The version of the shuffle, which changes if the shuffle of a seed ever changes.
+pub const SHUFFLE_VERSION: u32 = 1;
+
+/// The full deck shuffled with the seed, from the first card dealt.
+pub fn shuffle(seed: u64) -> Vec<Card> {
+    shuffle_deck(remaining_cards(&[]), seed)
+}
+
+/// The cards of a deck, like the short deck, shuffled with the seed, from the first card dealt.
+pub fn shuffle_deck(mut cards: Vec<Card>, seed: u64) -> Vec<Card> {
+    let mut key = [0; 32];
+    key[..8].copy_from_slice(&seed.to_le_bytes());
+    let mut rng = ChaCha8Rng::from_seed(key);
+    cards.sort_unstable_by_key(|card| card.index());
+    for i in (1..cards.len()).rev() {
+        let n = u32::try_from(i + 1).expect("a deck has at most 52 cards");
+        cards.swap(i, below(&mut rng, n) as usize);
+    }
+    cards
+}
+
+/// The first [`Hand`] of the deck shuffled with the seed.
+pub fn draw(seed: u64) -> Hand {
+    deal(seed, 1).remove(0)
+}
+
+/// The `k`th hand, from 0, of the sequence of single hands drawn with the seed.
+pub fn draw_nth(seed: u64, k: u64) -> Hand {
+    draw(seed.wrapping_add(k))
+}
+
+/// Deal `count` [`Hand`]s from the deck shuffled with the seed.
+///
+/// # Panics
+///
+/// If `count` is more than 10, as the deck then runs out of cards.
+pub fn deal(seed: u64, count: usize) -> Vec<Hand> {
+    let cards = shuffle(seed);
+    assert!(
+        count <= cards.len() / 5,
+        "cannot deal {count} hands from one deck"
+    );
+    cards
+        .chunks(5)
+        .take(count)
+        .map(|hand| Hand::try_from(hand).expect("we gave five unique cards"))
+        .collect()
+}
+
+/// A number from 0 to `n - 1`, drawn without bias by rejecting words above the largest multiple
+/// of `n`.
+fn below(rng: &mut impl RngCore, n: u32) -> u32 {
+    let words = 1_u64 << 32;
+    let limit = words - words % u64::from(n);
+    loop {
+        let x = rng.next_u32();
+        if u64::from(x) < limit {
+            return x % n;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(cards: &[Card]) -> String {
+        cards
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn shuffles_are_pinned() {
+        // If these change, so must SHUFFLE_VERSION.
+        assert_eq!(cards(&shuffle(0)[..5]), "3s,9r,5k,qh,qk");
+        assert_eq!(cards(&shuffle(231)[..5]), "4r,2h,2k,6k,qh");
+    }
+
+    #[test]
+    fn shuffles_are_permutations() {
+        for seed in 0..100 {
+            let mut shuffled = shuffle(seed);
+            shuffled.sort_unstable_by_key(|card| card.index());
+            let indices: Vec<u8> = shuffled.iter().map(|card| card.index()).collect();
+            assert_eq!(indices, (0..52).collect::<Vec<_>>());
+        }
+        assert_ne!(shuffle(1), shuffle(2));
+    }
+
+    #[test]
+    fn smaller_decks_keep_their_cards() {
+        let short = crate::rules::Rules {
+            short_deck: true,
+            ..crate::rules::Rules::default()
+        }
+        .deck();
+        let mut shuffled = shuffle_deck(short.clone(), 231);
+        assert_eq!(shuffled.len(), 36);
+        // The order the cards are given in does not matter.
+        let mut reversed = short.clone();
+        reversed.reverse();
+        assert_eq!(shuffle_deck(reversed, 231), shuffled);
+        shuffled.sort_unstable_by_key(|card| card.index());
+        assert_eq!(shuffled, short);
+    }
+
+    #[test]
+    fn sequences_draw_from_consecutive_seeds() {
+        assert_eq!(draw_nth(231, 0), draw(231));
+        assert_eq!(draw_nth(231, 2), draw(233));
+        assert_eq!(draw_nth(u64::MAX, 1), draw(0));
+    }
+
+    #[test]
+    fn hands_are_dealt_from_the_top() {
+        let shuffled = shuffle(231);
+        let hands = deal(231, 2);
+        assert_eq!(hands[0], Hand::try_from(&shuffled[..5]).unwrap());
+        assert_eq!(hands[1], Hand::try_from(&shuffled[5..10]).unwrap());
+    }
+
+    #[test]
+    fn draws_above_the_largest_multiple_are_rejected() {
+        use rand_chacha::rand_core::impls;
+
+        /// Gives the words in order.
+        struct Words(Vec<u32>);
+
+        impl RngCore for Words {
+            fn next_u32(&mut self) -> u32 {
+                self.0.remove(0)
+            }
+            fn next_u64(&mut self) -> u64 {
+                impls::next_u64_via_u32(self)
+            }
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                impls::fill_bytes_via_next(self, dest);
+            }
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+                self.fill_bytes(dest);
+                Ok(())
+            }
+        }
+
+        // 2^32 mod 3 is 1, so only the largest word is rejected.
+        assert_eq!(below(&mut Words(vec![u32::MAX, u32::MAX - 1]), 3), 2);
+        assert_eq!(below(&mut Words(vec![7]), 3), 1);
+        assert_eq!(below(&mut Words(vec![u32::MAX]), 1), 0);
+    }
+}
@@ -0,0 +1,133 @@
+//! Ed25519 signatures of analyze and draw responses, so services which relay results can prove
+//! they come from this server unmodified.
+//!
+//! With a [`SigningKey`] in the [`crate::config::RouterConfig`], the body of each `/draw` and
+//! `/analyze` response is signed, and the signature is sent base64-encoded in the
+//! [`SIGNATURE_HEADER`] header. The public key is served at `/.well-known/signing-key`. A
+//! signature covers the exact bytes of the body, so the body must be verified before it is parsed.
+
+use axum::{
+    body::{self, Full},
+    extract::State,
+    http::{HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use std::{fmt, str::FromStr, sync::Arc};
+
+/// The response header holding the signature of the body.
+pub const SIGNATURE_HEADER: &str = "x-signature-ed25519";
+
+/// An Ed25519 key pair, made from a 32-byte seed.
+#[derive(Clone)]
+pub struct SigningKey {
+    pair: Arc<Ed25519KeyPair>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SigningKeyError {
+    #[error("the key is not base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("the key is {0} bytes, but must be a 32-byte seed")]
+    Length(usize),
+}
+
+impl SigningKey {
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let pair = Ed25519KeyPair::from_seed_unchecked(seed).expect("every 32-byte seed is a key");
+        Self {
+            pair: Arc::new(pair),
+        }
+    }
+
+    /// The public key, base64-encoded.
+    pub fn public_key(&self) -> String {
+        STANDARD.encode(self.pair.public_key())
+    }
+
+    /// The signature of the `message`, base64-encoded.
+    pub fn sign(&self, message: &[u8]) -> String {
+        STANDARD.encode(self.pair.sign(message))
+    }
+}
+
+impl FromStr for SigningKey {
+    type Err = SigningKeyError;
+
+    /// Parse the base64-encoded 32-byte seed of the key.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let seed = STANDARD.decode(s.trim())?;
+        let seed: [u8; 32] = seed
+            .as_slice()
+            .try_into()
+            .map_err(|_| SigningKeyError::Length(seed.len()))?;
+        Ok(Self::from_seed(&seed))
+    }
+}
+
+impl fmt::Debug for SigningKey {
+    /// Shows the public key only, so the seed is never logged.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("public_key", &self.public_key())
+            .finish()
+    }
+}
+
+impl PartialEq for SigningKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.pair.public_key().as_ref() == other.pair.public_key().as_ref()
+    }
+}
+
+/// Middleware which signs the body of the response with the key.
+pub async fn sign_response<B>(
+    State(key): State<SigningKey>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let (mut parts, body) = next.run(request).await.into_parts();
+    let Ok(bytes) = hyper::body::to_bytes(body).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let signature = HeaderValue::from_str(&key.sign(&bytes)).expect("base64 is a valid header");
+    parts.headers.insert(SIGNATURE_HEADER, signature);
+    Response::from_parts(parts, body::boxed(Full::from(bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, ED25519};
+
+    fn verify(key: &SigningKey, message: &[u8], signature: &str) -> bool {
+        let public_key = STANDARD.decode(key.public_key()).unwrap();
+        let signature = STANDARD.decode(signature).unwrap();
+        UnparsedPublicKey::new(&ED25519, public_key)
+            .verify(message, &signature)
+            .is_ok()
+    }
+
+    #[test]
+    fn signatures_verify_with_the_public_key() {
+        let key = SigningKey::from_seed(&[7; 32]);
+        let signature = key.sign(br#""FullHouse""#);
+        assert!(verify(&key, br#""FullHouse""#, &signature));
+        assert!(!verify(&key, br#""Flush""#, &signature));
+    }
+
+    #[test]
+    fn keys_are_parsed_from_base64_seeds() {
+        let seed = STANDARD.encode([7; 32]);
+        let key: SigningKey = seed.parse().unwrap();
+        assert_eq!(key, SigningKey::from_seed(&[7; 32]));
+        assert!(!format!("{key:?}").contains(&seed));
+        assert!(matches!(
+            STANDARD.encode([7; 16]).parse::<SigningKey>(),
+            Err(SigningKeyError::Length(16))
+        ));
+        assert!("not base64!".parse::<SigningKey>().is_err());
+    }
+}
@@ -0,0 +1,67 @@
+//! Simulate many draws of a [`Hand`] to see how often each [`HandCategory`] occurs.
+
+use crate::{classify::classify, deck::draw_hand_with, hand::HandCategory};
+use rand::Rng;
+use std::collections::BTreeMap;
+
+/// Draw `iterations` hands and count how many of them fall into each [`HandCategory`].
+///
+/// Categories which never occurred are not included.
+pub fn category_frequencies<R: Rng + ?Sized>(
+    iterations: u64,
+    rng: &mut R,
+) -> BTreeMap<HandCategory, u64> {
+    let mut frequencies = BTreeMap::new();
+    for _ in 0..iterations {
+        *frequencies
+            .entry(classify(&draw_hand_with(rng)))
+            .or_insert(0) += 1;
+    }
+    frequencies
+}
+
+/// The largest half-width of the 95 % confidence intervals of the share of each category, from
+/// the `frequencies` of `iterations` hands.
+///
+/// The intervals are the normal approximation: a share `p` of `n` hands is `p ± 1.96 √(p (1 - p) / n)`.
+pub fn half_width(frequencies: &BTreeMap<HandCategory, u64>, iterations: u64) -> f64 {
+    #[allow(clippy::cast_precision_loss)]
+    let n = iterations as f64;
+    frequencies
+        .values()
+        .map(|&count| {
+            #[allow(clippy::cast_precision_loss)]
+            let p = count as f64 / n;
+            1.96 * (p * (1.0 - p) / n).sqrt()
+        })
+        .fold(0.0, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deck::seeded_rng;
+
+    #[test]
+    fn frequencies_sum_to_iterations() {
+        let frequencies = category_frequencies(1000, &mut seeded_rng(0));
+        assert_eq!(frequencies.values().sum::<u64>(), 1000);
+    }
+
+    #[test]
+    fn half_width_shrinks_with_iterations() {
+        let mut rng = seeded_rng(193);
+        let few = half_width(&category_frequencies(1_000, &mut rng), 1_000);
+        let many = half_width(&category_frequencies(100_000, &mut rng), 100_000);
+        // High card, about half of the hands, has the widest interval: 1.96 √(0.25 / n).
+        assert!((few - 0.031).abs() < 0.001, "{few}");
+        assert!((many - 0.0031).abs() < 0.0001, "{many}");
+    }
+
+    #[test]
+    fn high_card_is_most_common() {
+        let frequencies = category_frequencies(1000, &mut seeded_rng(0));
+        let most_common = frequencies.iter().max_by_key(|(_, &count)| count).unwrap();
+        assert_eq!(*most_common.0, HandCategory::HighCard);
+    }
+}
@@ -0,0 +1,192 @@
+//! A [`TestServer`] running the [router](crate::router) in-process, for integration tests of
+//! services which use the HTTP API.
+//!
+//! Requests are served by calling the router directly, without binding a port. Besides plain
+//! [`TestServer::get`] and [`TestServer::post_json`], there are typed helpers for the endpoints,
+//! which deserialize their responses.
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! use server::test_util::TestServer;
+//!
+//! let server = TestServer::new();
+//! let draw = server.draw().await;
+//! assert_eq!(server.analyze(&draw.hand.to_string()).await, Ok(draw.category));
+//! # }
+//! ```
+
+use crate::{
+    config::RouterConfig,
+    environment::Environment,
+    hand::{Hand, HandCategory},
+    router::{create, create_with_environment},
+};
+use axum::{
+    body::{Body, Bytes},
+    http::{header, Request, StatusCode},
+    Router,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tower::ServiceExt;
+
+/// The router, serving requests in-process.
+#[derive(Clone, Debug)]
+pub struct TestServer {
+    router: Router<(), Body>,
+}
+
+/// The status and body of a response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestResponse {
+    pub status: StatusCode,
+    pub body: Bytes,
+}
+
+impl TestResponse {
+    /// The body, deserialized from JSON.
+    ///
+    /// # Panics
+    ///
+    /// If the body is not JSON of a `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).expect("the body is JSON of the expected type")
+    }
+
+    /// The body as text.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// The response of `/draw`.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Draw {
+    pub hand: Hand,
+    pub category: HandCategory,
+    pub category_name: String,
+    pub category_description: String,
+    pub seed: u64,
+}
+
+impl Default for TestServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestServer {
+    /// A server with the default configuration, see [`create`].
+    pub fn new() -> Self {
+        Self { router: create() }
+    }
+
+    /// A server with the configuration and environment, see [`create_with_environment`]. With a
+    /// seeded [`Randomness`](crate::environment::Randomness), the draws are deterministic.
+    pub fn with_environment(config: &RouterConfig, environment: Environment) -> Self {
+        Self {
+            router: create_with_environment(config, environment),
+        }
+    }
+
+    /// Serve the request.
+    ///
+    /// # Panics
+    ///
+    /// If reading the response body fails.
+    pub async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self
+            .router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("the router never fails");
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .expect("the body can be read");
+        TestResponse { status, body }
+    }
+
+    /// `GET` the `uri`, like `/replay/42?count=3`.
+    pub async fn get(&self, uri: &str) -> TestResponse {
+        self.send(
+            Request::get(uri)
+                .body(Body::empty())
+                .expect("the request is valid"),
+        )
+        .await
+    }
+
+    /// `POST` the `body` as JSON to the `uri`.
+    pub async fn post_json(&self, uri: &str, body: &impl Serialize) -> TestResponse {
+        let body = serde_json::to_vec(body).expect("the body can be serialized");
+        self.send(
+            Request::post(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .expect("the request is valid"),
+        )
+        .await
+    }
+
+    /// Draw a hand with `/draw`.
+    ///
+    /// # Panics
+    ///
+    /// If `/draw` does not answer with a draw.
+    pub async fn draw(&self) -> Draw {
+        let response = self.get("/draw").await;
+        assert_eq!(response.status, StatusCode::OK, "{}", response.text());
+        response.json()
+    }
+
+    /// Analyze the `cards`, like `tr,jr,qr,kr,1r`, with `/analyze/:cards`. Returns the response
+    /// if the cards are rejected.
+    pub async fn analyze(&self, cards: &str) -> Result<HandCategory, TestResponse> {
+        let response = self.get(&format!("/analyze/{cards}")).await;
+        if response.status == StatusCode::OK {
+            Ok(response.json())
+        } else {
+            Err(response)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::SeededRandomness;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn drawn_hand_is_analyzed_the_same() {
+        let server = TestServer::new();
+        let draw = server.draw().await;
+        assert_eq!(
+            server.analyze(&draw.hand.to_string()).await,
+            Ok(draw.category)
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_cards_give_the_response() {
+        let response = TestServer::new().analyze("1s,ks").await.unwrap_err();
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert!(response.text().contains("must be 5"));
+    }
+
+    #[tokio::test]
+    async fn seeded_servers_draw_the_same() {
+        let server = || {
+            TestServer::with_environment(
+                &RouterConfig::default(),
+                Environment {
+                    randomness: Arc::new(SeededRandomness::new(169)),
+                    ..Environment::default()
+                },
+            )
+        };
+        assert_eq!(server().draw().await, server().draw().await);
+    }
+}
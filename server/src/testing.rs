@@ -0,0 +1,162 @@
+//! [`proptest`] strategies for generating cards, hands, decks and boards, for property testing
+//! code which uses this crate.
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use server::{classify::classify, hand::HandCategory, testing::hand_of};
+//!
+//! proptest!(|(hand in hand_of(HandCategory::Flush))| {
+//!     prop_assert_eq!(classify(&hand), HandCategory::Flush);
+//! });
+//! ```
+
+use crate::{
+    card::{Card, Rank, Suit},
+    classify::classify,
+    hand::{Hand, HandCategory},
+};
+use proptest::{
+    collection::vec,
+    prelude::*,
+    sample::{select, subsequence},
+};
+
+/// All 52 cards, in the order of [`Card::index`].
+fn all_cards() -> Vec<Card> {
+    (0..52)
+        .map(|index| Card::from_index(index).expect("index is below 52"))
+        .collect()
+}
+
+/// Any card.
+pub fn card() -> impl Strategy<Value = Card> {
+    (0..52u8).prop_map(|index| Card::from_index(index).expect("index is below 52"))
+}
+
+/// Any hand of five unique cards.
+pub fn hand() -> impl Strategy<Value = Hand> {
+    subsequence(all_cards(), 5)
+        .prop_map(|cards| Hand::try_from(cards.as_slice()).expect("five unique cards are given"))
+}
+
+/// A shuffled deck of all 52 cards.
+pub fn deck() -> impl Strategy<Value = Vec<Card>> {
+    Just(all_cards()).prop_shuffle()
+}
+
+/// Two hole cards and a Texas hold'em board of three to five cards, sharing no card.
+pub fn hole_and_board() -> impl Strategy<Value = (Vec<Card>, Vec<Card>)> {
+    subsequence(all_cards(), 5..=7)
+        .prop_shuffle()
+        .prop_map(|mut cards| {
+            let board = cards.split_off(2);
+            (cards, board)
+        })
+}
+
+/// Any hand of the `category`.
+pub fn hand_of(category: HandCategory) -> impl Strategy<Value = Hand> {
+    use HandCategory::*;
+
+    let cards = match category {
+        StraightFlush => (straight_ranks(), select(Suit::ALL.to_vec()))
+            .prop_map(|(ranks, suit)| {
+                ranks
+                    .into_iter()
+                    .map(|rank| Card::new(rank, suit))
+                    .collect()
+            })
+            .boxed(),
+        Straight => (straight_ranks(), vec(select(Suit::ALL.to_vec()), 5))
+            .prop_map(|(ranks, suits)| {
+                ranks
+                    .into_iter()
+                    .zip(suits)
+                    .map(|(rank, suit)| Card::new(rank, suit))
+                    .collect()
+            })
+            .boxed(),
+        Flush => (
+            subsequence(Rank::ALL.to_vec(), 5),
+            select(Suit::ALL.to_vec()),
+        )
+            .prop_map(|(ranks, suit)| {
+                ranks
+                    .into_iter()
+                    .map(|rank| Card::new(rank, suit))
+                    .collect()
+            })
+            .boxed(),
+        FourOfAKind => groups(&[4, 1]),
+        FullHouse => groups(&[3, 2]),
+        ThreeOfAKind => groups(&[3, 1, 1]),
+        TwoPair => groups(&[2, 2, 1]),
+        OnePair => groups(&[2, 1, 1, 1]),
+        HighCard => groups(&[1, 1, 1, 1, 1]),
+    };
+    // The generators above can make a stronger hand by chance, like a flush among high cards.
+    cards
+        .prop_map(|cards| Hand::try_from(cards.as_slice()).expect("five unique cards are given"))
+        .prop_filter("hand is of another category", move |hand| {
+            classify(hand) == category
+        })
+}
+
+/// The five ranks of any straight, with the Ace playing either low or high.
+fn straight_ranks() -> impl Strategy<Value = Vec<Rank>> {
+    // The top rank counted with the Ace as 14, from the Five to the Ace.
+    (5..=14usize).prop_map(|top| {
+        (top - 4..=top)
+            .map(|rank| Rank::ALL[(rank - 1) % 13])
+            .collect()
+    })
+}
+
+/// Cards in groups of the same rank, of the given sizes, with a different rank for each group.
+fn groups(sizes: &'static [usize]) -> BoxedStrategy<Vec<Card>> {
+    let suits: Vec<_> = sizes
+        .iter()
+        .map(|&size| subsequence(Suit::ALL.to_vec(), size))
+        .collect();
+    (
+        subsequence(Rank::ALL.to_vec(), sizes.len()).prop_shuffle(),
+        suits,
+    )
+        .prop_map(|(ranks, suits)| {
+            ranks
+                .into_iter()
+                .zip(suits)
+                .flat_map(|(rank, suits)| suits.into_iter().map(move |suit| Card::new(rank, suit)))
+                .collect()
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn hands_are_of_the_category(
+            (category, hand) in select(HandCategory::ALL.to_vec())
+                .prop_flat_map(|category| (Just(category), hand_of(category)))
+        ) {
+            prop_assert_eq!(classify(&hand), category);
+        }
+
+        #[test]
+        fn hole_and_board_share_no_card((hole, board) in hole_and_board()) {
+            prop_assert_eq!(hole.len(), 2);
+            prop_assert!((3..=5).contains(&board.len()));
+            prop_assert!(hole.iter().all(|card| !board.contains(card)));
+        }
+
+        #[test]
+        fn deck_has_every_card_once(mut deck in deck()) {
+            deck.sort();
+            deck.dedup();
+            prop_assert_eq!(deck.len(), 52);
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! Poker [`Variant`]s addressable by name, like `holdem` in `/variants/holdem/analyze/:cards`.
+//!
+//! A variant knows how the cards of one player are dealt, and how they are ranked. The
+//! [`Variants`] registry maps names to variants, and comes with the built-in ones:
+//!
+//! | Name         | Cards of a player                                   | Plays                     |
+//! |--------------|-----------------------------------------------------|---------------------------|
+//! | `holdem`     | 2 hole cards, then a board of 3 to 5 cards          | the best five             |
+//! | `omaha`      | 4 hole cards, then a board of 3 to 5 cards          | 2 hole and 3 board cards  |
+//! | `short-deck` | like `holdem`, from a deck without Twos to Fives    | see [`Rules::short_deck`] |
+//! | `lowball-27` | 5 cards                                             | Deuce-to-Seven low        |
+//! | `three-card` | 3 cards                                             | Three Card Poker ranking  |
+//! | `razz`       | 7 cards                                             | the best Ace-to-Five low  |
+//! | `stud-hi-lo` | 7 cards                                             | the best high and, if     |
+//! |              |                                                     | Eight or better, low five |
+//!
+//! Other crates add their own variants with [`Variants::register`], and give the registry to the
+//! router in [`RouterConfig`](crate::config::RouterConfig).
+
+use crate::{
+    card::Card,
+    compare::Strength,
+    hand::{Hand, HandCategory},
+    low::{best_low, hi_lo, LowStrength},
+    rules::{Rules, RulesError, Straights},
+    shuffle::shuffle_deck,
+};
+use itertools::Itertools;
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::{self, Debug},
+    ops::RangeInclusive,
+    sync::Arc,
+};
+
+/// A way to deal and rank poker hands.
+pub trait Variant: Send + Sync {
+    /// Deal the cards of one player from the deck shuffled with the seed, including any shared
+    /// cards, in the order [`Variant::analyze`] takes them. See [`crate::shuffle`].
+    fn deal(&self, seed: u64) -> Vec<Card>;
+
+    /// Rank the cards of one player.
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError>;
+}
+
+/// How the cards of a player rank in a [`Variant`].
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    /// The cards which play, like the best five of seven.
+    pub hand: Vec<Card>,
+    pub category: HandCategory,
+    /// Numeric ranks (Ace counted as 14, or as 1 in Ace-to-Five lows) breaking ties within the
+    /// category, most significant first.
+    pub tie_breakers: Vec<u8>,
+    /// Whether the lowest hand wins, like in lowball.
+    pub low_wins: bool,
+    /// The qualifying low hand, which splits the pot with this one, in Hi/Lo variants.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub low: Option<Box<Analysis>>,
+}
+
+impl Analysis {
+    fn high(hand: &Hand, strength: Strength) -> Self {
+        Self {
+            hand: hand.cards().copied().collect(),
+            category: strength.category,
+            tie_breakers: strength.tie_breakers,
+            low_wins: false,
+            low: None,
+        }
+    }
+
+    /// An Ace-to-Five low, where the best low hand has the greatest [`LowStrength`].
+    fn low(hand: &Hand, strength: LowStrength) -> Self {
+        Self {
+            hand: hand.cards().copied().collect(),
+            category: strength.category,
+            tie_breakers: strength.ranks,
+            low_wins: true,
+            low: None,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum VariantError {
+    #[error("number of cards ({given}) must be from {} to {}", expected.start(), expected.end())]
+    Cards {
+        given: usize,
+        expected: RangeInclusive<usize>,
+    },
+    #[error("{0} is given more than once")]
+    DuplicateCard(Card),
+    #[error(transparent)]
+    Rules(#[from] RulesError),
+}
+
+/// Fail unless there are `expected` cards, each given once.
+fn check_cards(cards: &[Card], expected: RangeInclusive<usize>) -> Result<(), VariantError> {
+    if !expected.contains(&cards.len()) {
+        return Err(VariantError::Cards {
+            given: cards.len(),
+            expected,
+        });
+    }
+    for (i, card) in cards.iter().enumerate() {
+        if cards[..i].contains(card) {
+            return Err(VariantError::DuplicateCard(*card));
+        }
+    }
+    Ok(())
+}
+
+/// The strongest hand under the `rules` among the `hands`, with its strength.
+fn best_of(
+    rules: &Rules,
+    hands: impl Iterator<Item = Vec<Card>>,
+) -> Result<(Hand, Strength), VariantError> {
+    let mut best: Option<(Hand, Strength)> = None;
+    for cards in hands {
+        let hand = Hand::try_from(cards.as_slice()).expect("the cards are unique");
+        let strength = rules.strength(&hand)?;
+        if best
+            .as_ref()
+            .is_none_or(|(_, best)| rules.compare(&strength, best).is_gt())
+        {
+            best = Some((hand, strength));
+        }
+    }
+    Ok(best.expect("there is at least one hand"))
+}
+
+/// The first `count` cards of the `deck` shuffled with the seed.
+fn deal_from(deck: Vec<Card>, seed: u64, count: usize) -> Vec<Card> {
+    let mut cards = shuffle_deck(deck, seed);
+    cards.truncate(count);
+    cards
+}
+
+/// Texas hold'em, or short-deck hold'em with the short-deck [`Rules`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Holdem {
+    pub rules: Rules,
+}
+
+impl Variant for Holdem {
+    fn deal(&self, seed: u64) -> Vec<Card> {
+        deal_from(self.rules.deck(), seed, 7)
+    }
+
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError> {
+        check_cards(cards, 5..=7)?;
+        let (hand, strength) = best_of(&self.rules, cards.iter().copied().combinations(5))?;
+        Ok(Analysis::high(&hand, strength))
+    }
+}
+
+/// Omaha hold'em, where exactly two of the four hole cards play with three of the board.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Omaha;
+
+impl Variant for Omaha {
+    fn deal(&self, seed: u64) -> Vec<Card> {
+        deal_from(Rules::default().deck(), seed, 9)
+    }
+
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError> {
+        check_cards(cards, 7..=9)?;
+        let (hole, board) = cards.split_at(4);
+        let hands = hole
+            .iter()
+            .copied()
+            .combinations(2)
+            .cartesian_product(board.iter().copied().combinations(3).collect_vec())
+            .map(|(hole, board)| [hole, board].concat());
+        let (hand, strength) = best_of(&Rules::default(), hands)?;
+        Ok(Analysis::high(&hand, strength))
+    }
+}
+
+/// Deuce-to-Seven lowball, where the lowest hand wins, the Ace is always high, and straights and
+/// flushes count against the hand.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Lowball27;
+
+impl Variant for Lowball27 {
+    fn deal(&self, seed: u64) -> Vec<Card> {
+        deal_from(Rules::default().deck(), seed, 5)
+    }
+
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError> {
+        check_cards(cards, 5..=5)?;
+        let rules = Rules {
+            straights: Straights::AceHigh,
+            ..Rules::default()
+        };
+        let hand = Hand::try_from(cards).expect("the cards are unique");
+        let strength = rules.strength(&hand)?;
+        Ok(Analysis {
+            low_wins: true,
+            ..Analysis::high(&hand, strength)
+        })
+    }
+}
+
+/// Three Card Poker, where a straight beats a flush, and there are no two pairs, full houses or
+/// four of a kinds.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ThreeCard;
+
+impl Variant for ThreeCard {
+    fn deal(&self, seed: u64) -> Vec<Card> {
+        deal_from(Rules::default().deck(), seed, 3)
+    }
+
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError> {
+        use HandCategory::{Flush, HighCard, OnePair, Straight, StraightFlush, ThreeOfAKind};
+
+        check_cards(cards, 3..=3)?;
+        let mut ranks = cards
+            .iter()
+            .map(|card| card.rank.numeric_ace_high())
+            .sorted_by_key(|&rank| Reverse(rank))
+            .collect_vec();
+        let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+        // The Ace also plays low, in A-2-3.
+        if ranks == [14, 3, 2] {
+            ranks = vec![3, 2, 1];
+        }
+        let [a, b, c] = [ranks[0], ranks[1], ranks[2]];
+        let straight = a - c == 2 && a != b && b != c;
+        let (category, tie_breakers) = match (straight, flush) {
+            (true, true) => (StraightFlush, vec![a]),
+            _ if a == c => (ThreeOfAKind, vec![a]),
+            (true, false) => (Straight, vec![a]),
+            (false, true) => (Flush, ranks),
+            _ if a == b => (OnePair, vec![a, c]),
+            _ if b == c => (OnePair, vec![b, a]),
+            _ => (HighCard, ranks),
+        };
+        Ok(Analysis {
+            hand: cards.to_vec(),
+            category,
+            tie_breakers,
+            low_wins: false,
+            low: None,
+        })
+    }
+}
+
+/// Razz, where the best Ace-to-Five low of a player's seven stud cards wins, see [`best_low`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Razz;
+
+impl Variant for Razz {
+    fn deal(&self, seed: u64) -> Vec<Card> {
+        deal_from(Rules::default().deck(), seed, 7)
+    }
+
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError> {
+        check_cards(cards, 5..=7)?;
+        let (hand, strength) = best_low(cards).expect("there are five unique cards");
+        Ok(Analysis::low(&hand, strength))
+    }
+}
+
+/// Seven Card Stud Hi/Lo, where the best high hand splits the pot with the best Ace-to-Five low of
+/// Eight or better, if any, see [`hi_lo`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct StudHiLo;
+
+impl Variant for StudHiLo {
+    fn deal(&self, seed: u64) -> Vec<Card> {
+        deal_from(Rules::default().deck(), seed, 7)
+    }
+
+    fn analyze(&self, cards: &[Card]) -> Result<Analysis, VariantError> {
+        check_cards(cards, 5..=7)?;
+        let hi_lo = hi_lo(cards).expect("there are five unique cards");
+        Ok(Analysis {
+            low: hi_lo
+                .low
+                .map(|(hand, strength)| Box::new(Analysis::low(&hand, strength))),
+            ..Analysis::high(&hi_lo.high, hi_lo.high_strength)
+        })
+    }
+}
+
+/// Variants by name.
+#[derive(Clone)]
+pub struct Variants(HashMap<String, Arc<dyn Variant>>);
+
+impl Default for Variants {
+    /// The built-in variants. See the [module](self).
+    fn default() -> Self {
+        let mut variants = Self::empty();
+        variants.register("holdem", Arc::new(Holdem::default()));
+        variants.register("omaha", Arc::new(Omaha));
+        variants.register(
+            "short-deck",
+            Arc::new(Holdem {
+                rules: Rules {
+                    short_deck: true,
+                    ..Rules::default()
+                },
+            }),
+        );
+        variants.register("lowball-27", Arc::new(Lowball27));
+        variants.register("three-card", Arc::new(ThreeCard));
+        variants.register("razz", Arc::new(Razz));
+        variants.register("stud-hi-lo", Arc::new(StudHiLo));
+        variants
+    }
+}
+
+impl Variants {
+    /// A registry without any variants.
+    pub fn empty() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Add the `variant` by `name`, returning the variant it replaces, if any.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        variant: Arc<dyn Variant>,
+    ) -> Option<Arc<dyn Variant>> {
+        self.0.insert(name.into(), variant)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Variant> {
+        self.0.get(name).map(|variant| &**variant)
+    }
+
+    /// The names of the variants, in alphabetical order.
+    pub fn names(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).sorted().collect()
+    }
+}
+
+impl Debug for Variants {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Variants").field(&self.names()).finish()
+    }
+}
+
+impl PartialEq for Variants {
+    /// Registries are equal when they have variants by the same names.
+    fn eq(&self, other: &Self) -> bool {
+        self.names() == other.names()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(variant: &str, cards: &str) -> Result<Analysis, VariantError> {
+        let cards: Vec<Card> = cards.split(',').map(|c| c.parse().unwrap()).collect();
+        Variants::default().get(variant).unwrap().analyze(&cards)
+    }
+
+    #[test]
+    fn dealt_cards_can_be_analyzed() {
+        let variants = Variants::default();
+        for name in variants.names() {
+            let variant = variants.get(name).unwrap();
+            for seed in 0..20 {
+                assert!(variant.analyze(&variant.deal(seed)).is_ok(), "{name}");
+            }
+        }
+    }
+
+    #[test]
+    fn holdem_plays_the_best_five() {
+        let analysis = analyze("holdem", "1s,ks,7s,2s,9s,3r,4k").unwrap();
+        assert_eq!(analysis.category, HandCategory::Flush);
+        assert_eq!(analysis.tie_breakers, [14, 13, 9, 7, 2]);
+    }
+
+    #[test]
+    fn omaha_plays_two_hole_cards() {
+        // Four spades in the hole, but only two of them play with the board.
+        let analysis = analyze("omaha", "1s,ks,7s,2s,9s,3r,4k").unwrap();
+        assert_eq!(analysis.category, HandCategory::HighCard);
+        let analysis = analyze("omaha", "1s,ks,7h,2h,9s,3s,4s,qh").unwrap();
+        assert_eq!(analysis.category, HandCategory::Flush);
+    }
+
+    #[test]
+    fn short_deck_rejects_low_cards() {
+        assert!(matches!(
+            analyze("short-deck", "1s,ks,7s,2s,9s"),
+            Err(VariantError::Rules(RulesError::NotInDeck(_)))
+        ));
+    }
+
+    #[test]
+    fn deuce_to_seven_counts_the_ace_high() {
+        let analysis = analyze("lowball-27", "1s,2h,3r,4k,5s").unwrap();
+        assert_eq!(analysis.category, HandCategory::HighCard);
+        assert_eq!(analysis.tie_breakers, [14, 5, 4, 3, 2]);
+        assert!(analysis.low_wins);
+    }
+
+    #[test]
+    fn three_card_straight_is_found() {
+        let analysis = analyze("three-card", "1s,2h,3r").unwrap();
+        assert_eq!(analysis.category, HandCategory::Straight);
+        assert_eq!(analysis.tie_breakers, [3]);
+        let analysis = analyze("three-card", "qs,qh,3r").unwrap();
+        assert_eq!(analysis.tie_breakers, [12, 3]);
+        assert_eq!(
+            analyze("three-card", "qs,qh"),
+            Err(VariantError::Cards {
+                given: 2,
+                expected: 3..=3
+            })
+        );
+    }
+
+    #[test]
+    fn razz_plays_the_best_low() {
+        // The Ace plays low, and the straight and the pair of Twos do not count.
+        let analysis = analyze("razz", "1s,2h,3r,4k,5s,2s,kh").unwrap();
+        assert_eq!(analysis.category, HandCategory::HighCard);
+        assert_eq!(analysis.tie_breakers, [5, 4, 3, 2, 1]);
+        assert!(analysis.low_wins);
+        let analysis = analyze("razz", "ks,kh,qs,qh,js").unwrap();
+        assert_eq!(analysis.category, HandCategory::TwoPair);
+    }
+
+    #[test]
+    fn stud_hi_lo_splits_on_a_qualifying_low() {
+        let analysis = analyze("stud-hi-lo", "1s,2s,3s,4s,8s,kh,kr").unwrap();
+        assert_eq!(analysis.category, HandCategory::Flush);
+        assert!(!analysis.low_wins);
+        let low = analysis.low.unwrap();
+        assert_eq!(low.tie_breakers, [8, 4, 3, 2, 1]);
+        assert!(low.low_wins);
+        // A Nine-high low does not qualify.
+        let analysis = analyze("stud-hi-lo", "1s,2h,3r,4k,9s,kh,kr").unwrap();
+        assert_eq!(analysis.category, HandCategory::OnePair);
+        assert!(analysis.low.is_none());
+    }
+
+    #[test]
+    fn custom_variants_can_be_registered() {
+        let mut variants = Variants::default();
+        assert!(variants
+            .register("holdem-again", Arc::new(Holdem::default()))
+            .is_none());
+        assert!(variants.get("holdem-again").is_some());
+        assert_ne!(variants, Variants::default());
+    }
+}